@@ -0,0 +1,127 @@
+use std::ffi::CStr;
+use std::sync::OnceLock;
+
+use c_str_macro::c_str;
+use crossbeam_channel::Sender;
+use fragile::Fragile;
+use reaper_medium::{
+    CommandId, HookCommand, OwnedGaccelRegister, Reaper, ReaperFunctionError, ReaperSession,
+    ToggleAction, ToggleActionResult,
+};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::lockout;
+use crate::osc_routes::LockoutRoute;
+use crate::settings;
+use crate::sync;
+use crate::OscRoute;
+
+/// Actions arpad adds to REAPER's action list, so its management can be bound to
+/// shortcuts and toolbar buttons
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Settings,
+    Status,
+    ResyncAll,
+    ToggleLockout,
+}
+
+impl Action {
+    const ALL: [Action; 4] = [
+        Action::Settings,
+        Action::Status,
+        Action::ResyncAll,
+        Action::ToggleLockout,
+    ];
+
+    fn command_name(&self) -> &'static CStr {
+        match self {
+            Action::Settings => c_str!("ARPAD_SETTINGS"),
+            Action::Status => c_str!("ARPAD_STATUS"),
+            Action::ResyncAll => c_str!("ARPAD_RESYNC_ALL"),
+            Action::ToggleLockout => c_str!("ARPAD_TOGGLE_LOCKOUT"),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Action::Settings => "arpad: Open settings...",
+            Action::Status => "arpad: Show status",
+            Action::ResyncAll => "arpad: Resync all clients",
+            Action::ToggleLockout => "arpad: Toggle lockout",
+        }
+    }
+
+    fn run(&self, reaper: &Reaper) {
+        match self {
+            Action::Settings => settings::edit(reaper),
+            Action::Status => settings::show_status(reaper),
+            Action::ResyncAll => sync::request_all(),
+            Action::ToggleLockout => {
+                let locked = !lockout::is_locked();
+                lockout::set_locked(locked);
+                if let Some(sender) = SENDER.get() {
+                    let msg = LockoutRoute::build_message(locked, reaper);
+                    let _ = sender.send(OscPacket::Message(msg).into());
+                }
+            }
+        }
+    }
+}
+
+static REAPER: OnceLock<Fragile<Reaper>> = OnceLock::new();
+static SENDER: OnceLock<Sender<Feedback>> = OnceLock::new();
+// Command ids REAPER assigned to the actions
+static COMMANDS: OnceLock<Vec<(CommandId, Action)>> = OnceLock::new();
+
+fn action(command_id: CommandId) -> Option<Action> {
+    COMMANDS
+        .get()?
+        .iter()
+        .find(|(id, _)| *id == command_id)
+        .map(|(_, action)| *action)
+}
+
+pub fn register(
+    session: &mut ReaperSession,
+    osc_sender: Sender<Feedback>,
+) -> Result<(), ReaperFunctionError> {
+    let _ = REAPER.set(Fragile::new(session.reaper().clone()));
+    let _ = SENDER.set(osc_sender);
+    let mut commands = Vec::new();
+    for action in Action::ALL {
+        let command_id = session.plugin_register_add_command_id(action.command_name())?;
+        session.plugin_register_add_gaccel(OwnedGaccelRegister::without_key_binding(
+            command_id,
+            action.description(),
+        ))?;
+        commands.push((command_id, action));
+    }
+    let _ = COMMANDS.set(commands);
+    session.plugin_register_add_hook_command::<ArpadActions>()?;
+    session.plugin_register_add_toggle_action::<ArpadActions>()
+}
+
+struct ArpadActions;
+
+impl HookCommand for ArpadActions {
+    fn call(command_id: CommandId, _flag: i32) -> bool {
+        let (Some(reaper), Some(action)) = (REAPER.get(), action(command_id)) else {
+            return false;
+        };
+        action.run(reaper.get());
+        true
+    }
+}
+
+impl ToggleAction for ArpadActions {
+    // Toolbar buttons for the lockout light up while locked
+    fn call(command_id: CommandId) -> ToggleActionResult {
+        match action(command_id) {
+            Some(Action::ToggleLockout) if lockout::is_locked() => ToggleActionResult::On,
+            Some(Action::ToggleLockout) => ToggleActionResult::Off,
+            _ => ToggleActionResult::NotRelevant,
+        }
+    }
+}
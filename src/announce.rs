@@ -0,0 +1,57 @@
+use std::sync::Mutex;
+
+use reaper_medium::{MediaTrack, Reaper, SetPlayStateArgs};
+
+use crate::utils::get_track_idx;
+
+static LAST: Mutex<String> = Mutex::new(String::new());
+
+/// The most recent announcement
+pub fn last() -> String {
+    LAST.lock().unwrap().clone()
+}
+
+fn remember(sentence: String) -> String {
+    *LAST.lock().unwrap() = sentence.clone();
+    sentence
+}
+
+// The track's name, or "Track N" for unnamed tracks
+fn track_label(reaper: &Reaper, track: MediaTrack) -> String {
+    if reaper.get_master_track(reaper_medium::ProjectContext::CurrentProject) == track {
+        return "Master".to_string();
+    }
+    let name = unsafe {
+        reaper
+            .get_set_media_track_info_get_name(track, |name| name.to_str().to_string())
+            .unwrap_or_default()
+    };
+    if name.is_empty() {
+        format!("Track {}", get_track_idx(reaper, track))
+    } else {
+        name
+    }
+}
+
+pub fn track_flag(reaper: &Reaper, track: MediaTrack, on: &str, off: &str, is_on: bool) -> String {
+    remember(format!(
+        "{} {}",
+        track_label(reaper, track),
+        if is_on { on } else { off }
+    ))
+}
+
+pub fn play_state(args: SetPlayStateArgs) -> String {
+    let sentence = if args.is_recording && !args.is_paused {
+        "Recording started"
+    } else if args.is_recording {
+        "Recording paused"
+    } else if args.is_paused {
+        "Playback paused"
+    } else if args.is_playing {
+        "Playback started"
+    } else {
+        "Stopped"
+    };
+    remember(sentence.to_string())
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::sync::{LazyLock, Mutex};
+
+use c_str_macro::c_str;
+use reaper_medium::{
+    MediaTrack, NativeColor, NativeColorValue, ProjectContext::CurrentProject, Reaper,
+};
+
+use crate::utils::get_track_guid;
+
+// Rules are persisted across sessions in this ext-state, one "color<TAB>pattern" per line
+const EXT_STATE_SECTION: &CStr = c_str!("arpad");
+const EXT_STATE_RULES_KEY: &CStr = c_str!("autocolor");
+
+/// Colors tracks whose name matches `pattern`: case-insensitive, with `*` matching any
+/// run of characters
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorRule {
+    pub pattern: String,
+    pub color: i32,
+}
+
+impl ColorRule {
+    pub fn matches(&self, name: &str) -> bool {
+        glob_match(&self.pattern.to_lowercase(), &name.to_lowercase())
+    }
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+// Loaded from ext-state on first use
+static RULES: Mutex<Option<Vec<ColorRule>>> = Mutex::new(None);
+
+// Last name reported for each track GUID, so rules follow renames but not color changes
+static NAMES: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn load(reaper: &Reaper) -> Vec<ColorRule> {
+    let value = unsafe {
+        let ptr = reaper
+            .low()
+            .GetExtState(EXT_STATE_SECTION.as_ptr(), EXT_STATE_RULES_KEY.as_ptr());
+        if ptr.is_null() {
+            return Vec::new();
+        }
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    };
+    value
+        .lines()
+        .filter_map(|line| {
+            let (color, pattern) = line.split_once('\t')?;
+            Some(ColorRule {
+                pattern: pattern.to_string(),
+                color: color.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn save(reaper: &Reaper, rules: &[ColorRule]) {
+    let value: Vec<String> = rules
+        .iter()
+        .map(|r| format!("{}\t{}", r.color, r.pattern))
+        .collect();
+    let value = CString::new(value.join("\n")).unwrap_or_default();
+    unsafe {
+        reaper.low().SetExtState(
+            EXT_STATE_SECTION.as_ptr(),
+            EXT_STATE_RULES_KEY.as_ptr(),
+            value.as_ptr(),
+            true,
+        );
+    }
+}
+
+fn with_rules<R>(reaper: &Reaper, f: impl FnOnce(&mut Vec<ColorRule>) -> R) -> R {
+    let mut rules = RULES.lock().unwrap();
+    f(rules.get_or_insert_with(|| load(reaper)))
+}
+
+pub fn rules(reaper: &Reaper) -> Vec<ColorRule> {
+    with_rules(reaper, |rules| rules.clone())
+}
+
+/// Adds a rule, or changes the color of the rule with the same pattern. Earlier
+/// rules take precedence.
+pub fn set_rule(reaper: &Reaper, rule: ColorRule) {
+    with_rules(reaper, |rules| {
+        match rules.iter_mut().find(|r| r.pattern == rule.pattern) {
+            Some(existing) => existing.color = rule.color,
+            None => rules.push(rule),
+        }
+        save(reaper, rules);
+    })
+}
+
+/// Removes the rule with this pattern; returns false when there was none
+pub fn remove_rule(reaper: &Reaper, pattern: &str) -> bool {
+    with_rules(reaper, |rules| {
+        let len = rules.len();
+        rules.retain(|r| r.pattern != pattern);
+        save(reaper, rules);
+        rules.len() != len
+    })
+}
+
+pub fn clear(reaper: &Reaper) {
+    with_rules(reaper, |rules| {
+        rules.clear();
+        save(reaper, rules);
+    })
+}
+
+/// Colors a track by the first rule matching its name. Tracks matching no rule keep
+/// their color.
+pub fn apply(reaper: &Reaper, track: MediaTrack, name: &str) {
+    let Some(color) = with_rules(reaper, |rules| {
+        rules.iter().find(|r| r.matches(name)).map(|r| r.color)
+    }) else {
+        return;
+    };
+    unsafe {
+        let current = reaper.get_set_media_track_info_get_custom_color(track);
+        if current.is_used && current.color.to_raw() == color {
+            return;
+        }
+        reaper.get_set_media_track_info_set_custom_color(
+            track,
+            NativeColorValue {
+                color: NativeColor::new(color),
+                is_used: true,
+            },
+        );
+    }
+}
+
+/// Called whenever REAPER reports a track's name, which it also does when only the
+/// color changed. The rules apply when the name actually changes, or to a track seen
+/// for the first time without a color of its own, like a new one; a track recolored by
+/// hand or created with a color keeps it.
+pub fn track_named(reaper: &Reaper, track: MediaTrack, name: &str) {
+    let guid = get_track_guid(reaper, track);
+    let prev = NAMES.lock().unwrap().insert(guid, name.to_string());
+    match prev {
+        Some(prev) if prev == name => {}
+        Some(_) => apply(reaper, track, name),
+        None => {
+            let colored = unsafe {
+                reaper
+                    .get_set_media_track_info_get_custom_color(track)
+                    .is_used
+            };
+            if !colored {
+                apply(reaper, track, name);
+            }
+        }
+    }
+}
+
+/// Applies the rules to every track in the project
+pub fn apply_all(reaper: &Reaper) {
+    for i in 0..reaper.count_tracks(CurrentProject) {
+        let Some(track) = reaper.get_track(CurrentProject, i) else {
+            continue;
+        };
+        let name = unsafe {
+            reaper.get_set_media_track_info_get_name(track, |name| name.to_str().to_string())
+        };
+        if let Some(name) = name {
+            apply(reaper, track, &name);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{
+    MediaTrack, Reaper, ReaperVolumeValue, TrackAttributeKey, TrackSendAttributeKey,
+    TrackSendCategory, TrackSendDirection,
+};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{
+    BankOffsetRoute, BankSlotGuidArgs, BankSlotGuidRoute, BankSlotVolumeArgs, BankSlotVolumeRoute,
+    SpillTrackRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::surfaces;
+use crate::utils::{get_track_by_guid, get_track_guid, volume_to_mode_value};
+use crate::OscRoute;
+
+const DEFAULT_BANK_SIZE: u32 = 8;
+
+/// The window of tracks a banked surface currently shows, like the fader banks of a
+/// hardware controller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bank {
+    pub offset: u32,
+    pub size: u32,
+    /// Re-center the bank on the track selected in REAPER
+    pub follow_selection: bool,
+    /// GUID of the track whose sends are spilled onto the bank slots, if any
+    pub spill: Option<String>,
+}
+
+// Each surface profile has its own bank, keyed by the surface's namespace
+static BANKS: LazyLock<Mutex<HashMap<String, Bank>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn with_bank<R>(f: impl FnOnce(&mut Bank) -> R) -> R {
+    let namespace = surfaces::current_namespace();
+    let mut banks = BANKS.lock().unwrap();
+    let bank = banks.entry(namespace).or_insert_with_key(|namespace| Bank {
+        offset: 0,
+        size: surfaces::profile_for_namespace(namespace)
+            .and_then(|p| p.bank_size)
+            .unwrap_or(DEFAULT_BANK_SIZE),
+        follow_selection: false,
+        spill: None,
+    });
+    f(bank)
+}
+
+/// The bank of the surface currently being served
+pub fn bank() -> Bank {
+    with_bank(|bank| bank.clone())
+}
+
+pub fn set_offset(offset: u32) {
+    with_bank(|bank| bank.offset = offset);
+}
+
+pub fn set_size(size: u32) {
+    with_bank(|bank| bank.size = size.max(1));
+}
+
+pub fn set_follow_selection(follow: bool) {
+    with_bank(|bank| bank.follow_selection = follow);
+}
+
+pub fn set_spill(track_guid: Option<String>) {
+    with_bank(|bank| bank.spill = track_guid);
+}
+
+/// What a bank slot controls: a track normally, or one of the spilled track's sends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotTarget {
+    Track(MediaTrack),
+    Send { track: MediaTrack, send_index: u32 },
+}
+
+impl SlotTarget {
+    /// The track shown in the slot; for spilled sends this is the send destination
+    pub fn display_track(&self, reaper: &Reaper) -> Option<MediaTrack> {
+        match *self {
+            SlotTarget::Track(track) => Some(track),
+            SlotTarget::Send { track, send_index } => unsafe {
+                reaper
+                    .get_track_send_info_desttrack(track, TrackSendDirection::Send, send_index)
+                    .ok()
+            },
+        }
+    }
+
+    pub fn volume(&self, reaper: &Reaper) -> ReaperVolumeValue {
+        let volume = match *self {
+            SlotTarget::Track(track) => unsafe {
+                reaper.get_media_track_info_value(track, TrackAttributeKey::Vol)
+            },
+            SlotTarget::Send { track, send_index } => unsafe {
+                reaper.get_track_send_info_value(
+                    track,
+                    TrackSendCategory::Send,
+                    send_index,
+                    TrackSendAttributeKey::Vol,
+                )
+            },
+        };
+        ReaperVolumeValue::new_panic(volume)
+    }
+}
+
+/// Returns what a slot of the current surface's bank controls, if anything
+pub fn slot_target(reaper: &Reaper, slot: u32) -> Option<SlotTarget> {
+    slot_target_in(reaper, &bank(), slot)
+}
+
+fn slot_target_in(reaper: &Reaper, bank: &Bank, slot: u32) -> Option<SlotTarget> {
+    if slot >= bank.size {
+        return None;
+    }
+    match &bank.spill {
+        Some(guid) => {
+            let track = get_track_by_guid(reaper, guid).ok()?;
+            let send_count = unsafe { reaper.get_track_num_sends(track, TrackSendCategory::Send) };
+            (slot < send_count).then_some(SlotTarget::Send {
+                track,
+                send_index: slot,
+            })
+        }
+        None => reaper
+            .get_track(CurrentProject, bank.offset + slot)
+            .map(SlotTarget::Track),
+    }
+}
+
+/// Moves every bank following the selection so the track at `track_index` sits in
+/// the middle of the window, keeping the window inside the track list.
+pub fn center_on(track_index: u32, track_count: u32) {
+    for bank in BANKS.lock().unwrap().values_mut() {
+        if !bank.follow_selection {
+            continue;
+        }
+        let max_offset = track_count.saturating_sub(bank.size);
+        bank.offset = track_index.saturating_sub(bank.size / 2).min(max_offset);
+    }
+}
+
+#[derive(Default)]
+struct SentBank {
+    bank: Option<Bank>,
+    slots: Vec<String>,
+    volumes: Vec<Option<f64>>,
+}
+
+/// Sends the bank offset, spill state and the GUID of every slot whenever the bank
+/// moves or the tracks inside it change, and slot volumes whenever they change. Each
+/// surface's bank is sent only to that surface.
+pub struct BankPollSource {
+    reaper: Reaper,
+    sent: HashMap<String, SentBank>,
+}
+
+impl BankPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            sent: HashMap::new(),
+        }
+    }
+
+    fn send(
+        osc_sender: &Sender<Feedback>,
+        namespace: &str,
+        msg: rosc::OscMessage,
+    ) -> Result<(), PollError> {
+        osc_sender
+            .send(surfaces::namespaced(namespace, OscPacket::Message(msg)).into())
+            .map_err(PollError::Send)
+    }
+
+    fn poll_surface(
+        &mut self,
+        namespace: &str,
+        osc_sender: &Sender<Feedback>,
+    ) -> Result<(), PollError> {
+        let _surface = surfaces::enter(namespace);
+        let bank = bank();
+        let mode = surfaces::volume_mode();
+        let targets: Vec<Option<SlotTarget>> = (0..bank.size)
+            .map(|slot| slot_target_in(&self.reaper, &bank, slot))
+            .collect();
+        let slots: Vec<String> = targets
+            .iter()
+            .map(|target| {
+                target
+                    .and_then(|t| t.display_track(&self.reaper))
+                    .map(|t| get_track_guid(&self.reaper, t))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let volumes: Vec<Option<f64>> = targets
+            .iter()
+            .map(|target| {
+                target.map(|t| volume_to_mode_value(&self.reaper, mode, t.volume(&self.reaper)))
+            })
+            .collect();
+
+        let prev = self.sent.entry(namespace.to_string()).or_default();
+        let bank_changed = prev.bank.as_ref() != Some(&bank) || prev.slots != slots;
+        if bank_changed {
+            Self::send(
+                osc_sender,
+                namespace,
+                BankOffsetRoute::build_message(bank.offset, &self.reaper),
+            )?;
+            Self::send(
+                osc_sender,
+                namespace,
+                SpillTrackRoute::build_message(
+                    bank.spill.clone().unwrap_or_default(),
+                    &self.reaper,
+                ),
+            )?;
+            for (slot, track_guid) in slots.iter().enumerate() {
+                Self::send(
+                    osc_sender,
+                    namespace,
+                    BankSlotGuidRoute::build_message(
+                        BankSlotGuidArgs {
+                            slot: slot as u32,
+                            track_guid: track_guid.clone(),
+                        },
+                        &self.reaper,
+                    ),
+                )?;
+            }
+        }
+        for (slot, volume) in volumes.iter().enumerate() {
+            let Some(volume) = volume else {
+                continue;
+            };
+            if bank_changed || prev.volumes.get(slot) != Some(&Some(*volume)) {
+                Self::send(
+                    osc_sender,
+                    namespace,
+                    BankSlotVolumeRoute::build_message(
+                        BankSlotVolumeArgs {
+                            slot: slot as u32,
+                            volume: *volume,
+                        },
+                        &self.reaper,
+                    ),
+                )?;
+            }
+        }
+
+        prev.bank = Some(bank);
+        prev.slots = slots;
+        prev.volumes = volumes;
+        Ok(())
+    }
+}
+
+impl PollSource for BankPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let mut namespaces: Vec<&str> = surfaces::profiles()
+            .iter()
+            .map(|p| p.namespace.as_str())
+            .collect();
+        namespaces.dedup();
+        for namespace in namespaces {
+            self.poll_surface(namespace, osc_sender)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::io;
+use std::net::{SocketAddrV4, UdpSocket};
+
+use rosc::{encoder, OscPacket};
+
+use crate::destinations;
+use crate::transfer;
+use crate::utils::immediate_bundle;
+
+// Largest datagram sent as a batch: a 1500 byte Ethernet MTU minus IP and UDP headers
+const MAX_DATAGRAM_LEN: usize = 1472;
+// "#bundle\0" plus the timetag
+const BUNDLE_HEADER_LEN: usize = 16;
+// Each bundle element is preceded by its size
+const ELEMENT_SIZE_LEN: usize = 4;
+
+/// Outgoing packets collected during a run cycle, sent per destination as bundles of
+/// at most one datagram each
+#[derive(Default)]
+pub struct OutgoingBatch {
+    pending: Vec<(SocketAddrV4, Vec<OscPacket>)>,
+}
+
+impl OutgoingBatch {
+    pub fn push(&mut self, dest: SocketAddrV4, packet: OscPacket) {
+        match self.pending.iter_mut().find(|(d, _)| *d == dest) {
+            Some((_, packets)) => packets.push(packet),
+            None => self.pending.push((dest, vec![packet])),
+        }
+    }
+
+    /// Sends the pending packets, fragmenting those too large for one datagram, along
+    /// with fragments due to be sent again. Returns the destinations that just became
+    /// unreachable.
+    pub fn flush(&mut self, sock: &UdpSocket) -> Vec<(SocketAddrV4, io::Error)> {
+        let mut unreachable = Vec::new();
+        for (dest, packets) in self.pending.drain(..) {
+            for datagram in pack(packets) {
+                let datagrams = if datagram.len() > MAX_DATAGRAM_LEN {
+                    transfer::start(dest, datagram)
+                } else {
+                    vec![datagram]
+                };
+                for datagram in datagrams {
+                    send(sock, dest, &datagram, &mut unreachable);
+                }
+            }
+        }
+        for (dest, fragment) in transfer::retransmit() {
+            send(sock, dest, &fragment, &mut unreachable);
+        }
+        unreachable
+    }
+}
+
+// Sends a datagram unless the destination is backing off after failed sends
+fn send(
+    sock: &UdpSocket,
+    dest: SocketAddrV4,
+    datagram: &[u8],
+    unreachable: &mut Vec<(SocketAddrV4, io::Error)>,
+) {
+    if !destinations::should_send(dest) {
+        return;
+    }
+    if let Some(e) = destinations::record(dest, sock.send_to(datagram, dest)) {
+        unreachable.push((dest, e));
+    }
+}
+
+// Splits the packets into datagrams that fit the MTU, keeping their order. A packet
+// too large on its own is left alone, to be fragmented.
+fn pack(packets: Vec<OscPacket>) -> Vec<Vec<u8>> {
+    let mut datagrams = Vec::new();
+    let mut current = Vec::new();
+    let mut len = BUNDLE_HEADER_LEN;
+    for packet in packets {
+        let Ok(packet_len) = encoder::encode(&packet).map(|buf| buf.len()) else {
+            continue;
+        };
+        if !current.is_empty() && len + ELEMENT_SIZE_LEN + packet_len > MAX_DATAGRAM_LEN {
+            datagrams.extend(encode(std::mem::take(&mut current)));
+            len = BUNDLE_HEADER_LEN;
+        }
+        len += ELEMENT_SIZE_LEN + packet_len;
+        current.push(packet);
+    }
+    if !current.is_empty() {
+        datagrams.extend(encode(current));
+    }
+    datagrams
+}
+
+// A lone packet is sent as is rather than wrapped in a bundle of one
+fn encode(mut packets: Vec<OscPacket>) -> Option<Vec<u8>> {
+    let packet = match packets.len() {
+        1 => packets.pop()?,
+        _ => immediate_bundle(packets),
+    };
+    encoder::encode(&packet).ok()
+}
@@ -0,0 +1,50 @@
+use std::time::SystemTime;
+
+use rosc::{OscBundle, OscTime};
+
+// Bundles waiting for their timetag are dropped beyond this, so a client sending
+// far-future timetags can't grow the queue without bound
+const MAX_SCHEDULED_BUNDLES: usize = 1024;
+
+// The OSC timetag meaning "execute immediately"
+const IMMEDIATELY: OscTime = OscTime {
+    seconds: 0,
+    fractional: 1,
+};
+
+/// When a bundle is due, or None for bundles to run immediately
+fn due_time(timetag: OscTime) -> Option<SystemTime> {
+    (timetag != IMMEDIATELY).then(|| SystemTime::from(timetag))
+}
+
+/// Incoming bundles whose timetag is still in the future
+#[derive(Default)]
+pub struct BundleQueue {
+    scheduled: Vec<(SystemTime, OscBundle)>,
+}
+
+impl BundleQueue {
+    /// Returns the bundle if it is due now, or holds it until its timetag
+    pub fn schedule(&mut self, bundle: OscBundle) -> Option<OscBundle> {
+        let Some(due) = due_time(bundle.timetag).filter(|&due| due > SystemTime::now()) else {
+            return Some(bundle);
+        };
+        if self.scheduled.len() >= MAX_SCHEDULED_BUNDLES {
+            eprintln!("Dropping OSC bundle, too many bundles scheduled");
+            return None;
+        }
+        self.scheduled.push((due, bundle));
+        None
+    }
+
+    /// Removes the bundles that became due, earliest first
+    pub fn take_due(&mut self) -> Vec<OscBundle> {
+        let now = SystemTime::now();
+        let (mut due, pending): (Vec<_>, Vec<_>) =
+            self.scheduled.drain(..).partition(|(at, _)| *at <= now);
+        self.scheduled = pending;
+        // Stable, so bundles sharing a timetag run in the order they arrived
+        due.sort_by_key(|(at, _)| *at);
+        due.into_iter().map(|(_, bundle)| bundle).collect()
+    }
+}
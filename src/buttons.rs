@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use rosc::{OscMessage, OscType};
+use serde::Deserialize;
+
+use crate::ReceiverError;
+
+/// How an incoming on/off value drives a track switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ButtonMode {
+    /// The value is the new state
+    #[default]
+    Absolute,
+    /// Each press flips the state; releases are ignored
+    TogglePress,
+    /// A press flips the state and the release restores it
+    Momentary,
+}
+
+impl ButtonMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "absolute" => Some(ButtonMode::Absolute),
+            "toggle-on-press" | "toggle-press" => Some(ButtonMode::TogglePress),
+            "momentary" => Some(ButtonMode::Momentary),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ButtonMode::Absolute => "absolute",
+            ButtonMode::TogglePress => "toggle-on-press",
+            ButtonMode::Momentary => "momentary",
+        }
+    }
+}
+
+/// The track switches whose button mode can be configured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Switch {
+    Mute,
+    Solo,
+    RecArm,
+}
+
+impl Switch {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mute" => Some(Switch::Mute),
+            "solo" => Some(Switch::Solo),
+            "rec-arm" => Some(Switch::RecArm),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Switch::Mute => "mute",
+            Switch::Solo => "solo",
+            Switch::RecArm => "rec-arm",
+        }
+    }
+}
+
+/// The `[buttons]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ButtonsConfig {
+    pub mute: ButtonMode,
+    pub solo: ButtonMode,
+    pub rec_arm: ButtonMode,
+}
+
+static CONFIG: Mutex<Option<ButtonsConfig>> = Mutex::new(None);
+
+// Modes changed at runtime with /arpad/config/button-mode
+static MODES: LazyLock<Mutex<HashMap<Switch, ButtonMode>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// States from before a momentary press, keyed by switch and track GUID, restored on release
+static HELD: LazyLock<Mutex<HashMap<(Switch, String), bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn init(config: ButtonsConfig) {
+    *CONFIG.lock().unwrap() = Some(config);
+}
+
+pub fn mode(switch: Switch) -> ButtonMode {
+    if let Some(mode) = MODES.lock().unwrap().get(&switch) {
+        return *mode;
+    }
+    let config = CONFIG.lock().unwrap().clone().unwrap_or_default();
+    match switch {
+        Switch::Mute => config.mute,
+        Switch::Solo => config.solo,
+        Switch::RecArm => config.rec_arm,
+    }
+}
+
+pub fn set_mode(switch: Switch, mode: ButtonMode) {
+    MODES.lock().unwrap().insert(switch, mode);
+}
+
+/// Reads the first argument of a button message: a bool, or a number where anything
+/// non-zero counts as pressed
+pub fn pressed(msg: &OscMessage) -> Result<bool, ReceiverError> {
+    match msg.args.first() {
+        Some(OscType::Bool(b)) => Ok(*b),
+        Some(OscType::Float(f)) => Ok(*f != 0.0),
+        Some(OscType::Double(d)) => Ok(*d != 0.0),
+        Some(OscType::Int(i)) => Ok(*i != 0),
+        _ => Err(ReceiverError::BadValue(
+            "Invalid button value, expected a bool or a number".to_string(),
+        )),
+    }
+}
+
+/// Works out the new state of a track switch from a button message, given its current
+/// state. Returns None when the message leaves the switch alone.
+pub fn next_state(
+    switch: Switch,
+    track_guid: &str,
+    msg: &OscMessage,
+    current: bool,
+) -> Result<Option<bool>, ReceiverError> {
+    let pressed = pressed(msg)?;
+    Ok(match mode(switch) {
+        ButtonMode::Absolute => Some(pressed),
+        ButtonMode::TogglePress => pressed.then_some(!current),
+        ButtonMode::Momentary => {
+            let mut held = HELD.lock().unwrap();
+            let key = (switch, track_guid.to_string());
+            if pressed {
+                if held.contains_key(&key) {
+                    return Ok(None);
+                }
+                held.insert(key, current);
+                Some(!current)
+            } else {
+                held.remove(&key)
+            }
+        }
+    })
+}
@@ -0,0 +1,158 @@
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+use c_str_macro::c_str;
+use reaper_medium::{MediaTrack, Reaper, TrackSendCategory};
+use serde::Deserialize;
+
+use crate::cuemix;
+use crate::utils::{find_track_by_guid_or_name, get_config_var, set_track_send_info_value};
+use crate::RouteError;
+
+const DEFAULT_CLICK_TRACK: &str = "Click";
+
+// I_DSTCHAN holds the first output channel in the low bits; higher bits flag mono
+// and multichannel outputs
+const DSTCHAN_CHANNEL_MASK: i32 = 0x3ff;
+
+/// The `[click]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ClickConfig {
+    /// GUID or name of the track carrying the click; defaults to a track named "Click"
+    pub track: Option<String>,
+}
+
+static CONFIG: Mutex<Option<ClickConfig>> = Mutex::new(None);
+
+pub fn init(config: ClickConfig) {
+    *CONFIG.lock().unwrap() = Some(config);
+}
+
+pub fn click_track(reaper: &Reaper) -> Result<MediaTrack, RouteError> {
+    let id = CONFIG
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|c| c.track.clone())
+        .unwrap_or_else(|| DEFAULT_CLICK_TRACK.to_string());
+    find_track_by_guid_or_name(reaper, &id)
+}
+
+/// Index of the click track's hardware output starting at `channel`, if any
+pub fn hw_output_index(reaper: &Reaper, track: MediaTrack, channel: u32) -> Option<u32> {
+    unsafe {
+        (0..reaper.get_track_num_sends(track, TrackSendCategory::HardwareOutput)).find(|&i| {
+            let dst = reaper.low().GetTrackSendInfo_Value(
+                track.as_ptr(),
+                TrackSendCategory::HardwareOutput.to_raw(),
+                i as i32,
+                c_str!("I_DSTCHAN").as_ptr(),
+            ) as i32;
+            dst & DSTCHAN_CHANNEL_MASK == channel as i32
+        })
+    }
+}
+
+/// Adds or removes the click track's hardware output starting at `channel`
+pub fn set_hw_output(
+    reaper: &Reaper,
+    track: MediaTrack,
+    channel: u32,
+    enabled: bool,
+) -> Result<(), RouteError> {
+    match (hw_output_index(reaper, track, channel), enabled) {
+        (None, true) => {
+            // A send without a destination track is a hardware output
+            let index = unsafe {
+                reaper
+                    .low()
+                    .CreateTrackSend(track.as_ptr(), std::ptr::null_mut())
+            };
+            let index = u32::try_from(index)
+                .map_err(|_| RouteError::ValueNotFound("New hardware output".to_string()))?;
+            set_track_send_info_value(
+                reaper,
+                track,
+                TrackSendCategory::HardwareOutput,
+                index,
+                c_str!("I_DSTCHAN"),
+                channel as f64,
+            )
+        }
+        (Some(index), false) => {
+            remove_send(reaper, track, TrackSendCategory::HardwareOutput, index);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Adds or removes the click track's send to a cue bus
+pub fn set_cue_output(
+    reaper: &Reaper,
+    track: MediaTrack,
+    cue: MediaTrack,
+    enabled: bool,
+) -> Result<(), RouteError> {
+    match (cuemix::cue_send_index(reaper, cue, track), enabled) {
+        (None, true) => cuemix::add_cue_send(reaper, track, cue).map(|_| ()),
+        (Some(index), false) => {
+            remove_send(reaper, track, TrackSendCategory::Send, index);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn remove_send(reaper: &Reaper, track: MediaTrack, category: TrackSendCategory, index: u32) {
+    unsafe {
+        reaper
+            .low()
+            .RemoveTrackSend(track.as_ptr(), category.to_raw(), index as i32);
+    }
+}
+
+/// Which metronome click a volume applies to
+#[derive(Debug, Clone, Copy)]
+pub enum ClickBeat {
+    /// The first beat of each measure
+    Accent,
+    /// Every other beat
+    Beat,
+}
+
+impl ClickBeat {
+    pub fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "accent" => Some(ClickBeat::Accent),
+            "beat" => Some(ClickBeat::Beat),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClickBeat::Accent => "accent",
+            ClickBeat::Beat => "beat",
+        }
+    }
+
+    fn config_var(&self) -> &'static CStr {
+        match self {
+            ClickBeat::Accent => c_str!("projmetrov1"),
+            ClickBeat::Beat => c_str!("projmetrov2"),
+        }
+    }
+}
+
+/// Metronome volume for the beat as a linear gain
+pub fn volume(reaper: &Reaper, beat: ClickBeat) -> Option<f64> {
+    get_config_var::<f64>(reaper, beat.config_var()).map(|ptr| unsafe { *ptr })
+}
+
+pub fn set_volume(reaper: &Reaper, beat: ClickBeat, volume: f64) -> Option<()> {
+    let ptr = get_config_var::<f64>(reaper, beat.config_var())?;
+    unsafe { *ptr = volume };
+    Some(())
+}
@@ -0,0 +1,101 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::net::SocketAddrV4;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// When each client last sent a packet, keyed by the address it sends from
+static CLIENTS: LazyLock<Mutex<HashMap<SocketAddrV4, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// How long a silent client keeps receiving feedback; None turns broadcasting off
+static TIMEOUT: OnceLock<Option<Duration>> = OnceLock::new();
+
+static REPLY_TO_SENDER: AtomicBool = AtomicBool::new(true);
+
+// When every sender, surfaces included, was last heard from, for noticing reconnects
+static LAST_HEARD: LazyLock<Mutex<HashMap<SocketAddrV4, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Silence after which a sender is treated as reconnecting; None turns resyncing off
+static RESYNC_AFTER: OnceLock<Option<Duration>> = OnceLock::new();
+
+thread_local! {
+    // Source address of the packet currently being dispatched
+    static CURRENT_CLIENT: Cell<Option<SocketAddrV4>> = const { Cell::new(None) };
+}
+
+/// `timeout_secs` of 0 stops feedback from going to clients that aren't surfaces, and
+/// `resync_after_secs` of 0 stops the full state from being sent on reconnect
+pub fn init(timeout_secs: u64, reply_to_sender: bool, resync_after_secs: u64) {
+    let _ = TIMEOUT.set((timeout_secs > 0).then(|| Duration::from_secs(timeout_secs)));
+    REPLY_TO_SENDER.store(reply_to_sender, Ordering::Relaxed);
+    let _ =
+        RESYNC_AFTER.set((resync_after_secs > 0).then(|| Duration::from_secs(resync_after_secs)));
+}
+
+/// Records a message from any sender. Returns true when the sender is new or comes
+/// back after a silence, so it should be sent the full state.
+pub fn heard(client: SocketAddrV4) -> bool {
+    let Some(resync_after) = RESYNC_AFTER.get().copied().flatten() else {
+        return false;
+    };
+    LAST_HEARD
+        .lock()
+        .unwrap()
+        .insert(client, Instant::now())
+        .is_none_or(|last| last.elapsed() > resync_after)
+}
+
+/// Records a packet from `client`, adding it to the table if it's new
+pub fn seen(client: SocketAddrV4) {
+    CLIENTS.lock().unwrap().insert(client, Instant::now());
+}
+
+/// How long a silent client keeps receiving feedback, if broadcasting to clients is on
+pub fn timeout() -> Option<Duration> {
+    TIMEOUT.get().copied().flatten()
+}
+
+/// Clients heard from within the timeout; clients idle for longer are forgotten
+pub fn active() -> Vec<SocketAddrV4> {
+    let Some(timeout) = timeout() else {
+        return Vec::new();
+    };
+    let mut clients = CLIENTS.lock().unwrap();
+    clients.retain(|_, last_seen| last_seen.elapsed() < timeout);
+    clients.keys().copied().collect()
+}
+
+/// Marks `client` as the sender of the packet being dispatched until the guard is
+/// dropped
+pub fn enter(client: Option<SocketAddrV4>) -> ClientGuard {
+    ClientGuard {
+        prev: CURRENT_CLIENT.with(|c| c.replace(client)),
+    }
+}
+
+pub struct ClientGuard {
+    prev: Option<SocketAddrV4>,
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        CURRENT_CLIENT.with(|c| c.set(self.prev));
+    }
+}
+
+/// Source address of the packet being dispatched
+pub fn current_client() -> Option<SocketAddrV4> {
+    CURRENT_CLIENT.with(|c| c.get())
+}
+
+/// Where query replies go: the client that asked, unless replies are configured to go
+/// to the surface destinations
+pub fn reply_address() -> Option<SocketAddrV4> {
+    REPLY_TO_SENDER
+        .load(Ordering::Relaxed)
+        .then(current_client)
+        .flatten()
+}
@@ -0,0 +1,76 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::Reaper;
+use rosc::OscPacket;
+use serde::Deserialize;
+
+use crate::feedback::{Feedback, Lane};
+use crate::osc_routes::{ClockArgs, ClockRoute};
+use crate::polling::{PollError, PollSource};
+use crate::OscRoute;
+
+/// The `[clock]` section of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// How many times per second `/clock` is broadcast; 0 disables it
+    pub rate: f64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self { rate: 10.0 }
+    }
+}
+
+static CONFIG: OnceLock<ClockConfig> = OnceLock::new();
+
+pub fn init(config: ClockConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// Broadcasts the big clock at a fixed rate. The clock is computed once per
+/// broadcast and fanned out to every surface by the sender thread.
+pub struct ClockPollSource {
+    reaper: Reaper,
+    interval: Option<Duration>,
+    last_sent: Instant,
+}
+
+impl ClockPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        let rate = CONFIG.get().cloned().unwrap_or_default().rate;
+        Self {
+            reaper,
+            interval: (rate > 0.0).then(|| Duration::from_secs_f64(1.0 / rate)),
+            last_sent: Instant::now(),
+        }
+    }
+}
+
+impl PollSource for ClockPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let Some(interval) = self.interval else {
+            return Ok(());
+        };
+        if self.last_sent.elapsed() < interval {
+            return Ok(());
+        }
+        self.last_sent = Instant::now();
+        osc_sender
+            .send(
+                OscPacket::Message(ClockRoute::build_message(
+                    ClockArgs::collect(&self.reaper),
+                    &self.reaper,
+                ))
+                .into(),
+            )
+            .map_err(PollError::Send)
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
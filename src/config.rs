@@ -0,0 +1,257 @@
+use std::env;
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::net::SocketAddrV4;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use c_str_macro::c_str;
+use reaper_medium::Reaper;
+use serde::Deserialize;
+
+use crate::buttons::{self, ButtonsConfig};
+use crate::click::{self, ClickConfig};
+use crate::clock::ClockConfig;
+use crate::destinations::Destination;
+use crate::journal::JournalConfig;
+use crate::position::PositionConfig;
+use crate::smoothing::{self, SmoothingConfig};
+use crate::strip::{self, StripConfig};
+use crate::surfaces::SurfaceProfile;
+use crate::talkback::{self, TalkbackConfig};
+use crate::vu::VuConfig;
+
+const CONFIG_FILE_NAME: &str = "arpad.toml";
+
+// Project ext-state holding a block in the format of the config file, saved with the
+// project and merged over the config file while it is active
+const PROJECT_EXT_SECTION: &CStr = c_str!("arpad");
+const PROJECT_CONFIG_KEY: &CStr = c_str!("config");
+const PROJECT_CONFIG_BUF_LEN: usize = 65536;
+
+// Environment variables overriding the `[osc]` section
+const LISTEN_ENV: &str = "ARPAD_LISTEN";
+const DESTINATION_ENV: &str = "ARPAD_DESTINATION";
+const QUEUE_SIZE_ENV: &str = "ARPAD_QUEUE_SIZE";
+
+/// The `[osc]` section of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OscConfig {
+    /// Address the plugin receives OSC on
+    pub listen: SocketAddrV4,
+    /// Where feedback goes when no `[[surface]]` is configured, as "host:port"
+    pub destination: Destination,
+    /// Number of outgoing packets buffered for the sender thread, for control feedback
+    /// and for meters each
+    pub queue_size: usize,
+    /// Seconds a client keeps receiving feedback after the last packet it sent; 0
+    /// sends feedback only to the configured destinations
+    pub client_timeout: u64,
+    /// Whether query replies go to the address the query came from rather than to the
+    /// surface destinations
+    pub reply_to_sender: bool,
+    /// Listen-only destinations receiving a copy of the shared feedback and query
+    /// replies, e.g. a logging host
+    pub echo: Vec<SocketAddrV4>,
+    /// Seconds of silence after which a surface with `resync` set is sent the full
+    /// state when it's heard from again, as after a Wi-Fi drop; 0 turns this off
+    pub resync_after: u64,
+    /// Whether packets too large for one datagram, like state dumps or FX chunks, are
+    /// sent as acknowledged `/arpad/blob` fragments rather than as one oversized datagram.
+    /// Off by default, as ordinary OSC clients can't reassemble fragments.
+    pub fragment_large_packets: bool,
+    /// Actions that may be run through `/action` when no `[[surface]]` is configured,
+    /// as numeric ids or command names; "*" allows every action
+    pub actions: Vec<String>,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        Self {
+            listen: SocketAddrV4::from_str("0.0.0.0:9090").unwrap(),
+            destination: SocketAddrV4::from_str("0.0.0.0:9091").unwrap().into(),
+            queue_size: 128,
+            client_timeout: 60,
+            reply_to_sender: true,
+            echo: Vec::new(),
+            resync_after: 10,
+            fragment_large_packets: false,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl OscConfig {
+    fn apply_env(&mut self) {
+        override_from_env(LISTEN_ENV, &mut self.listen);
+        override_from_env(DESTINATION_ENV, &mut self.destination);
+        override_from_env(QUEUE_SIZE_ENV, &mut self.queue_size);
+    }
+}
+
+fn override_from_env<T: FromStr>(name: &str, value: &mut T) {
+    let Ok(text) = env::var(name) else {
+        return;
+    };
+    match text.parse() {
+        Ok(parsed) => *value = parsed,
+        Err(_) => eprintln!("Ignoring invalid {}: {}", name, text),
+    }
+}
+
+/// Settings read from `arpad.toml` in the REAPER resource directory
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub osc: OscConfig,
+    /// Logical surfaces served by this plugin instance, declared as `[[surface]]` tables
+    #[serde(rename = "surface")]
+    pub surfaces: Vec<SurfaceProfile>,
+    pub talkback: TalkbackConfig,
+    pub click: ClickConfig,
+    pub strip: StripConfig,
+    pub clock: ClockConfig,
+    pub buttons: ButtonsConfig,
+    pub vu: VuConfig,
+    pub smoothing: SmoothingConfig,
+    pub position: PositionConfig,
+    pub journal: JournalConfig,
+}
+
+impl Config {
+    pub fn path(reaper: &Reaper) -> PathBuf {
+        reaper.get_resource_path(|path| path.join(CONFIG_FILE_NAME))
+    }
+
+    /// Loads the config file, falling back to defaults when it is missing or invalid.
+    /// Environment variables take precedence over the `[osc]` section.
+    pub fn load(reaper: &Reaper) -> Config {
+        let mut config = Self::load_file(reaper);
+        config.osc.apply_env();
+        config
+    }
+
+    /// Sets values in the config file, given as section, key and value, keeping the
+    /// rest of its settings. Comments in the file are not kept.
+    pub fn update_file(reaper: &Reaper, edits: &[(&str, &str, toml::Value)]) -> Result<(), String> {
+        let path = Self::path(reaper);
+        let mut table: toml::Table = match fs::read_to_string(&path) {
+            Ok(text) => text
+                .parse()
+                .map_err(|e| format!("{}: {}", path.display(), e))?,
+            Err(_) => toml::Table::new(),
+        };
+        for (section, key, value) in edits {
+            let section = table
+                .entry(*section)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            let Some(section) = section.as_table_mut() else {
+                return Err(format!(
+                    "{} in {} is not a section",
+                    section,
+                    path.display()
+                ));
+            };
+            section.insert(key.to_string(), value.clone());
+        }
+        let text = toml::to_string(&table).map_err(|e| e.to_string())?;
+        fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn load_file(reaper: &Reaper) -> Config {
+        let path = Self::path(reaper);
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Config::default();
+        };
+        toml::from_str(&text).unwrap_or_else(|e| {
+            eprintln!("Failed to parse {}: {}", path.display(), e);
+            Config::default()
+        })
+    }
+
+    /// The config file with `block`, in the same format, merged over it
+    fn merged(reaper: &Reaper, block: &str) -> Result<Config, String> {
+        let path = Self::path(reaper);
+        let mut table: toml::Table = match fs::read_to_string(&path) {
+            Ok(text) => text
+                .parse()
+                .map_err(|e| format!("{}: {}", path.display(), e))?,
+            Err(_) => toml::Table::new(),
+        };
+        let overrides: toml::Table = block.parse().map_err(|e| e.to_string())?;
+        merge(&mut table, overrides);
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Applies the sections a project can override, from the config file with the
+    /// active project's block merged over it. Called whenever another project becomes
+    /// active; the other sections only take effect when REAPER starts.
+    pub fn apply_project(reaper: &Reaper) {
+        let config = match project_block(reaper) {
+            Some(block) => Self::merged(reaper, &block).unwrap_or_else(|e| {
+                eprintln!("Ignoring invalid project config: {}", e);
+                Self::load_file(reaper)
+            }),
+            None => Self::load_file(reaper),
+        };
+        talkback::init(config.talkback);
+        click::init(config.click);
+        strip::init(config.strip);
+        buttons::init(config.buttons);
+        smoothing::init(config.smoothing);
+    }
+}
+
+// Merges `overrides` into `base` table by table, so a project only gives the keys it
+// changes
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The config block saved in the active project, if any
+pub fn project_block(reaper: &Reaper) -> Option<String> {
+    let mut buf = vec![0 as std::os::raw::c_char; PROJECT_CONFIG_BUF_LEN];
+    let len = unsafe {
+        reaper.low().GetProjExtState(
+            std::ptr::null_mut(),
+            PROJECT_EXT_SECTION.as_ptr(),
+            PROJECT_CONFIG_KEY.as_ptr(),
+            buf.as_mut_ptr(),
+            buf.len() as i32,
+        )
+    };
+    if len <= 0 {
+        return None;
+    }
+    let block = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_string_lossy();
+    Some(block.into_owned())
+}
+
+/// Saves a config block in the active project and applies it. An empty block removes
+/// the project's overrides.
+pub fn set_project_block(reaper: &Reaper, block: &str) -> Result<(), String> {
+    if !block.trim().is_empty() {
+        Config::merged(reaper, block)?;
+    }
+    let value = CString::new(block).map_err(|e| e.to_string())?;
+    unsafe {
+        reaper.low().SetProjExtState(
+            std::ptr::null_mut(),
+            PROJECT_EXT_SECTION.as_ptr(),
+            PROJECT_CONFIG_KEY.as_ptr(),
+            value.as_ptr(),
+        );
+    }
+    Config::apply_project(reaper);
+    Ok(())
+}
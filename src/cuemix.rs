@@ -0,0 +1,115 @@
+use std::ffi::{CStr, CString};
+
+use c_str_macro::c_str;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{
+    MediaTrack, Reaper, TrackSendAttributeKey, TrackSendCategory, TrackSendDirection,
+};
+
+use crate::utils::set_track_send_info_value;
+use crate::RouteError;
+
+// Track extension key marking a track as a cue bus; stored in the project so cue
+// buses survive reloads
+const CUE_EXT_KEY: &CStr = c_str!("P_EXT:arpad_cue");
+
+// Send mode 3 is post-FX, pre-fader, so cue mixes don't follow the main faders
+const PRE_FADER_POST_FX: f64 = 3.0;
+
+pub fn is_cue_bus(reaper: &Reaper, track: MediaTrack) -> bool {
+    let mut buf = [0 as std::os::raw::c_char; 8];
+    unsafe {
+        reaper.low().GetSetMediaTrackInfo_String(
+            track.as_ptr(),
+            CUE_EXT_KEY.as_ptr(),
+            buf.as_mut_ptr(),
+            false,
+        ) && CStr::from_ptr(buf.as_ptr()).to_bytes() == b"1"
+    }
+}
+
+/// All cue buses in the project, in track order
+pub fn cue_buses(reaper: &Reaper) -> Vec<MediaTrack> {
+    (0..reaper.count_tracks(CurrentProject))
+        .filter_map(|i| reaper.get_track(CurrentProject, i))
+        .filter(|&track| is_cue_bus(reaper, track))
+        .collect()
+}
+
+/// Appends a cue bus and gives it a pre-fader send from every track that isn't
+/// itself a cue bus. The bus is not sent to the master so it can feed its own outputs.
+pub fn create_cue_bus(reaper: &Reaper, name: &str) -> Result<MediaTrack, RouteError> {
+    let name = CString::new(name).unwrap_or_default();
+    let index = reaper.count_tracks(CurrentProject);
+    let sources: Vec<MediaTrack> = (0..index)
+        .filter_map(|i| reaper.get_track(CurrentProject, i))
+        .filter(|&track| !is_cue_bus(reaper, track))
+        .collect();
+    unsafe {
+        reaper.low().InsertTrackAtIndex(index as i32, false);
+        let bus = reaper
+            .get_track(CurrentProject, index)
+            .ok_or_else(|| RouteError::ValueNotFound("New cue bus".to_string()))?;
+        let low = reaper.low();
+        low.GetSetMediaTrackInfo_String(
+            bus.as_ptr(),
+            c_str!("P_NAME").as_ptr(),
+            name.as_ptr() as *mut _,
+            true,
+        );
+        low.GetSetMediaTrackInfo_String(
+            bus.as_ptr(),
+            CUE_EXT_KEY.as_ptr(),
+            c_str!("1").as_ptr() as *mut _,
+            true,
+        );
+        low.SetMediaTrackInfo_Value(bus.as_ptr(), c_str!("B_MAINSEND").as_ptr(), 0.0);
+        for source in sources {
+            add_cue_send(reaper, source, bus)?;
+        }
+        low.Undo_OnStateChange(c_str!("Create cue bus via arpad").as_ptr());
+        Ok(bus)
+    }
+}
+
+/// Adds a pre-fader send from `source` to the cue bus, returning its index
+pub fn add_cue_send(
+    reaper: &Reaper,
+    source: MediaTrack,
+    cue: MediaTrack,
+) -> Result<u32, RouteError> {
+    let send_index = unsafe { reaper.low().CreateTrackSend(source.as_ptr(), cue.as_ptr()) };
+    let send_index = u32::try_from(send_index)
+        .map_err(|_| RouteError::ValueNotFound("New cue send".to_string()))?;
+    set_track_send_info_value(
+        reaper,
+        source,
+        TrackSendCategory::Send,
+        send_index,
+        c_str!("I_SENDMODE"),
+        PRE_FADER_POST_FX,
+    )?;
+    Ok(send_index)
+}
+
+/// Index of the send from `source` feeding the cue bus, if there is one
+pub fn cue_send_index(reaper: &Reaper, cue: MediaTrack, source: MediaTrack) -> Option<u32> {
+    unsafe {
+        (0..reaper.get_track_num_sends(source, TrackSendCategory::Send)).find(|&i| {
+            reaper
+                .get_track_send_info_desttrack(source, TrackSendDirection::Send, i)
+                .is_ok_and(|dest| dest == cue)
+        })
+    }
+}
+
+pub fn cue_send_volume(reaper: &Reaper, source: MediaTrack, send_index: u32) -> f64 {
+    unsafe {
+        reaper.get_track_send_info_value(
+            source,
+            TrackSendCategory::Send,
+            send_index,
+            TrackSendAttributeKey::Vol,
+        )
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, SocketAddrV4, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use rosc::{OscMessage, OscType};
+use serde::Deserialize;
+
+// How often host names are looked up again, to follow address changes such as a new
+// DHCP lease
+const RESOLVE_INTERVAL: Duration = Duration::from_secs(30);
+// How often a host name is looked up while sends to it fail or it doesn't resolve
+const FAILING_RESOLVE_INTERVAL: Duration = Duration::from_secs(5);
+// Sends to a failing destination pause for this long after the first failure, doubling
+// with every further failure up to the maximum
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A feedback destination given as "host:port", where the host is an IPv4 address or
+/// a host name. Host names are looked up again periodically and while sends fail, so
+/// a surface whose address changes keeps receiving feedback.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Destination {
+    spec: String,
+    resolved: Arc<Mutex<Resolved>>,
+}
+
+#[derive(Debug)]
+struct Resolved {
+    addr: Option<SocketAddrV4>,
+    at: Instant,
+}
+
+impl Destination {
+    /// The destination as configured
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// The address feedback currently goes to, or None while the host name doesn't
+    /// resolve
+    pub fn addr(&self) -> Option<SocketAddrV4> {
+        self.resolved.lock().unwrap().addr
+    }
+
+    /// Looks the host name up again when it's due. Literal addresses never change.
+    pub fn refresh(&self) {
+        if self.spec.parse::<SocketAddrV4>().is_ok() {
+            return;
+        }
+        let (addr, at) = {
+            let resolved = self.resolved.lock().unwrap();
+            (resolved.addr, resolved.at)
+        };
+        let failing = addr.is_none_or(is_failing);
+        let interval = if failing {
+            FAILING_RESOLVE_INTERVAL
+        } else {
+            RESOLVE_INTERVAL
+        };
+        if at.elapsed() < interval {
+            return;
+        }
+        let addr = resolve(&self.spec);
+        let mut resolved = self.resolved.lock().unwrap();
+        if addr.is_some() && addr != resolved.addr {
+            eprintln!("OSC destination {} now resolves to {:?}", self.spec, addr);
+        }
+        // A failed lookup keeps the last known address
+        resolved.addr = addr.or(resolved.addr);
+        resolved.at = Instant::now();
+    }
+}
+
+impl From<SocketAddrV4> for Destination {
+    fn from(addr: SocketAddrV4) -> Self {
+        Self {
+            spec: addr.to_string(),
+            resolved: Arc::new(Mutex::new(Resolved {
+                addr: Some(addr),
+                at: Instant::now(),
+            })),
+        }
+    }
+}
+
+impl FromStr for Destination {
+    type Err = String;
+
+    /// Host names that don't resolve yet are accepted and looked up again later
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (_, port) = spec
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Invalid destination {}, expected host:port", spec))?;
+        port.parse::<u16>()
+            .map_err(|_| format!("Invalid port in destination {}", spec))?;
+        Ok(Self {
+            spec: spec.to_string(),
+            resolved: Arc::new(Mutex::new(Resolved {
+                addr: resolve(spec),
+                at: Instant::now(),
+            })),
+        })
+    }
+}
+
+impl TryFrom<String> for Destination {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, Self::Error> {
+        spec.parse()
+    }
+}
+
+fn resolve(spec: &str) -> Option<SocketAddrV4> {
+    spec.to_socket_addrs().ok()?.find_map(|addr| match addr {
+        SocketAddr::V4(addr) => Some(addr),
+        SocketAddr::V6(_) => None,
+    })
+}
+
+// Destinations whose last send failed
+static FAILING: LazyLock<Mutex<HashMap<SocketAddrV4, Failure>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+struct Failure {
+    count: u32,
+    retry_at: Instant,
+}
+
+fn is_failing(addr: SocketAddrV4) -> bool {
+    FAILING.lock().unwrap().contains_key(&addr)
+}
+
+/// Whether sends to the destination are due; false while backing off after failures
+pub fn should_send(addr: SocketAddrV4) -> bool {
+    FAILING
+        .lock()
+        .unwrap()
+        .get(&addr)
+        .is_none_or(|failure| Instant::now() >= failure.retry_at)
+}
+
+/// Records the outcome of a send. Returns the error when the destination just became
+/// unreachable, so it can be reported once rather than on every send.
+pub fn record(addr: SocketAddrV4, result: io::Result<usize>) -> Option<io::Error> {
+    let mut failing = FAILING.lock().unwrap();
+    match result {
+        Ok(_) => {
+            if failing.remove(&addr).is_some() {
+                eprintln!("OSC destination {} is reachable again", addr);
+            }
+            None
+        }
+        Err(e) => {
+            let failure = failing.entry(addr).or_insert(Failure {
+                count: 0,
+                retry_at: Instant::now(),
+            });
+            failure.count += 1;
+            let backoff = INITIAL_BACKOFF
+                .saturating_mul(1 << (failure.count - 1).min(16))
+                .min(MAX_BACKOFF);
+            failure.retry_at = Instant::now() + backoff;
+            (failure.count == 1).then(|| {
+                eprintln!("OSC destination {} is unreachable: {}", addr, e);
+                e
+            })
+        }
+    }
+}
+
+/// The `/arpad/error` notification sent to the other destinations when one becomes
+/// unreachable
+pub fn unreachable_message(addr: SocketAddrV4, error: &io::Error) -> OscMessage {
+    OscMessage {
+        addr: "/arpad/error".to_string(),
+        args: vec![
+            OscType::String("unreachable".to_string()),
+            OscType::String(addr.to_string()),
+            OscType::String(error.to_string()),
+        ],
+    }
+}
@@ -0,0 +1,137 @@
+use std::ffi::{CStr, CString};
+use std::ptr::{null_mut, NonNull};
+
+use c_str_macro::c_str;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper, TrackEnvelope};
+
+use crate::RouteError;
+
+// Points closer than this to the edit cursor are moved rather than doubled
+const POINT_TOLERANCE: f64 = 0.0001;
+
+/// Switches of a track envelope
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnvelopeFlag {
+    Visible,
+    Armed,
+}
+
+impl EnvelopeFlag {
+    // GetSetEnvelopeInfo_String key
+    fn key(&self) -> &'static CStr {
+        match self {
+            EnvelopeFlag::Visible => c_str!("VISIBLE"),
+            EnvelopeFlag::Armed => c_str!("ARM"),
+        }
+    }
+}
+
+/// Finds a track envelope by the name REAPER shows, e.g. "Volume" or "Pan", or by
+/// its chunk name, e.g. "VOLENV2", for names that can't be part of an OSC address.
+/// Envelopes the track doesn't have yet aren't found.
+pub fn find(reaper: &Reaper, track: MediaTrack, name: &str) -> Result<TrackEnvelope, RouteError> {
+    let not_found = || RouteError::ValueNotFound(format!("Envelope {}", name));
+    let env_name = CString::new(name).map_err(|_| not_found())?;
+    let chunk_name = CString::new(format!("<{}", name.trim_start_matches('<').to_uppercase()))
+        .map_err(|_| not_found())?;
+    let env = unsafe {
+        let low = reaper.low();
+        let env = low.GetTrackEnvelopeByName(track.as_ptr(), env_name.as_ptr());
+        if env.is_null() {
+            low.GetTrackEnvelopeByChunkName(track.as_ptr(), chunk_name.as_ptr())
+        } else {
+            env
+        }
+    };
+    NonNull::new(env).ok_or_else(not_found)
+}
+
+pub fn flag(reaper: &Reaper, env: TrackEnvelope, flag: EnvelopeFlag) -> bool {
+    let mut buf = [0 as std::os::raw::c_char; 8];
+    unsafe {
+        reaper.low().GetSetEnvelopeInfo_String(
+            env.as_ptr(),
+            flag.key().as_ptr(),
+            buf.as_mut_ptr(),
+            false,
+        ) && CStr::from_ptr(buf.as_ptr()).to_bytes() == b"1"
+    }
+}
+
+pub fn set_flag(reaper: &Reaper, env: TrackEnvelope, flag: EnvelopeFlag, on: bool) {
+    let value = if on { c_str!("1") } else { c_str!("0") };
+    unsafe {
+        let low = reaper.low();
+        low.GetSetEnvelopeInfo_String(
+            env.as_ptr(),
+            flag.key().as_ptr(),
+            value.as_ptr() as *mut _,
+            true,
+        );
+        // Showing or hiding an envelope changes the track heights
+        low.TrackList_AdjustWindows(false);
+        low.UpdateArrange();
+    }
+}
+
+/// The envelope's value at the edit cursor, in the envelope's own units: gain for
+/// volume, where 1.0 is 0dB, -1.0 to 1.0 for pan and width, and 0 to 1.0 for FX
+/// parameters
+pub fn value_at_cursor(reaper: &Reaper, env: TrackEnvelope) -> f64 {
+    let position = reaper.get_cursor_position_ex(CurrentProject).get();
+    let mut value = 0.0;
+    unsafe {
+        let low = reaper.low();
+        low.Envelope_Evaluate(
+            env.as_ptr(),
+            position,
+            0.0,
+            0,
+            &mut value,
+            null_mut(),
+            null_mut(),
+            null_mut(),
+        );
+        low.ScaleFromEnvelopeMode(low.GetEnvelopeScalingMode(env.as_ptr()), value)
+    }
+}
+
+/// Writes a point with `value`, in the units of `value_at_cursor`, at the edit
+/// cursor. A point already there is changed instead, so repeated writes don't pile up.
+pub fn write_at_cursor(reaper: &Reaper, env: TrackEnvelope, value: f64) {
+    let position = reaper.get_cursor_position_ex(CurrentProject).get();
+    unsafe {
+        let low = reaper.low();
+        let mut raw = low.ScaleToEnvelopeMode(low.GetEnvelopeScalingMode(env.as_ptr()), value);
+        let index = low.GetEnvelopePointByTime(env.as_ptr(), position);
+        let mut time = 0.0;
+        let existing = index >= 0
+            && low.GetEnvelopePoint(
+                env.as_ptr(),
+                index,
+                &mut time,
+                null_mut(),
+                null_mut(),
+                null_mut(),
+                null_mut(),
+            )
+            && (time - position).abs() < POINT_TOLERANCE;
+        if existing {
+            low.SetEnvelopePoint(
+                env.as_ptr(),
+                index,
+                null_mut(),
+                &mut raw,
+                null_mut(),
+                null_mut(),
+                null_mut(),
+                null_mut(),
+            );
+        } else {
+            low.InsertEnvelopePoint(env.as_ptr(), position, raw, 0, 0.0, false, null_mut());
+        }
+        low.UpdateArrange();
+        low.Undo_OnStateChange(c_str!("Write envelope point via arpad").as_ptr());
+    }
+}
@@ -0,0 +1,103 @@
+use std::net::SocketAddrV4;
+
+use reaper_low::raw::GUID;
+use reaper_medium::{MediaTrack, Reaper};
+use rosc::{OscMessage, OscPacket, OscType};
+
+use crate::utils::guid_to_string;
+
+/// What the main thread hands to the sender thread. High-rate track feedback travels
+/// as a raw GUID plus typed arguments, so formatting the GUID and address, and
+/// encoding the packet, happen off REAPER's main thread.
+#[derive(Debug)]
+pub enum Feedback {
+    Packet(OscPacket),
+    Track(TrackFeedback),
+    /// A query reply for the client at this address only
+    Reply(SocketAddrV4, OscPacket),
+    /// Ends a run cycle; feedback collected since the previous flush is sent
+    Flush,
+}
+
+impl Feedback {
+    /// Builds the packet to send, or None for a flush; called on the sender thread
+    pub fn into_packet(self) -> Option<OscPacket> {
+        match self {
+            Feedback::Packet(packet) => Some(packet),
+            Feedback::Track(track) => Some(OscPacket::Message(track.into_message())),
+            Feedback::Reply(_, packet) => Some(packet),
+            Feedback::Flush => None,
+        }
+    }
+
+    /// The client a reply is for; other feedback is routed to the surfaces
+    pub fn reply_to(&self) -> Option<SocketAddrV4> {
+        match self {
+            Feedback::Reply(client, _) => Some(*client),
+            _ => None,
+        }
+    }
+}
+
+impl From<OscPacket> for Feedback {
+    fn from(packet: OscPacket) -> Self {
+        Feedback::Packet(packet)
+    }
+}
+
+/// Which lane of the sender thread a poll source's feedback travels in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lane {
+    /// Feedback a performer waits on, such as mute and solo, and query replies
+    Control,
+    /// Continuous meter and clock streams, sent only while no control feedback waits
+    Meters,
+}
+
+/// Track parameters whose feedback is formatted on the sender thread
+#[derive(Debug, Clone, Copy)]
+pub enum TrackParam {
+    Name,
+    Volume,
+    Pan,
+    Mute,
+    Color,
+}
+
+impl TrackParam {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TrackParam::Name => "name",
+            TrackParam::Volume => "volume",
+            TrackParam::Pan => "pan",
+            TrackParam::Mute => "mute",
+            TrackParam::Color => "color",
+        }
+    }
+}
+
+/// Feedback for `/track/{track_guid}/{param}`
+#[derive(Debug)]
+pub struct TrackFeedback {
+    guid: GUID,
+    param: TrackParam,
+    args: Vec<OscType>,
+}
+
+impl TrackFeedback {
+    pub fn new(reaper: &Reaper, track: MediaTrack, param: TrackParam, args: Vec<OscType>) -> Self {
+        let guid = unsafe { reaper.get_set_media_track_info_get_guid(track) };
+        Self { guid, param, args }
+    }
+
+    pub fn into_message(self) -> OscMessage {
+        OscMessage {
+            addr: format!(
+                "/track/{}/{}",
+                guid_to_string(self.guid),
+                self.param.as_str()
+            ),
+            args: self.args,
+        }
+    }
+}
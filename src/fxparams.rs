@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{Reaper, TrackFxLocation};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{
+    TrackFxParamFormattedRoute, TrackFxParamTextArgs, TrackFxParamValueArgs, TrackFxParamValueRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::utils::{get_track_guid, immediate_bundle};
+use crate::OscRoute;
+
+const FX_PARAM_INTERVAL: Duration = Duration::from_millis(100);
+
+// Track GUID, FX index and parameter index
+type ParamKey = (String, u32, u32);
+
+/// Sends the value and formatted value of every FX parameter that changed since the
+/// last poll, whether it was changed from a surface, the plugin UI or automation
+pub struct FxParamPollSource {
+    reaper: Reaper,
+    last_poll: Instant,
+    // None until the first poll, which records the values without sending them
+    prev: Option<HashMap<ParamKey, f64>>,
+}
+
+impl FxParamPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_poll: Instant::now(),
+            prev: None,
+        }
+    }
+}
+
+impl PollSource for FxParamPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_poll.elapsed() < FX_PARAM_INTERVAL {
+            return Ok(());
+        }
+        self.last_poll = Instant::now();
+        let reaper = &self.reaper;
+        let mut values = HashMap::new();
+        let mut changed = Vec::new();
+        for track in (0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+        {
+            let guid = get_track_guid(reaper, track);
+            for fx_index in 0..unsafe { reaper.track_fx_get_count(track) } {
+                let fx = TrackFxLocation::NormalFxChain(fx_index);
+                for param_index in 0..unsafe { reaper.track_fx_get_num_params(track, fx) } {
+                    let value =
+                        unsafe { reaper.track_fx_get_param_normalized(track, fx, param_index) }
+                            .get();
+                    let key = (guid.clone(), fx_index, param_index);
+                    if let Some(prev) = &self.prev {
+                        if prev.get(&key) != Some(&value) {
+                            changed.push((track, fx_index, param_index, value));
+                        }
+                    }
+                    values.insert(key, value);
+                }
+            }
+        }
+        self.prev = Some(values);
+        for (track, fx_index, param_index, value) in changed {
+            let mut content = vec![OscPacket::Message(TrackFxParamValueRoute::build_message(
+                TrackFxParamValueArgs {
+                    track,
+                    fx_index,
+                    param_index,
+                    value,
+                },
+                reaper,
+            ))];
+            if let Some(text) =
+                TrackFxParamFormattedRoute::formatted_value(reaper, track, fx_index, param_index)
+            {
+                content.push(OscPacket::Message(
+                    TrackFxParamFormattedRoute::build_message(
+                        TrackFxParamTextArgs {
+                            track,
+                            fx_index,
+                            param_index,
+                            text,
+                        },
+                        reaper,
+                    ),
+                ));
+            }
+            osc_sender
+                .send(immediate_bundle(content).into())
+                .map_err(PollError::Send)?;
+        }
+        Ok(())
+    }
+}
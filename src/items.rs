@@ -0,0 +1,58 @@
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+use c_str_macro::c_str;
+use reaper_medium::{MediaItem, MediaTrack, Reaper};
+
+use crate::RouteError;
+
+/// Item properties reachable from a surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemAttribute {
+    Mute,
+    Position,
+    Selected,
+}
+
+impl ItemAttribute {
+    // GetMediaItemInfo_Value key
+    fn key(&self) -> &'static CStr {
+        match self {
+            ItemAttribute::Mute => c_str!("B_MUTE"),
+            ItemAttribute::Position => c_str!("D_POSITION"),
+            ItemAttribute::Selected => c_str!("B_UISEL"),
+        }
+    }
+
+    fn undo_description(&self) -> &'static CStr {
+        match self {
+            ItemAttribute::Mute => c_str!("Mute item via arpad"),
+            ItemAttribute::Position => c_str!("Move item via arpad"),
+            ItemAttribute::Selected => c_str!("Select item via arpad"),
+        }
+    }
+}
+
+/// The item at `index` on the track, counting from the earliest
+pub fn item(reaper: &Reaper, track: MediaTrack, index: u32) -> Result<MediaItem, RouteError> {
+    let item = unsafe { reaper.low().GetTrackMediaItem(track.as_ptr(), index as i32) };
+    NonNull::new(item).ok_or_else(|| RouteError::ValueNotFound(format!("Item {}", index)))
+}
+
+pub fn get(reaper: &Reaper, item: MediaItem, attribute: ItemAttribute) -> f64 {
+    unsafe {
+        reaper
+            .low()
+            .GetMediaItemInfo_Value(item.as_ptr(), attribute.key().as_ptr())
+    }
+}
+
+/// Changes an item as an edit in the arrange view would, adding an undo point
+pub fn set(reaper: &Reaper, item: MediaItem, attribute: ItemAttribute, value: f64) {
+    unsafe {
+        let low = reaper.low();
+        low.SetMediaItemInfo_Value(item.as_ptr(), attribute.key().as_ptr(), value);
+        low.UpdateArrange();
+        low.Undo_OnStateChange(attribute.undo_description().as_ptr());
+    }
+}
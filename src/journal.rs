@@ -0,0 +1,157 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender};
+use reaper_medium::Reaper;
+use rosc::{OscMessage, OscType};
+use serde::Deserialize;
+
+use crate::clients;
+use crate::project;
+use crate::surfaces;
+
+// Journal of projects that were never saved, in the REAPER resource directory
+const UNSAVED_JOURNAL_NAME: &str = "arpad-journal.log";
+const JOURNAL_EXTENSION: &str = "arpad.log";
+// Lines are written in batches, at most this long after they were recorded
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The `[journal]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    /// Whether every message changing the project is appended to a log next to the
+    /// project file
+    pub enabled: bool,
+}
+
+// Lines on their way to the writer thread, with the journal each belongs in; unset
+// while the journal is off
+static WRITER: OnceLock<Sender<(PathBuf, String)>> = OnceLock::new();
+
+/// Starts the thread writing the journal when it's enabled, so fader moves don't wait
+/// on the disk on REAPER's main thread
+pub fn init(config: JournalConfig) {
+    if !config.enabled {
+        return;
+    }
+    let (sender, receiver) = unbounded::<(PathBuf, String)>();
+    if WRITER.set(sender).is_err() {
+        return;
+    }
+    thread::spawn(move || {
+        // The journal written last, kept open between lines
+        let mut file: Option<(PathBuf, BufWriter<File>)> = None;
+        loop {
+            let (path, line) = match receiver.recv_timeout(FLUSH_INTERVAL) {
+                Ok(entry) => entry,
+                Err(RecvTimeoutError::Timeout) => {
+                    flush(&mut file);
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            // Another project became active since the last line
+            if file.as_ref().is_none_or(|(open, _)| *open != path) {
+                flush(&mut file);
+                file = match OpenOptions::new().create(true).append(true).open(&path) {
+                    Ok(opened) => Some((path, BufWriter::new(opened))),
+                    Err(e) => {
+                        eprintln!("Failed to open journal {}: {}", path.display(), e);
+                        None
+                    }
+                };
+            }
+            if let Some((path, writer)) = file.as_mut() {
+                if let Err(e) = writer.write_all(line.as_bytes()) {
+                    eprintln!("Failed to write journal {}: {}", path.display(), e);
+                }
+            }
+        }
+        flush(&mut file);
+    });
+}
+
+fn flush(file: &mut Option<(PathBuf, BufWriter<File>)>) {
+    if let Some((path, writer)) = file.as_mut() {
+        if let Err(e) = writer.flush() {
+            eprintln!("Failed to write journal {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// The active project's journal: "Show.arpad.log" next to "Show.rpp", or
+/// `arpad-journal.log` in the REAPER resource directory while the project is unsaved
+fn path(reaper: &Reaper) -> PathBuf {
+    match project::project_file(reaper) {
+        Some(file) => file.with_extension(JOURNAL_EXTENSION),
+        None => reaper.get_resource_path(|path| path.join(UNSAVED_JOURNAL_NAME)),
+    }
+}
+
+// `time` in UTC, e.g. "2026-10-16 21:43:05.120Z", without a date library
+fn format_utc(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+    // Civil date from days since 1970-01-01, after Howard Hinnant's civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+fn format_arg(arg: &OscType) -> String {
+    match arg {
+        OscType::Int(i) => i.to_string(),
+        OscType::Long(l) => l.to_string(),
+        OscType::Float(f) => f.to_string(),
+        OscType::Double(d) => d.to_string(),
+        OscType::Bool(b) => b.to_string(),
+        OscType::String(s) => format!("{:?}", s),
+        OscType::Blob(b) => format!("<{} bytes>", b.len()),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Appends a message that changed the project to the journal, with the time, the
+/// client that sent it and the surface it came through. Columns are tab-separated.
+/// The line is written on the journal's own thread.
+pub fn record(reaper: &Reaper, msg: &OscMessage) {
+    let Some(writer) = WRITER.get() else {
+        return;
+    };
+    let client = clients::current_client().map_or("-".to_string(), |c| c.to_string());
+    let surface = surfaces::profile_for_namespace(&surfaces::current_namespace())
+        .map_or("-".to_string(), |p| p.name.clone());
+    let args: Vec<String> = msg.args.iter().map(format_arg).collect();
+    let line = format!(
+        "{}\t{}\t{}\t{}\t{}\n",
+        format_utc(SystemTime::now()),
+        client,
+        surface,
+        msg.addr,
+        args.join(" ")
+    );
+    let _ = writer.send((path(reaper), line));
+}
@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper, TrackFxLocation};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{
+    LastTouchedParamNameParams, LastTouchedParamNameRoute, LastTouchedParamValueParams,
+    LastTouchedParamValueRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::utils::immediate_bundle;
+use crate::OscRoute;
+
+const LAST_TOUCHED_INTERVAL: Duration = Duration::from_millis(50);
+
+// GetLastTouchedFX marks FX in the input chain, or the monitoring chain on the master
+// track, with this bit of the FX number
+const INPUT_FX_FLAG: i32 = 0x1000000;
+
+/// The FX parameter most recently changed in REAPER, whether from a plugin UI, an
+/// envelope or a surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LastTouched {
+    pub track: MediaTrack,
+    pub fx: TrackFxLocation,
+    pub param_index: u32,
+}
+
+/// The last touched parameter, or None when nothing was touched yet or it was on
+/// take FX, which arpad doesn't address
+pub fn get(reaper: &Reaper) -> Option<LastTouched> {
+    let mut track_number = 0;
+    let mut fx_number = 0;
+    let mut param_number = 0;
+    let touched = unsafe {
+        reaper
+            .low()
+            .GetLastTouchedFX(&mut track_number, &mut fx_number, &mut param_number)
+    };
+    // The high word of the track number is the item number for take FX
+    if !touched || track_number >> 16 != 0 {
+        return None;
+    }
+    let track = match track_number {
+        0 => reaper.get_master_track(CurrentProject),
+        n => reaper.get_track(CurrentProject, n as u32 - 1)?,
+    };
+    let fx = if fx_number & INPUT_FX_FLAG != 0 {
+        TrackFxLocation::InputFxChain((fx_number & !INPUT_FX_FLAG) as u32)
+    } else {
+        TrackFxLocation::NormalFxChain(fx_number as u32)
+    };
+    let param_index = u32::try_from(param_number).ok()?;
+    // The FX may have been removed since it was touched
+    if param_index >= unsafe { reaper.track_fx_get_num_params(track, fx) } {
+        return None;
+    }
+    Some(LastTouched {
+        track,
+        fx,
+        param_index,
+    })
+}
+
+/// Sends the name and value of the last touched parameter when another parameter is
+/// touched, and its value when it changes, so one encoder can follow whatever was
+/// touched last
+pub struct LastTouchedPollSource {
+    reaper: Reaper,
+    last_poll: Instant,
+    prev: Option<(LastTouched, f64)>,
+}
+
+impl LastTouchedPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_poll: Instant::now(),
+            prev: None,
+        }
+    }
+}
+
+impl PollSource for LastTouchedPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_poll.elapsed() < LAST_TOUCHED_INTERVAL {
+            return Ok(());
+        }
+        self.last_poll = Instant::now();
+        let reaper = &self.reaper;
+        let Some(touched) = get(reaper) else {
+            self.prev = None;
+            return Ok(());
+        };
+        let value = unsafe {
+            reaper.track_fx_get_param_normalized(touched.track, touched.fx, touched.param_index)
+        }
+        .get();
+        let prev = self.prev.replace((touched, value));
+        let moved = prev.is_none_or(|(prev, _)| prev != touched);
+        if !moved && prev.is_some_and(|(_, prev)| prev == value) {
+            return Ok(());
+        }
+        let mut content = Vec::new();
+        if moved {
+            if let Ok(params) = LastTouchedParamNameRoute::collect_send_params(
+                &LastTouchedParamNameParams {},
+                reaper,
+            ) {
+                content.push(OscPacket::Message(
+                    LastTouchedParamNameRoute::build_message(params, reaper),
+                ));
+            }
+        }
+        content.push(OscPacket::Message(
+            LastTouchedParamValueRoute::build_message(
+                (LastTouchedParamValueParams {}, value),
+                reaper,
+            ),
+        ));
+        osc_sender
+            .send(immediate_bundle(content).into())
+            .map_err(PollError::Send)
+    }
+}
@@ -1,6 +1,6 @@
+use std::cell::RefCell;
 use std::error::Error;
-use std::net::{SocketAddrV4, UdpSocket};
-use std::str::FromStr;
+use std::net::{SocketAddr, SocketAddrV4, UdpSocket};
 use std::sync::OnceLock;
 
 use reaper_low::PluginContext;
@@ -12,23 +12,156 @@ use reaper_medium::{
 
 use fragile::Fragile;
 
-use rosc::{encoder, OscMessage, OscPacket};
+use rosc::{OscMessage, OscPacket};
 
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_channel::{bounded, select_biased, Receiver, Sender};
 use std::thread;
 
+mod actions;
+
+mod announce;
+
+mod autocolor;
+
+mod bank;
+use bank::BankPollSource;
+
+mod batch;
+use batch::OutgoingBatch;
+
+mod bundles;
+use bundles::BundleQueue;
+
+mod buttons;
+use buttons::Switch;
+
+mod click;
+
+mod clients;
+
+mod clock;
+use clock::ClockPollSource;
+
+mod config;
+use config::Config;
+
+mod cuemix;
+
+mod destinations;
+
+mod envelopes;
+
+mod feedback;
+use feedback::Feedback;
+
+mod fxparams;
+use fxparams::FxParamPollSource;
+
+mod items;
+
+mod journal;
+
+mod lasttouched;
+use lasttouched::LastTouchedPollSource;
+
+mod lockout;
+
+mod markers;
+
+mod master;
+use master::MasterMonoPollSource;
+
+mod metering;
+use metering::{InputPeakPollSource, LoudnessPollSource, MaxPeakPollSource};
+
+mod midi;
+use midi::MidiActivityPollSource;
+
+mod monitor;
+
+mod monitorfx;
+
 mod utils;
-use utils::{get_track_by_guid, get_track_guid, get_track_idx};
+use utils::{
+    get_track_by_guid, get_track_guid, get_track_idx, immediate_bundle, volume_to_mode_value,
+};
+
+#[macro_use]
+mod route_macro;
+
+mod route_registry;
+use route_registry::RouteRegistry;
 
 mod osc_routes;
 use osc_routes::*;
 
+mod pickup;
+
+mod playhead;
+use playhead::PlayPositionPollSource;
+
+mod playrate;
+use playrate::PreservePitchPollSource;
+
 mod polling;
 use polling::*;
 
+mod position;
+use position::PositionPollSource;
+
+mod project;
+use project::{ProjectDirtyPollSource, ProjectPollSource};
+
+mod quantize;
+use quantize::QuantizedTransportPollSource;
+
+mod registry;
+
+mod render;
+use render::RenderPollSource;
+
+mod sends;
+use sends::SendSwitchPollSource;
+
+mod setlist;
+use setlist::CurrentCuePollSource;
+
+mod settings;
+
+mod smoothing;
+use smoothing::SmoothingPollSource;
+
+mod solo;
+
+mod stats;
+
+mod strip;
+
+mod subscriptions;
+
+mod surfaces;
+
+mod sync;
+
+mod tags;
+
+mod talkback;
+
+mod tempo;
+
+mod tracks;
+
+mod transfer;
+
+mod undo;
+
+mod vu;
+use vu::VuPollSource;
+
 #[derive(Debug)]
 pub enum RouteError {
     GuidNotFound(String),
+    AmbiguousGuid(String),
     ValueNotFound(String),
 }
 
@@ -55,6 +188,7 @@ impl std::fmt::Display for RouteError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             RouteError::GuidNotFound(guid) => write!(f, "GUID not found: {}", guid),
+            RouteError::AmbiguousGuid(guid) => write!(f, "GUID matches several tracks: {}", guid),
             RouteError::ValueNotFound(value) => write!(f, "Value not found: {}", value),
         }
     }
@@ -64,6 +198,24 @@ pub(crate) trait OscRoute {
     type SendParams;
     type ReceiveParams;
 
+    /// Whether incoming values still apply while the plugin is locked out. Only routes
+    /// that can't change the project, like the lockout itself, set this.
+    const ALLOWED_IN_LOCKOUT: bool = false;
+
+    /// Whether changes are recorded for `/arpad/undo-last`. Routes driving the
+    /// transport, navigation or arpad's own settings rather than the project unset this,
+    /// as do triggers and momentary switches, which have no value to set back.
+    const UNDOABLE: bool = true;
+
+    /// Whether the route only reports a value, as documented with `@readonly`. Messages
+    /// to it change nothing, so they aren't journaled or undone.
+    const READONLY: bool = false;
+
+    /// Whether receiving can block REAPER's main thread, e.g. in a modal dialog or a
+    /// render. Feedback collected so far is sent first, instead of waiting for the end
+    /// of a run cycle that only comes once the block is over.
+    const MAY_BLOCK: bool = false;
+
     fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams>;
     fn receive(
         params: Self::ReceiveParams,
@@ -76,13 +228,28 @@ pub(crate) trait OscRoute {
         params: &Self::ReceiveParams,
         reaper: &Reaper,
     ) -> Result<Self::SendParams, RouteError>;
+    /// Build the packet answering a query. Routes answering with more than one
+    /// message override this to reply with a bundle.
+    fn build_packet(params: Self::SendParams, reaper: &Reaper) -> OscPacket {
+        OscPacket::Message(Self::build_message(params, reaper))
+    }
+    /// Build the packets answering a query, each sent whole. Routes whose answers can
+    /// outgrow a datagram override this to split them.
+    fn build_reply(params: Self::SendParams, reaper: &Reaper) -> Vec<OscPacket> {
+        vec![Self::build_packet(params, reaper)]
+    }
+    /// Build feedback for the sender thread. Routes sending high-rate feedback override
+    /// this so the sender thread formats their addresses.
+    fn build_feedback(params: Self::SendParams, reaper: &Reaper) -> Feedback {
+        Feedback::Packet(OscPacket::Message(Self::build_message(params, reaper)))
+    }
 }
 
 fn dispatch_route<T: OscRoute>(
     segments: &[&str],
     msg: &OscMessage,
     reaper: &Reaper,
-    osc_sender: &Sender<OscPacket>,
+    osc_sender: &Sender<Feedback>,
 ) {
     let is_query = segments.last() == Some(&"?");
     let match_segments = if is_query {
@@ -95,31 +262,96 @@ fn dispatch_route<T: OscRoute>(
         if is_query {
             match T::collect_send_params(&params, reaper) {
                 Ok(send_params) => {
-                    let response_msg = T::build_message(send_params, reaper);
-                    osc_sender.send(OscPacket::Message(response_msg)).unwrap();
+                    for packet in T::build_reply(send_params, reaper) {
+                        // Answer only the surface that asked
+                        let response = surfaces::namespaced(&surfaces::current_namespace(), packet);
+                        let feedback = match clients::reply_address() {
+                            Some(client) => Feedback::Reply(client, response),
+                            None => response.into(),
+                        };
+                        osc_sender.send(feedback).unwrap();
+                    }
                 }
                 Err(e) => {
                     eprintln!("Query failed: {:?}", e);
                 }
             }
-        } else {
-            T::receive(params, msg, reaper).unwrap_or_else(|e| {
-                eprintln!("Receive failed: {:?}", e);
-            });
+        } else if !surfaces::permits(match_segments) {
+            eprintln!(
+                "Surface not permitted to change /{}",
+                match_segments.join("/")
+            );
+        } else if !lockout::is_locked() || T::ALLOWED_IN_LOCKOUT {
+            // The value from before the change, to set it back on /arpad/undo-last
+            let restore = (T::UNDOABLE && !T::ALLOWED_IN_LOCKOUT && !T::READONLY)
+                .then(|| T::collect_send_params(&params, reaper).ok())
+                .flatten()
+                .and_then(|answer| {
+                    undo::restore_message(T::build_packet(answer, reaper), msg, |addr| {
+                        T::matcher(&parse_osc_address(addr)).is_some()
+                    })
+                });
+            if T::MAY_BLOCK {
+                osc_sender.send(Feedback::Flush).unwrap();
+            }
+            match T::receive(params, msg, reaper) {
+                // Routes allowed in lockout can't change the project, and read-only ones
+                // change nothing, so they aren't journaled or undone
+                Ok(()) if !T::ALLOWED_IN_LOCKOUT && !T::READONLY => {
+                    journal::record(reaper, msg);
+                    if let Some(restore) = restore {
+                        undo::record(restore);
+                    }
+                }
+                Ok(()) => {}
+                Err(e) => eprintln!("Receive failed: {:?}", e),
+            }
         }
     }
 }
 
 struct ArpadSurface {
-    osc_sender: Sender<OscPacket>,
-    sock: UdpSocket,
+    osc_sender: Sender<Feedback>,
     reaper: Reaper,
-    poll_manager: PollManager,
 }
 
 impl ArpadSurface {
     fn send(&self, msg: OscMessage) {
-        self.osc_sender.send(OscPacket::Message(msg)).unwrap();
+        self.send_feedback(Feedback::Packet(OscPacket::Message(msg)));
+    }
+
+    fn send_feedback(&self, feedback: Feedback) {
+        self.osc_sender.send(feedback).unwrap();
+    }
+
+    fn announce(&self, sentence: String) {
+        self.send(AnnounceRoute::build_message(sentence, &self.reaper));
+    }
+
+    fn is_master(&self, track: reaper_medium::MediaTrack) -> bool {
+        track == self.reaper.get_master_track(CurrentProject)
+    }
+
+    // Like track volume, the master volume is converted to each surface's volume mode
+    fn send_master_volume(&self, volume: reaper_medium::ReaperVolumeValue) {
+        let build = || {
+            let value = volume_to_mode_value(&self.reaper, surfaces::volume_mode(), volume);
+            OscPacket::Message(MasterVolumeRoute::build_message(
+                (MasterVolumeParams {}, value),
+                &self.reaper,
+            ))
+        };
+        if let Some(namespace) = surfaces::shared_volume_namespace() {
+            let packet = {
+                let _surface = surfaces::enter(namespace);
+                build()
+            };
+            self.send_feedback(Feedback::Packet(packet));
+            return;
+        }
+        for packet in surfaces::per_surface(build) {
+            self.osc_sender.send(packet.into()).unwrap();
+        }
     }
 }
 
@@ -127,26 +359,117 @@ impl std::fmt::Debug for ArpadSurface {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("ArpadSurface")
             .field("osc_sender", &"...")
-            .field("sock", &"...")
             .field("reaper", &"...")
-            .field("poll_manager", &"[PollManager omitted]")
             .finish()
     }
 }
 
+/// Drains the socket and runs the poll sources. Driven both by the control surface
+/// run loop and by a REAPER timer, so incoming control keeps working while the run
+/// loop is starved (e.g. behind modal dialogs).
+struct Runtime {
+    osc_sender: Sender<Feedback>,
+    meter_sender: Sender<Feedback>,
+    sock: UdpSocket,
+    reaper: Reaper,
+    poll_manager: PollManager,
+    routes: RouteRegistry,
+    bundles: BundleQueue,
+}
+
+impl Runtime {
+    fn tick(&mut self) {
+        self.poll_manager
+            .poll_all(&self.osc_sender, &self.meter_sender);
+        for bundle in self.bundles.take_due() {
+            handle_packet(
+                &self.reaper,
+                OscPacket::Bundle(bundle),
+                &self.routes,
+                &mut self.bundles,
+                &self.osc_sender,
+            );
+        }
+        let mut buf = [0u8; rosc::decoder::MTU];
+        loop {
+            match self.sock.recv_from(&mut buf) {
+                Ok((size, addr)) => {
+                    if let Ok((_addr, packet)) = rosc::decoder::decode_udp(&buf[..size]) {
+                        monitor::log_incoming(&self.reaper, &self.sock, &packet);
+                        stats::count_incoming(&packet);
+                        let client = match addr {
+                            SocketAddr::V4(addr) => Some(addr),
+                            SocketAddr::V6(_) => None,
+                        };
+                        let _client = clients::enter(client);
+                        handle_packet(
+                            &self.reaper,
+                            packet,
+                            &self.routes,
+                            &mut self.bundles,
+                            &self.osc_sender,
+                        );
+                    }
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    // No data available, exit loop
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("OSC receive error: {:?}", e);
+                    break;
+                }
+            }
+        }
+        if sync::take_request_all() {
+            // Sent as shared feedback, reaching every surface and client
+            let _client = clients::enter(None);
+            let _surface = surfaces::enter("");
+            sync::queue_state(&self.reaper);
+        }
+        send_state(&self.reaper, &self.routes, &self.osc_sender);
+        monitor::flush(&self.reaper);
+        stats::update();
+        self.osc_sender.send(Feedback::Flush).unwrap();
+    }
+}
+
+static RUNTIME: OnceLock<Fragile<RefCell<Runtime>>> = OnceLock::new();
+
+fn tick() {
+    let Some(runtime) = RUNTIME.get() else {
+        return;
+    };
+    // A route can open a modal dialog, inside which REAPER keeps firing the timer;
+    // skip those nested ticks instead of re-entering the dispatcher, apart from
+    // reporting on a render
+    match runtime.get().try_borrow_mut() {
+        Ok(mut runtime) => runtime.tick(),
+        Err(_) => render::report_progress(),
+    }
+}
+
+extern "C" fn timer_tick() {
+    tick();
+}
+
 impl ControlSurface for ArpadSurface {
     fn set_track_list_change(&self) {
+        registry::invalidate();
         for i in 0..self.reaper.count_tracks(CurrentProject) {
             let track = self.reaper.get_track(CurrentProject, i).unwrap();
             let track_idx = get_track_idx(&self.reaper, track);
             self.osc_sender
-                .send(OscPacket::Message(TrackIndexRoute::build_message(
-                    TrackIndexArgs {
-                        track,
-                        index: track_idx as i32,
-                    },
-                    &self.reaper,
-                )))
+                .send(
+                    OscPacket::Message(TrackIndexRoute::build_message(
+                        TrackIndexArgs {
+                            track,
+                            index: track_idx as i32,
+                        },
+                        &self.reaper,
+                    ))
+                    .into(),
+                )
                 .unwrap();
             unsafe {
                 for i in 0..self
@@ -162,14 +485,17 @@ impl ControlSurface for ArpadSurface {
                         )
                         .unwrap();
                     self.osc_sender
-                        .send(OscPacket::Message(TrackSendGuidRoute::build_message(
-                            TrackSendGuidArgs {
-                                track,
-                                send_index: i as i32,
-                                send_guid: get_track_guid(&self.reaper, dest),
-                            },
-                            &self.reaper,
-                        )))
+                        .send(
+                            OscPacket::Message(TrackSendGuidRoute::build_message(
+                                TrackSendGuidArgs {
+                                    track,
+                                    send_index: i as i32,
+                                    send_guid: get_track_guid(&self.reaper, dest),
+                                },
+                                &self.reaper,
+                            ))
+                            .into(),
+                        )
                         .unwrap();
                 }
             }
@@ -177,7 +503,8 @@ impl ControlSurface for ArpadSurface {
     }
     // This is also called when track color changes!
     fn set_track_title(&self, args: reaper_medium::SetTrackTitleArgs) {
-        self.send(osc_routes::TrackNameRoute::build_message(
+        autocolor::track_named(&self.reaper, args.track, args.name.to_str());
+        self.send_feedback(osc_routes::TrackNameRoute::build_feedback(
             TrackNameArgs {
                 track: args.track,
                 name: args.name.to_string(),
@@ -189,7 +516,7 @@ impl ControlSurface for ArpadSurface {
                 .get_set_media_track_info_get_custom_color(args.track)
                 .color
         };
-        self.send(osc_routes::TrackColorRoute::build_message(
+        self.send_feedback(osc_routes::TrackColorRoute::build_feedback(
             TrackColorArgs {
                 track: args.track,
                 color: color.to_raw(),
@@ -198,113 +525,610 @@ impl ControlSurface for ArpadSurface {
         ));
     }
     fn set_surface_volume(&self, args: reaper_medium::SetSurfaceVolumeArgs) {
-        self.send(osc_routes::TrackVolumeRoute::build_message(
+        if self.is_master(args.track) {
+            self.send_master_volume(args.volume);
+        }
+        if let Some(namespace) = surfaces::shared_volume_namespace() {
+            let feedback = {
+                let _surface = surfaces::enter(namespace);
+                osc_routes::TrackVolumeRoute::build_feedback(args, &self.reaper)
+            };
+            self.send_feedback(feedback);
+            return;
+        }
+        for packet in surfaces::per_surface(|| {
+            OscPacket::Message(TrackVolumeRoute::build_message(args, &self.reaper))
+        }) {
+            self.osc_sender.send(packet.into()).unwrap();
+        }
+    }
+    fn set_surface_pan(&self, args: reaper_medium::SetSurfacePanArgs) {
+        if self.is_master(args.track) {
+            self.send(MasterPanRoute::build_message(
+                (MasterPanParams {}, args.pan.get()),
+                &self.reaper,
+            ));
+        }
+        self.send_feedback(osc_routes::TrackPanRoute::build_feedback(
             args,
             &self.reaper,
         ));
-    }
-    fn set_surface_pan(&self, args: reaper_medium::SetSurfacePanArgs) {
-        self.send(osc_routes::TrackPanRoute::build_message(args, &self.reaper));
+        for msg in osc_routes::pan_mode_feedback(&self.reaper, args.track) {
+            self.send(msg);
+        }
     }
     fn set_surface_mute(&self, args: reaper_medium::SetSurfaceMuteArgs) {
-        self.send(osc_routes::TrackMuteRoute::build_message(
+        if self.is_master(args.track) {
+            self.send(MasterSwitchRoute::build_message(
+                MasterSwitchArgs {
+                    switch: Switch::Mute,
+                    on: args.is_mute,
+                },
+                &self.reaper,
+            ));
+        }
+        self.send_feedback(osc_routes::TrackMuteRoute::build_feedback(
             args,
             &self.reaper,
         ));
+        self.announce(announce::track_flag(
+            &self.reaper,
+            args.track,
+            "muted",
+            "unmuted",
+            args.is_mute,
+        ));
+    }
+    fn set_surface_solo(&self, args: reaper_medium::SetSurfaceSoloArgs) {
+        if self.is_master(args.track) {
+            self.send(MasterSwitchRoute::build_message(
+                MasterSwitchArgs {
+                    switch: Switch::Solo,
+                    on: args.is_solo,
+                },
+                &self.reaper,
+            ));
+        }
+        self.announce(announce::track_flag(
+            &self.reaper,
+            args.track,
+            "soloed",
+            "unsoloed",
+            args.is_solo,
+        ));
+    }
+    fn set_surface_rec_arm(&self, args: reaper_medium::SetSurfaceRecArmArgs) {
+        self.announce(announce::track_flag(
+            &self.reaper,
+            args.track,
+            "armed",
+            "disarmed",
+            args.is_armed,
+        ));
+    }
+    fn set_auto_mode(&self, _: reaper_medium::SetAutoModeArgs) {
+        // REAPER doesn't say which track changed, so every track's mode is sent
+        for track in (0..self.reaper.count_tracks(CurrentProject))
+            .filter_map(|i| self.reaper.get_track(CurrentProject, i))
+        {
+            self.send(TrackAutomationModeRoute::build_message(
+                TrackAutomationModeRoute::collect(&self.reaper, track),
+                &self.reaper,
+            ));
+        }
+    }
+    fn set_play_state(&self, args: reaper_medium::SetPlayStateArgs) {
+        let state = reaper_medium::PlayState {
+            is_playing: args.is_playing,
+            is_paused: args.is_paused,
+            is_recording: args.is_recording,
+        };
+        self.send_feedback(
+            immediate_bundle(
+                TransportControl::ALL
+                    .iter()
+                    .map(|&control| {
+                        OscPacket::Message(TransportRoute::build_message(
+                            TransportArgs {
+                                control,
+                                on: control.is_on(state),
+                            },
+                            &self.reaper,
+                        ))
+                    })
+                    .collect(),
+            )
+            .into(),
+        );
+        self.announce(announce::play_state(args));
+    }
+    fn set_repeat_state(&self, args: reaper_medium::SetRepeatStateArgs) {
+        self.send(TransportRepeatRoute::build_message(
+            (TransportRepeatParams {}, args.is_enabled),
+            &self.reaper,
+        ));
+    }
+    fn ext_set_bpm_and_play_rate(&self, args: reaper_medium::ExtSetBpmAndPlayRateArgs) -> i32 {
+        if let Some(bpm) = args.tempo {
+            self.send(ProjectTempoRoute::build_message(
+                (ProjectTempoParams {}, bpm.get()),
+                &self.reaper,
+            ));
+        }
+        if let Some(rate) = args.play_rate {
+            self.send(TransportPlayRateRoute::build_message(
+                (TransportPlayRateParams {}, rate.get()),
+                &self.reaper,
+            ));
+        }
+        1
+    }
+    fn ext_set_fx_enabled(&self, args: reaper_medium::ExtSetFxEnabledArgs) -> i32 {
+        if let reaper_medium::TrackFxLocation::NormalFxChain(fx_index) = args.fx_location {
+            self.send(TrackFxEnabledRoute::build_message(
+                (
+                    TrackFxEnabledParams {
+                        track_guid: get_track_guid(&self.reaper, args.track),
+                        fx_index,
+                    },
+                    args.is_enabled,
+                ),
+                &self.reaper,
+            ));
+        }
+        1
+    }
+    fn ext_set_fx_change(&self, args: reaper_medium::ExtSetFxChangeArgs) -> i32 {
+        for msg in osc_routes::fx_chain_feedback(&self.reaper, args.track) {
+            self.send(msg);
+        }
+        1
+    }
+    fn set_surface_selected(&self, args: reaper_medium::SetSurfaceSelectedArgs) {
+        if args.is_selected {
+            let track_idx = get_track_idx(&self.reaper, args.track);
+            // The master track reports index 0, regular tracks start at 1
+            if track_idx > 0 {
+                bank::center_on(track_idx - 1, self.reaper.count_tracks(CurrentProject));
+            }
+        }
     }
     fn run(&mut self) {
-        self.poll_manager.poll_all(&self.osc_sender);
-        let mut buf = [0u8; rosc::decoder::MTU];
+        tick();
+    }
+}
+
+// Spawn the OSC sending thread. Control feedback always goes before meters, so a flood
+// of meter data can't hold up mute or solo feedback.
+fn start_sender_thread(
+    sock: UdpSocket,
+    osc_receiver: Receiver<Feedback>,
+    meter_receiver: Receiver<Feedback>,
+) {
+    thread::spawn(move || {
+        let mut batch = OutgoingBatch::default();
         loop {
-            match self.sock.recv_from(&mut buf) {
-                Ok((size, _addr)) => {
-                    if let Ok((_addr, packet)) = rosc::decoder::decode_udp(&buf[..size]) {
-                        handle_packet(self.reaper.clone(), packet, &self.osc_sender);
+            let feedback = select_biased! {
+                recv(osc_receiver) -> feedback => feedback,
+                recv(meter_receiver) -> feedback => feedback,
+            };
+            let Ok(feedback) = feedback else {
+                break;
+            };
+            let reply_to = feedback.reply_to();
+            let Some(msg) = feedback.into_packet() else {
+                // Meters queued before the flush go out with it rather than a cycle late
+                while let Ok(meter) = meter_receiver.try_recv() {
+                    let reply_to = meter.reply_to();
+                    if let Some(msg) = meter.into_packet() {
+                        queue_outgoing(&sock, &mut batch, reply_to, msg);
                     }
                 }
-                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                    // No data available, exit loop
-                    break;
-                }
-                Err(e) => {
-                    eprintln!("OSC receive error: {:?}", e);
-                    break;
+                surfaces::refresh_destinations();
+                // The other destinations are told when one becomes unreachable
+                for (dest, error) in batch.flush(&sock) {
+                    let notice =
+                        OscPacket::Message(destinations::unreachable_message(dest, &error));
+                    for (to, packet) in surfaces::route_outgoing(&notice) {
+                        if to != dest {
+                            batch.push(to, packet);
+                        }
+                    }
                 }
-            }
+                continue;
+            };
+            queue_outgoing(&sock, &mut batch, reply_to, msg);
         }
-    }
+    });
 }
 
-// Spawn the OSC sending thread
-fn start_sender_thread(dev_addr: SocketAddrV4, sock: UdpSocket, osc_receiver: Receiver<OscPacket>) {
-    thread::spawn(move || {
-        for msg in osc_receiver.iter() {
-            if let Ok(buf) = encoder::encode(&msg) {
-                let _ = sock.send_to(buf.as_slice(), dev_addr);
+fn queue_outgoing(
+    sock: &UdpSocket,
+    batch: &mut OutgoingBatch,
+    reply_to: Option<SocketAddrV4>,
+    msg: OscPacket,
+) {
+    match reply_to {
+        Some(client) => {
+            batch.push(client, msg.clone());
+            for dest in surfaces::echo_destinations() {
+                if *dest != client {
+                    batch.push(*dest, msg.clone());
+                }
             }
         }
-    });
+        None => {
+            for (dest, packet) in surfaces::route_outgoing(&msg) {
+                batch.push(dest, packet);
+            }
+        }
+    }
+    monitor::log_outgoing(sock, &msg);
+    stats::count_outgoing(&msg);
 }
 
 fn parse_osc_address(addr: &str) -> Vec<&str> {
     addr.split('/').filter(|s| !s.is_empty()).collect()
 }
 
-fn handle_packet(reaper: Reaper, packet: OscPacket, osc_sender: &Sender<OscPacket>) {
+/// Dispatches a message, or the contents of a bundle in order. Bundles with a future
+/// timetag, nested ones included, are queued and dispatched once they are due.
+fn handle_packet(
+    reaper: &Reaper,
+    packet: OscPacket,
+    routes: &RouteRegistry,
+    bundles: &mut BundleQueue,
+    osc_sender: &Sender<Feedback>,
+) {
     match packet {
         OscPacket::Message(msg) => {
-            println!("OSC message: {:?}", msg);
-            let segments = parse_osc_address(&msg.addr);
-            dispatch_route::<TrackNameRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackSelectedRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackVolumeRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackPanRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackMuteRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackSoloRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackRecArmRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackSendVolumeRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackSendPanRoute>(&segments, &msg, &reaper, osc_sender);
-            dispatch_route::<TrackColorRoute>(&segments, &msg, &reaper, osc_sender);
+            let (namespace, addr) = surfaces::resolve_incoming(&msg.addr);
+            // Surfaces get their feedback at their destination; other senders become
+            // clients receiving the shared feedback
+            if let Some(client) = clients::current_client() {
+                subscriptions::seen(client);
+                if namespace.is_empty() {
+                    clients::seen(client);
+                }
+            }
+            let _surface = surfaces::enter(namespace);
+            let segments = parse_osc_address(addr);
+            match segments.as_slice() {
+                // Controllers following the selection address the selected tracks in place
+                // of a GUID
+                ["track", "selected", rest @ ..] if !rest.is_empty() => {
+                    for track in tracks::selected_tracks(reaper) {
+                        let guid = get_track_guid(reaper, track);
+                        let mut expanded = vec!["track", guid.as_str()];
+                        expanded.extend_from_slice(rest);
+                        routes.dispatch(&expanded, &msg, reaper, osc_sender);
+                    }
+                }
+                // Fragments of a packet too large for one datagram are acknowledged, and
+                // the packet is handled once complete
+                ["arpad", "blob"] => {
+                    if let Some(client) = clients::current_client() {
+                        receive_fragment(reaper, client, &msg, routes, bundles, osc_sender);
+                    }
+                }
+                _ => routes.dispatch(&segments, &msg, reaper, osc_sender),
+            }
+            if undo::take_request() {
+                undo_last(reaper, routes, osc_sender);
+            }
+            // Only surfaces asking for it are resynced on their own, so one-off tools
+            // don't get the whole state mixed into their replies
+            let reconnected =
+                surfaces::wants_resync() && clients::current_client().is_some_and(clients::heard);
+            if sync::take_request() || reconnected {
+                sync::queue_state(reaper);
+            }
         }
         OscPacket::Bundle(bundle) => {
-            println!("OSC bundle: {:?}", bundle);
+            let Some(bundle) = bundles.schedule(bundle) else {
+                return;
+            };
+            for packet in bundle.content {
+                handle_packet(reaper, packet, routes, bundles, osc_sender);
+            }
         }
     }
 }
 
-const HOST_ADDR: &str = "0.0.0.0:9090";
-const DEVICE_ADDR: &str = "0.0.0.0:9091";
+/// Sets back the value changed most recently through the surface being served
+fn undo_last(reaper: &Reaper, routes: &RouteRegistry, osc_sender: &Sender<Feedback>) {
+    let Some(restore) = undo::pop() else {
+        eprintln!("Nothing to undo");
+        return;
+    };
+    let _restoring = undo::restoring();
+    let segments = parse_osc_address(&restore.addr);
+    routes.dispatch(&segments, &restore, reaper, osc_sender);
+}
 
-#[reaper_extension_plugin]
-fn plugin_main(context: PluginContext) -> Result<(), Box<dyn Error>> {
-    fn get_addr_from_arg(arg: &str) -> SocketAddrV4 {
-        SocketAddrV4::from_str(arg).unwrap()
+/// Acknowledges a fragment of a packet too large for one datagram, and handles the
+/// packet once its last fragment arrived
+fn receive_fragment(
+    reaper: &Reaper,
+    client: SocketAddrV4,
+    msg: &OscMessage,
+    routes: &RouteRegistry,
+    bundles: &mut BundleQueue,
+    osc_sender: &Sender<Feedback>,
+) {
+    let (ack, packet) = transfer::receive(client, msg);
+    if let Some(ack) = ack {
+        let _ = osc_sender.send(Feedback::Reply(client, OscPacket::Message(ack)));
     }
-    let host_addr = get_addr_from_arg(HOST_ADDR);
-    let dev_addr = get_addr_from_arg(DEVICE_ADDR);
-    let sock = UdpSocket::bind(host_addr).unwrap();
-    sock.set_nonblocking(true)?;
-    let (osc_sender, osc_receiver) = bounded(128); // buffer size as needed
-    start_sender_thread(dev_addr, sock.try_clone().unwrap(), osc_receiver);
+    if let Some((_, packet)) = packet.and_then(|buf| rosc::decoder::decode_udp(&buf).ok()) {
+        handle_packet(reaper, packet, routes, bundles, osc_sender);
+    }
+}
 
+/// Answers this run cycle's share of the queries making up the full states queued for
+/// surfaces and clients, so they can redraw from scratch
+fn send_state(reaper: &Reaper, routes: &RouteRegistry, osc_sender: &Sender<Feedback>) {
+    for (namespace, client, addresses) in sync::next_queries() {
+        let _client = clients::enter(client);
+        let _surface = surfaces::enter(&namespace);
+        for addr in addresses {
+            let mut segments = parse_osc_address(&addr);
+            segments.push("?");
+            let query = OscMessage {
+                addr: format!("{}/?", addr),
+                args: vec![],
+            };
+            routes.dispatch(&segments, &query, reaper, osc_sender);
+        }
+    }
+}
+
+fn register_routes(routes: &mut RouteRegistry) {
+    routes
+        .register::<TrackNameRoute>()
+        .register::<TrackSelectedRoute>()
+        .register::<TrackVolumeRoute>()
+        .register::<TrackPanRoute>()
+        .register::<TrackMuteRoute>()
+        .register::<TrackSoloRoute>()
+        .register::<TrackRecArmRoute>()
+        .register::<TrackSendVolumeRoute>()
+        .register::<TrackSendPanRoute>()
+        .register::<TrackSendPageRoute>()
+        .register::<TrackColorRoute>()
+        .register::<ArpadMonitorRoute>()
+        .register::<TrackSpatialRoute>()
+        .register::<TrackGroupRoute>()
+        .register::<GroupsRoute>()
+        .register::<RenderStatusRoute>()
+        .register::<RenderProgressRoute>()
+        .register::<RenderRoute>()
+        .register::<SoloInFrontRoute>()
+        .register::<SoloDimRoute>()
+        .register::<MetersResetRoute>()
+        .register::<MasterLoudnessRoute>()
+        .register::<TrackLoudnessRoute>()
+        .register::<LoudnessIncludeSelectedRoute>()
+        .register::<WindowRoute>()
+        .register::<ThemeColorRoute>()
+        .register::<TempoMarkerCountRoute>()
+        .register::<TempoMarkerRoute>()
+        .register::<MarkerDropRoute>()
+        .register::<VkbNoteRoute>()
+        .register::<TrackInputPeakRoute>()
+        .register::<BankOffsetRoute>()
+        .register::<BankSizeRoute>()
+        .register::<BankSlotGuidRoute>()
+        .register::<FollowSelectionRoute>()
+        .register::<BankSlotVolumeRoute>()
+        .register::<SpillRoute>()
+        .register::<SpillTrackRoute>()
+        .register::<MasterHwOutCountRoute>()
+        .register::<MasterHwOutVolumeRoute>()
+        .register::<MasterHwOutMuteRoute>()
+        .register::<TrackMaxPeakRoute>()
+        .register::<TrackFolderDepthRoute>()
+        .register::<OverviewRoute>()
+        .register::<CueCreateRoute>()
+        .register::<CuesRoute>()
+        .register::<CueSendLevelRoute>()
+        .register::<TalkbackRoute>()
+        .register::<CueNextRoute>()
+        .register::<CuePrevRoute>()
+        .register::<CueGotoRoute>()
+        .register::<CueCurrentRoute>()
+        .register::<ClickHwOutputRoute>()
+        .register::<ClickCueOutputRoute>()
+        .register::<ClickVolumeRoute>()
+        .register::<CountInRoute>()
+        .register::<PrerollRoute>()
+        .register::<PrerollMeasuresRoute>()
+        .register::<TrackCreateRoute>()
+        .register::<TrackDuplicateRoute>()
+        .register::<TrackFxParamModRoute>()
+        .register::<TrackStripRoute>()
+        .register::<VolumeModeRoute>()
+        .register::<ClockRoute>()
+        .register::<AnnounceRoute>()
+        .register::<ProjectChangedRoute>()
+        .register::<TrackResolveRoute>()
+        .register::<ButtonModeRoute>()
+        .register::<ExclusiveSoloRoute>()
+        .register::<AutocolorRulesRoute>()
+        .register::<AutocolorRuleRoute>()
+        .register::<AutocolorRemoveRoute>()
+        .register::<AutocolorActionRoute>()
+        .register::<TrackTagsRoute>()
+        .register::<TracksByTagRoute>()
+        .register::<TracksSetRoute>()
+        .register::<TracksInStateRoute>()
+        .register::<TrackFxParamValueRoute>()
+        .register::<TrackFxParamNameRoute>()
+        .register::<TrackFxParamFormattedRoute>()
+        .register::<MonitorFxCountRoute>()
+        .register::<MonitorFxNameRoute>()
+        .register::<MonitorFxBypassRoute>()
+        .register::<MonitorFxParamRoute>()
+        .register::<TransportRoute>()
+        .register::<TransportRepeatRoute>()
+        .register::<TransportPlayRateRoute>()
+        .register::<TransportPreservePitchRoute>()
+        .register::<TempoTapRoute>()
+        .register::<MarkerPassedRoute>()
+        .register::<TrackMidiActivityRoute>()
+        .register::<MasterVolumeRoute>()
+        .register::<MasterPanRoute>()
+        .register::<MasterSwitchRoute>()
+        .register::<TrackVuRoute>()
+        .register::<TrackReceiveCountRoute>()
+        .register::<TrackReceiveGuidRoute>()
+        .register::<TrackReceiveVolumeRoute>()
+        .register::<TrackReceivePanRoute>()
+        .register::<TrackReceiveMuteRoute>()
+        .register::<MatrixRoute>()
+        .register::<MasterMonoRoute>()
+        .register::<MasterDimRoute>()
+        .register::<MasterDimAmountRoute>()
+        .register::<TransportLocateRoute>()
+        .register::<SubscribeRoute>()
+        .register::<UnsubscribeRoute>()
+        .register::<TransportQuantizedRoute>()
+        .register::<LockoutRoute>()
+        .register::<TrackSendSwitchRoute>()
+        .register::<ProjectTempoRoute>()
+        .register::<ProjectTimeSigRoute>()
+        .register::<TransportTimeRoute>()
+        .register::<TransportBeatsRoute>()
+        .register::<TransportSamplesRoute>()
+        .register::<ProjectMarkerCountRoute>()
+        .register::<ProjectMarkerRoute>()
+        .register::<ProjectMarkerGotoRoute>()
+        .register::<TrackInputGainRoute>()
+        .register::<MeterModeRoute>()
+        .register::<TrackIndexSelectRoute>()
+        .register::<SyncRoute>()
+        .register::<TrackAutomationModeRoute>()
+        .register::<SessionRoute>()
+        .register::<TrackMonitorRoute>()
+        .register::<TrackRecInputRoute>()
+        .register::<BlobAckRoute>()
+        .register::<TrackPhaseRoute>()
+        .register::<TrackChannelCountRoute>()
+        .register::<TrackPanModeRoute>()
+        .register::<TrackWidthRoute>()
+        .register::<TrackPanLeftRoute>()
+        .register::<TrackPanRightRoute>()
+        .register::<TrackFxCountRoute>()
+        .register::<TrackFxNameRoute>()
+        .register::<TrackFxEnabledRoute>()
+        .register::<TrackFxOpenRoute>()
+        .register::<LastTouchedParamValueRoute>()
+        .register::<LastTouchedParamNameRoute>()
+        .register::<ProjectConfigRoute>()
+        .register::<TrackEnvelopeVisibleRoute>()
+        .register::<TrackEnvelopeArmedRoute>()
+        .register::<TrackEnvelopeValueRoute>()
+        .register::<TrackItemMuteRoute>()
+        .register::<TrackItemPositionRoute>()
+        .register::<TrackItemSelectedRoute>()
+        .register::<ProjectNameRoute>()
+        .register::<ProjectPathRoute>()
+        .register::<ProjectLengthRoute>()
+        .register::<ProjectTrackCountRoute>()
+        .register::<ProjectDirtyRoute>()
+        .register::<ProjectUndoRoute>()
+        .register::<ProjectRedoRoute>()
+        .register::<ProjectSaveRoute>()
+        .register::<ActionRoute>()
+        .register::<NamedActionRoute>()
+        .register::<UndoLastRoute>();
+}
+
+#[reaper_extension_plugin]
+fn plugin_main(context: PluginContext) -> Result<(), Box<dyn Error>> {
     let mut session = reaper_medium::ReaperSession::load(context);
     let reaper = session.reaper().clone();
+    let config = Config::load(&reaper);
+    surfaces::init(
+        config.surfaces,
+        config.osc.destination,
+        config.osc.actions,
+        config.osc.echo,
+    );
+    clients::init(
+        config.osc.client_timeout,
+        config.osc.reply_to_sender,
+        config.osc.resync_after,
+    );
+    talkback::init(config.talkback);
+    buttons::init(config.buttons);
+    click::init(config.click);
+    strip::init(config.strip);
+    clock::init(config.clock);
+    vu::init(config.vu);
+    smoothing::init(config.smoothing);
+    position::init(config.position);
+    journal::init(config.journal);
+    settings::init(config.osc.listen);
+    transfer::init(config.osc.fragment_large_packets);
+
+    let sock = UdpSocket::bind(config.osc.listen)?;
+    sock.set_nonblocking(true)?;
+    let (osc_sender, osc_receiver) = bounded(config.osc.queue_size);
+    let (meter_sender, meter_receiver) = bounded(config.osc.queue_size);
+    start_sender_thread(sock.try_clone().unwrap(), osc_receiver, meter_receiver);
     let mut poll_manager = PollManager::new();
     // poll_manager.add_source(Box::new(TrackColorPollSource::new(reaper.clone())));
     //  TODO: add various polling sources here
-    let mut arpad = ArpadSurface {
+    poll_manager.add_source(Box::new(RenderPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(LoudnessPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(InputPeakPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(MaxPeakPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(BankPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(CurrentCuePollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(ClockPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(ProjectPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(ProjectDirtyPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(FxParamPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(LastTouchedPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(PlayPositionPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(MidiActivityPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(VuPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(MasterMonoPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(PreservePitchPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(QuantizedTransportPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(SmoothingPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(SendSwitchPollSource::new(reaper.clone())));
+    poll_manager.add_source(Box::new(PositionPollSource::new(reaper.clone())));
+    let mut routes = RouteRegistry::new();
+    register_routes(&mut routes);
+    let _ = RUNTIME.set(Fragile::new(RefCell::new(Runtime {
+        osc_sender: osc_sender.clone(),
+        meter_sender,
         sock,
-        osc_sender,
         reaper: reaper.clone(),
         poll_manager,
+        routes,
+        bundles: BundleQueue::default(),
+    })));
+    tick();
+    let action_sender = osc_sender.clone();
+    let arpad = ArpadSurface {
+        osc_sender,
+        reaper: reaper.clone(),
     };
-    arpad.run();
     match session.plugin_register_add_csurf_inst(Box::new(arpad)) {
         Ok(_) => {}
         Err(_) => {
             println!("Failed to load csurf");
         }
     }
+    if session.plugin_register_add_timer(timer_tick).is_err() {
+        println!("Failed to register timer");
+    }
+    if actions::register(&mut session, action_sender).is_err() {
+        println!("Failed to register actions");
+    }
     let _ = REAPER_SESSION.set(Fragile::new(session));
 
     Ok(())
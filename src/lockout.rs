@@ -0,0 +1,70 @@
+use std::net::SocketAddrV4;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// While set, incoming messages that would change state are ignored
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+// Address and surface namespace of the sender that set the lockout; None while
+// unlocked or when the lockout was set from within REAPER
+static HOLDER: Mutex<Option<(Option<SocketAddrV4>, String)>> = Mutex::new(None);
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::Relaxed)
+}
+
+/// Sets or lifts the lockout from within REAPER
+pub fn set_locked(locked: bool) {
+    LOCKED.store(locked, Ordering::Relaxed);
+    *HOLDER.lock().unwrap() = None;
+}
+
+/// Sets or lifts the lockout for an OSC sender, which then holds it
+pub fn set_locked_by(locked: bool, client: Option<SocketAddrV4>, namespace: &str) {
+    LOCKED.store(locked, Ordering::Relaxed);
+    *HOLDER.lock().unwrap() = locked.then(|| (client, namespace.to_string()));
+}
+
+/// Whether an OSC sender may change the lockout: anyone while unlocked, and while
+/// locked only the client or surface holding it, or an admin surface. A lockout set
+/// from within REAPER is lifted there or by an admin surface.
+pub fn may_change(client: Option<SocketAddrV4>, namespace: &str, admin: bool) -> bool {
+    if !is_locked() || admin {
+        return true;
+    }
+    match &*HOLDER.lock().unwrap() {
+        Some((holder, holder_namespace)) => {
+            (!holder_namespace.is_empty() && holder_namespace == namespace)
+                || (holder.is_some() && *holder == client)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn only_the_holder_or_an_admin_may_unlock() {
+        let holder = SocketAddrV4::from_str("10.0.0.2:8000").ok();
+        let other = SocketAddrV4::from_str("10.0.0.3:8000").ok();
+        set_locked_by(true, holder, "");
+        assert!(!may_change(other, "", false));
+        assert!(!may_change(None, "/foh", false));
+        assert!(may_change(holder, "", false));
+        assert!(may_change(other, "/admin", true));
+
+        set_locked_by(true, other, "/monitors");
+        assert!(may_change(holder, "/monitors", false));
+        assert!(!may_change(holder, "/foh", false));
+
+        set_locked(true);
+        assert!(!may_change(other, "/monitors", false));
+        assert!(may_change(other, "/admin", true));
+
+        set_locked(false);
+        assert!(may_change(other, "", false));
+    }
+}
@@ -0,0 +1,154 @@
+use std::ffi::{CStr, CString};
+
+use c_str_macro::c_str;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::Reaper;
+
+/// The position new markers are dropped at: the play position while playing,
+/// otherwise the edit cursor.
+pub fn playhead_position(reaper: &Reaper) -> f64 {
+    if reaper.get_play_state_ex(CurrentProject).is_playing {
+        reaper.get_play_position_ex(CurrentProject).get()
+    } else {
+        reaper.get_cursor_position_ex(CurrentProject).get()
+    }
+}
+
+// parse_timestr_pos and format_timestr_pos modes
+const MEASURES_BEATS_MODE: i32 = 2;
+const SAMPLES_MODE: i32 = 4;
+const TIMECODE_MODE: i32 = 5;
+
+fn format_position(reaper: &Reaper, position: f64, mode: i32) -> String {
+    let mut buf = [0 as std::os::raw::c_char; 64];
+    unsafe {
+        reaper
+            .low()
+            .format_timestr_pos(position, buf.as_mut_ptr(), buf.len() as i32, mode);
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+/// `position` in bars.beats through the tempo map, e.g. "17.3.50"
+pub fn format_beats(reaper: &Reaper, position: f64) -> String {
+    format_position(reaper, position, MEASURES_BEATS_MODE)
+}
+
+/// `position` in samples at the project sample rate
+pub fn position_samples(reaper: &Reaper, position: f64) -> i64 {
+    format_position(reaper, position, SAMPLES_MODE)
+        .trim()
+        .parse()
+        .unwrap_or_default()
+}
+
+/// Parses a locate target, either timecode ("01:00:15:12", frames at the project frame
+/// rate) or bars.beats ("17.3" or "17.3.50", through the tempo map), into seconds
+pub fn parse_position(reaper: &Reaper, text: &str) -> Option<f64> {
+    let text = text.trim();
+    let numeric = |fields: &[&str]| {
+        fields
+            .iter()
+            .all(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()))
+    };
+    let mode = if text.contains(':') {
+        // Drop-frame timecode separates the frames with a semicolon
+        let fields: Vec<&str> = text.split([':', ';']).collect();
+        (fields.len() == 4 && numeric(&fields)).then_some(TIMECODE_MODE)?
+    } else {
+        let fields: Vec<&str> = text.split('.').collect();
+        (fields.len() <= 3 && numeric(&fields)).then_some(MEASURES_BEATS_MODE)?
+    };
+    let text = CString::new(text).ok()?;
+    Some(unsafe { reaper.low().parse_timestr_pos(text.as_ptr(), mode) })
+}
+
+/// Moves the edit cursor, and the play position while playing, to `position` seconds
+pub fn locate(reaper: &Reaper, position: f64) {
+    unsafe {
+        reaper.low().SetEditCurPos(position, true, true);
+    }
+}
+
+/// Adds a marker at `position` seconds, returning its displayed index
+pub fn add_marker(reaper: &Reaper, position: f64, name: &str) -> i32 {
+    let name = CString::new(name).unwrap_or_default();
+    unsafe {
+        let index = reaper.low().AddProjectMarker(
+            std::ptr::null_mut(),
+            false,
+            position,
+            0.0,
+            name.as_ptr(),
+            -1,
+        );
+        reaper
+            .low()
+            .Undo_OnStateChange(c_str!("Add marker via arpad").as_ptr());
+        index
+    }
+}
+
+/// A marker or region as returned by EnumProjectMarkers3
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectMarker {
+    pub is_region: bool,
+    pub position: f64,
+    pub region_end: f64,
+    pub name: String,
+    /// The number REAPER displays for the marker or region
+    pub number: i32,
+}
+
+/// The markers, or the regions, of the current project in timeline order
+pub fn markers_of_kind(reaper: &Reaper, regions: bool) -> Vec<ProjectMarker> {
+    project_markers(reaper)
+        .into_iter()
+        .filter(|m| m.is_region == regions)
+        .collect()
+}
+
+/// All markers and regions of the current project, in timeline order
+pub fn project_markers(reaper: &Reaper) -> Vec<ProjectMarker> {
+    let mut markers = Vec::new();
+    let mut idx = 0;
+    loop {
+        let mut is_region = false;
+        let mut position = 0.0;
+        let mut region_end = 0.0;
+        let mut name = std::ptr::null();
+        let mut number = 0;
+        let mut color = 0;
+        let next = unsafe {
+            reaper.low().EnumProjectMarkers3(
+                std::ptr::null_mut(),
+                idx,
+                &mut is_region,
+                &mut position,
+                &mut region_end,
+                &mut name,
+                &mut number,
+                &mut color,
+            )
+        };
+        if next == 0 {
+            break;
+        }
+        let name = if name.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(name) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        markers.push(ProjectMarker {
+            is_region,
+            position,
+            region_end,
+            name,
+            number,
+        });
+        idx = next;
+    }
+    markers
+}
@@ -0,0 +1,145 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use c_str_macro::c_str;
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{Reaper, TrackSendAttributeKey, TrackSendCategory};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{MasterMonoParams, MasterMonoRoute};
+use crate::polling::{PollError, PollSource};
+use crate::utils::{get_toggle_action_state, set_toggle_action_state, set_track_send_info_value};
+use crate::{OscRoute, RouteError};
+
+// "Master track: Toggle stereo/mono (L+R)"
+const MONO_COMMAND: u32 = 40917;
+
+const MONO_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+const DEFAULT_DIM_DB: f64 = -20.0;
+
+// Attenuation in dB applied to the master hardware outputs while dimmed
+static DIM_DB: Mutex<f64> = Mutex::new(DEFAULT_DIM_DB);
+
+// Master hardware output volumes from before the dim, restored when it's released;
+// None while not dimmed
+static DIMMED_OUTPUTS: Mutex<Option<Vec<f64>>> = Mutex::new(None);
+
+pub fn is_mono(reaper: &Reaper) -> bool {
+    get_toggle_action_state(reaper, MONO_COMMAND)
+}
+
+pub fn set_mono(reaper: &Reaper, mono: bool) {
+    set_toggle_action_state(reaper, MONO_COMMAND, mono);
+}
+
+pub fn is_dimmed() -> bool {
+    DIMMED_OUTPUTS.lock().unwrap().is_some()
+}
+
+pub fn dim_amount() -> f64 {
+    *DIM_DB.lock().unwrap()
+}
+
+/// Turns every hardware output of the master down by the dim amount, or back to the
+/// volumes they had before the dim
+pub fn set_dimmed(reaper: &Reaper, dimmed: bool) -> Result<(), RouteError> {
+    let mut saved = DIMMED_OUTPUTS.lock().unwrap();
+    if dimmed == saved.is_some() {
+        return Ok(());
+    }
+    if dimmed {
+        let volumes = hw_output_volumes(reaper);
+        apply_dim(reaper, &volumes, dim_amount())?;
+        *saved = Some(volumes);
+    } else {
+        let volumes = saved.take().unwrap_or_default();
+        apply_dim(reaper, &volumes, 0.0)?;
+    }
+    Ok(())
+}
+
+/// Sets the dim attenuation in dB, applying it at once while dimmed
+pub fn set_dim_amount(reaper: &Reaper, db: f64) -> Result<(), RouteError> {
+    *DIM_DB.lock().unwrap() = db;
+    match DIMMED_OUTPUTS.lock().unwrap().as_ref() {
+        Some(volumes) => apply_dim(reaper, volumes, db),
+        None => Ok(()),
+    }
+}
+
+fn hw_output_volumes(reaper: &Reaper) -> Vec<f64> {
+    let master = reaper.get_master_track(CurrentProject);
+    unsafe {
+        (0..reaper.get_track_num_sends(master, TrackSendCategory::HardwareOutput))
+            .map(|i| {
+                reaper.get_track_send_info_value(
+                    master,
+                    TrackSendCategory::HardwareOutput,
+                    i,
+                    TrackSendAttributeKey::Vol,
+                )
+            })
+            .collect()
+    }
+}
+
+// Sets each hardware output to its undimmed volume attenuated by `db`
+fn apply_dim(reaper: &Reaper, volumes: &[f64], db: f64) -> Result<(), RouteError> {
+    let master = reaper.get_master_track(CurrentProject);
+    let factor = 10f64.powf(db / 20.0);
+    for (index, volume) in volumes.iter().enumerate() {
+        set_track_send_info_value(
+            reaper,
+            master,
+            TrackSendCategory::HardwareOutput,
+            index as u32,
+            c_str!("D_VOL"),
+            volume * factor,
+        )?;
+    }
+    Ok(())
+}
+
+/// Sends `/master/mono` when the master mono downmix is toggled, from a surface or
+/// from REAPER itself. REAPER has no control surface callback for it.
+pub struct MasterMonoPollSource {
+    reaper: Reaper,
+    last_check: Instant,
+    // None until the first check, which records the state without sending it
+    mono: Option<bool>,
+}
+
+impl MasterMonoPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_check: Instant::now(),
+            mono: None,
+        }
+    }
+}
+
+impl PollSource for MasterMonoPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_check.elapsed() < MONO_CHECK_INTERVAL {
+            return Ok(());
+        }
+        self.last_check = Instant::now();
+        let mono = is_mono(&self.reaper);
+        if self.mono.replace(mono).is_none_or(|prev| prev == mono) {
+            return Ok(());
+        }
+        osc_sender
+            .send(
+                OscPacket::Message(MasterMonoRoute::build_message(
+                    (MasterMonoParams {}, mono),
+                    &self.reaper,
+                ))
+                .into(),
+            )
+            .map_err(PollError::Send)
+    }
+}
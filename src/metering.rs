@@ -0,0 +1,515 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper, TrackAttributeKey};
+use rosc::OscPacket;
+
+use crate::feedback::{Feedback, Lane};
+use crate::osc_routes::{
+    LoudnessArgs, MasterLoudnessRoute, TrackInputPeakArgs, TrackInputPeakRoute, TrackLoudnessArgs,
+    TrackLoudnessRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::utils::get_track_guid;
+use crate::OscRoute;
+
+const LOUDNESS_SEND_INTERVAL: Duration = Duration::from_millis(100);
+// Meters are sampled on this clock, however often the poll source runs
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+// 3 second short-term window
+const SHORT_TERM_SAMPLES: usize = 60;
+// 400 ms gating blocks, starting every 100 ms so they overlap by 75%
+const GATING_BLOCK_SAMPLES: usize = 8;
+const GATING_BLOCK_STEP: u64 = 2;
+const ABSOLUTE_GATE_DB: f64 = -70.0;
+const RELATIVE_GATE_DB: f64 = -10.0;
+// Gating blocks are kept as a histogram of 0.1 dB bins between the absolute gate and +10 dB
+const HISTOGRAM_STEP_DB: f64 = 0.1;
+const HISTOGRAM_BINS: usize = 800;
+/// Reported instead of -inf so clients always receive a finite float
+pub const SILENCE_DB: f64 = -150.0;
+
+// Track_GetPeakInfo returns RMS readings instead of peaks for channels offset by this amount
+const RMS_CHANNEL_OFFSET: i32 = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoudnessMeasure {
+    ShortTerm,
+    Integrated,
+}
+
+impl LoudnessMeasure {
+    pub fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "short-term" => Some(LoudnessMeasure::ShortTerm),
+            "integrated" => Some(LoudnessMeasure::Integrated),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoudnessMeasure::ShortTerm => "short-term",
+            LoudnessMeasure::Integrated => "integrated",
+        }
+    }
+
+    fn select(&self, (short_term, integrated): (f64, f64)) -> f64 {
+        match self {
+            LoudnessMeasure::ShortTerm => short_term,
+            LoudnessMeasure::Integrated => integrated,
+        }
+    }
+}
+
+static INCLUDE_SELECTED_TRACKS: AtomicBool = AtomicBool::new(false);
+
+// Latest (short-term, integrated) loudness by track GUID, for answering queries
+static LATEST_LOUDNESS: LazyLock<Mutex<HashMap<String, (f64, f64)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn include_selected_tracks() -> bool {
+    INCLUDE_SELECTED_TRACKS.load(Ordering::Relaxed)
+}
+
+pub fn set_include_selected_tracks(include: bool) {
+    INCLUDE_SELECTED_TRACKS.store(include, Ordering::Relaxed);
+}
+
+/// Returns the latest loudness in dB for a metered track
+pub fn latest_loudness(track_guid: &str, measure: LoudnessMeasure) -> Option<f64> {
+    LATEST_LOUDNESS
+        .lock()
+        .unwrap()
+        .get(track_guid)
+        .map(|values| measure.select(*values))
+}
+
+fn to_db(mean_square: f64) -> f64 {
+    if mean_square <= 0.0 {
+        return SILENCE_DB;
+    }
+    (10.0 * mean_square.log10()).max(SILENCE_DB)
+}
+
+fn histogram_bin(db: f64) -> usize {
+    (((db - ABSOLUTE_GATE_DB) / HISTOGRAM_STEP_DB).max(0.0) as usize).min(HISTOGRAM_BINS - 1)
+}
+
+/// Gated loudness in the manner of BS.1770, computed from REAPER's RMS meter readings.
+/// REAPER does not expose samples to extensions, so the K-weighting pre-filter can't
+/// be applied; readings are plain dB of the mean square, without the LUFS offset.
+struct LoudnessMeter {
+    short_term: VecDeque<f64>,
+    // Samples taken since the meter was reset
+    samples: u64,
+    // Block count and summed mean square per loudness bin above the absolute gate
+    histogram: Vec<(u32, f64)>,
+}
+
+impl Default for LoudnessMeter {
+    fn default() -> Self {
+        Self {
+            short_term: VecDeque::with_capacity(SHORT_TERM_SAMPLES + 1),
+            samples: 0,
+            histogram: vec![(0, 0.0); HISTOGRAM_BINS],
+        }
+    }
+}
+
+impl LoudnessMeter {
+    fn push(&mut self, mean_square: f64) {
+        self.short_term.push_back(mean_square);
+        if self.short_term.len() > SHORT_TERM_SAMPLES {
+            self.short_term.pop_front();
+        }
+
+        self.samples += 1;
+        if self.short_term.len() >= GATING_BLOCK_SAMPLES
+            && self.samples.is_multiple_of(GATING_BLOCK_STEP)
+        {
+            let block = self
+                .short_term
+                .iter()
+                .rev()
+                .take(GATING_BLOCK_SAMPLES)
+                .sum::<f64>()
+                / GATING_BLOCK_SAMPLES as f64;
+            let db = to_db(block);
+            if db > ABSOLUTE_GATE_DB {
+                let (count, sum) = &mut self.histogram[histogram_bin(db)];
+                *count += 1;
+                *sum += block;
+            }
+        }
+    }
+
+    fn short_term(&self) -> f64 {
+        if self.short_term.is_empty() {
+            return SILENCE_DB;
+        }
+        let sum: f64 = self.short_term.iter().sum();
+        to_db(sum / self.short_term.len() as f64)
+    }
+
+    fn integrated(&self) -> f64 {
+        // Mean square of the blocks from a bin upwards
+        let mean_from = |bin: usize| -> Option<f64> {
+            let (count, sum) = self.histogram[bin..]
+                .iter()
+                .fold((0, 0.0), |(count, sum), (c, s)| (count + c, sum + s));
+            (count > 0).then(|| sum / count as f64)
+        };
+
+        let Some(above_absolute) = mean_from(0) else {
+            return SILENCE_DB;
+        };
+        let relative_gate = to_db(above_absolute) + RELATIVE_GATE_DB;
+        // Only bins lying wholly above the relative gate
+        let first_bin = ((relative_gate - ABSOLUTE_GATE_DB) / HISTOGRAM_STEP_DB)
+            .ceil()
+            .clamp(0.0, (HISTOGRAM_BINS - 1) as f64) as usize;
+        mean_from(first_bin).map_or(SILENCE_DB, to_db)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Streams short-term and integrated loudness of the master (and, when enabled,
+/// the selected tracks). Integrated loudness restarts whenever playback starts.
+pub struct LoudnessPollSource {
+    reaper: Reaper,
+    meters: HashMap<String, LoudnessMeter>,
+    was_playing: bool,
+    next_sample: Option<Instant>,
+    last_sent: Option<Instant>,
+}
+
+impl LoudnessPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            meters: HashMap::new(),
+            was_playing: false,
+            next_sample: None,
+            last_sent: None,
+        }
+    }
+
+    /// Number of samples due on the fixed clock since the last poll. A late poll
+    /// repeats its reading for the samples it missed, up to a short-term window.
+    fn samples_due(&mut self, now: Instant) -> usize {
+        let due = match self.next_sample {
+            None => 1,
+            Some(next) if now < next => return 0,
+            Some(next) => {
+                1 + (now.duration_since(next).as_nanos() / SAMPLE_INTERVAL.as_nanos()) as usize
+            }
+        };
+        self.next_sample = match self.next_sample {
+            Some(next) if due <= SHORT_TERM_SAMPLES => Some(next + SAMPLE_INTERVAL * due as u32),
+            _ => Some(now + SAMPLE_INTERVAL),
+        };
+        due.min(SHORT_TERM_SAMPLES)
+    }
+
+    fn mean_square(&self, track: MediaTrack) -> f64 {
+        unsafe {
+            let channels = self
+                .reaper
+                .get_media_track_info_value(track, TrackAttributeKey::Nchan)
+                .max(1.0) as i32;
+            // Channel powers are summed, as BS.1770 does for the front channels
+            (0..channels)
+                .map(|ch| {
+                    let rms = self
+                        .reaper
+                        .low()
+                        .Track_GetPeakInfo(track.as_ptr(), RMS_CHANNEL_OFFSET + ch);
+                    rms * rms
+                })
+                .sum()
+        }
+    }
+
+    fn metered_tracks(&self) -> Vec<MediaTrack> {
+        let mut tracks = vec![self.reaper.get_master_track(CurrentProject)];
+        if include_selected_tracks() {
+            for i in 0..self.reaper.count_tracks(CurrentProject) {
+                let track = self.reaper.get_track(CurrentProject, i).unwrap();
+                let selected = unsafe {
+                    self.reaper
+                        .get_media_track_info_value(track, TrackAttributeKey::Selected)
+                };
+                if selected != 0.0 {
+                    tracks.push(track);
+                }
+            }
+        }
+        tracks
+    }
+}
+
+impl PollSource for LoudnessPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let is_playing = self.reaper.get_play_state_ex(CurrentProject).is_playing;
+        if is_playing && !self.was_playing {
+            self.meters.values_mut().for_each(LoudnessMeter::reset);
+        }
+        self.was_playing = is_playing;
+
+        let now = Instant::now();
+        let due = self.samples_due(now);
+        if due == 0 {
+            return Ok(());
+        }
+        let master = self.reaper.get_master_track(CurrentProject);
+        let tracks = self.metered_tracks();
+        let mut guids = Vec::with_capacity(tracks.len());
+        for track in &tracks {
+            let guid = get_track_guid(&self.reaper, *track);
+            let mean_square = self.mean_square(*track);
+            let meter = self.meters.entry(guid.clone()).or_default();
+            for _ in 0..due {
+                meter.push(mean_square);
+            }
+            guids.push(guid);
+        }
+        // Drop meters for tracks that are no longer selected
+        self.meters.retain(|guid, _| guids.contains(guid));
+
+        if self
+            .last_sent
+            .is_some_and(|t| now.duration_since(t) < LOUDNESS_SEND_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.last_sent = Some(now);
+
+        let mut latest = LATEST_LOUDNESS.lock().unwrap();
+        latest.clear();
+        for (track, guid) in tracks.into_iter().zip(guids) {
+            let meter = &self.meters[&guid];
+            let (short_term, integrated) = (meter.short_term(), meter.integrated());
+            latest.insert(guid, (short_term, integrated));
+            for (measure, db) in [
+                (LoudnessMeasure::ShortTerm, short_term),
+                (LoudnessMeasure::Integrated, integrated),
+            ] {
+                let msg = if track == master {
+                    MasterLoudnessRoute::build_message(LoudnessArgs { measure, db }, &self.reaper)
+                } else {
+                    TrackLoudnessRoute::build_message(
+                        TrackLoudnessArgs {
+                            track,
+                            loudness: LoudnessArgs { measure, db },
+                        },
+                        &self.reaper,
+                    )
+                };
+                osc_sender
+                    .send(OscPacket::Message(msg).into())
+                    .map_err(PollError::Send)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
+
+const INPUT_PEAK_SEND_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Number of input channels a track records from, based on I_RECINPUT, or None for
+/// MIDI and disabled inputs.
+pub fn record_input_channels(reaper: &Reaper, track: MediaTrack) -> Option<u32> {
+    let rec_input =
+        unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::RecInput) } as i32;
+    if rec_input < 0 || rec_input & 4096 != 0 {
+        return None;
+    }
+    if rec_input & 2048 != 0 {
+        let channels =
+            unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Nchan) };
+        return Some(channels as u32);
+    }
+    Some(if rec_input & 1024 != 0 { 2 } else { 1 })
+}
+
+/// Streams input levels of record-armed tracks. While a track is armed REAPER meters
+/// its record input, so these are the levels a take would be recorded at.
+pub struct InputPeakPollSource {
+    reaper: Reaper,
+    last_sent: Option<Instant>,
+}
+
+impl InputPeakPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_sent: None,
+        }
+    }
+}
+
+impl PollSource for InputPeakPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let now = Instant::now();
+        if self
+            .last_sent
+            .is_some_and(|t| now.duration_since(t) < INPUT_PEAK_SEND_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.last_sent = Some(now);
+
+        for i in 0..self.reaper.count_tracks(CurrentProject) {
+            let track = self.reaper.get_track(CurrentProject, i).unwrap();
+            let armed = unsafe {
+                self.reaper
+                    .get_media_track_info_value(track, TrackAttributeKey::RecArm)
+            };
+            if armed == 0.0 {
+                continue;
+            }
+            let Some(channels) = record_input_channels(&self.reaper, track) else {
+                continue;
+            };
+            for channel in 0..channels {
+                let peak = unsafe {
+                    self.reaper
+                        .low()
+                        .Track_GetPeakInfo(track.as_ptr(), channel as i32)
+                };
+                osc_sender
+                    .send(
+                        OscPacket::Message(TrackInputPeakRoute::build_message(
+                            TrackInputPeakArgs {
+                                track,
+                                channel,
+                                peak,
+                            },
+                            &self.reaper,
+                        ))
+                        .into(),
+                    )
+                    .map_err(PollError::Send)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
+
+// Highest peak of every track since playback last started, by track GUID
+static MAX_PEAKS: LazyLock<Mutex<HashMap<String, f64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the highest peak of a track since playback last started, where 1.0 is 0dB
+pub fn max_peak_since_play(track_guid: &str) -> Option<f64> {
+    MAX_PEAKS.lock().unwrap().get(track_guid).copied()
+}
+
+/// Forgets the maximum peaks, which start again from the current playback
+pub fn reset_max_peaks() {
+    MAX_PEAKS.lock().unwrap().clear();
+}
+
+/// Records the maximum peak of every track while playing, for gain-staging passes.
+/// Maxima are kept after playback stops and cleared when it starts again.
+pub struct MaxPeakPollSource {
+    reaper: Reaper,
+    was_playing: bool,
+}
+
+impl MaxPeakPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            was_playing: false,
+        }
+    }
+}
+
+impl PollSource for MaxPeakPollSource {
+    fn poll_and_send(&mut self, _: &Sender<Feedback>) -> Result<(), PollError> {
+        let is_playing = self.reaper.get_play_state_ex(CurrentProject).is_playing;
+        let mut max_peaks = MAX_PEAKS.lock().unwrap();
+        if is_playing && !self.was_playing {
+            max_peaks.clear();
+        }
+        self.was_playing = is_playing;
+        if !is_playing {
+            return Ok(());
+        }
+
+        let master = self.reaper.get_master_track(CurrentProject);
+        let tracks = (0..self.reaper.count_tracks(CurrentProject))
+            .filter_map(|i| self.reaper.get_track(CurrentProject, i))
+            .chain(std::iter::once(master));
+        for track in tracks {
+            let peak = unsafe {
+                let channels = self
+                    .reaper
+                    .get_media_track_info_value(track, TrackAttributeKey::Nchan)
+                    as i32;
+                (0..channels)
+                    .map(|ch| self.reaper.low().Track_GetPeakInfo(track.as_ptr(), ch))
+                    .fold(0.0, f64::max)
+            };
+            let max_peak = max_peaks
+                .entry(get_track_guid(&self.reaper, track))
+                .or_insert(0.0);
+            *max_peak = max_peak.max(peak);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_seconds(meter: &mut LoudnessMeter, seconds: u32, mean_square: f64) {
+        for _ in 0..seconds * 20 {
+            meter.push(mean_square);
+        }
+    }
+
+    #[test]
+    fn steady_signal_integrates_to_its_level() {
+        let mut meter = LoudnessMeter::default();
+        push_seconds(&mut meter, 10, 0.01);
+        assert!((meter.integrated() - to_db(0.01)).abs() < HISTOGRAM_STEP_DB);
+        assert!((meter.short_term() - to_db(0.01)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn quiet_passages_are_gated_out() {
+        let mut meter = LoudnessMeter::default();
+        push_seconds(&mut meter, 10, 0.01);
+        // 30 dB down: above the absolute gate, below the relative one
+        push_seconds(&mut meter, 10, 0.00001);
+        assert!((meter.integrated() - to_db(0.01)).abs() < HISTOGRAM_STEP_DB);
+        assert!((meter.short_term() - to_db(0.00001)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn silence_reads_as_silence() {
+        let mut meter = LoudnessMeter::default();
+        push_seconds(&mut meter, 10, 0.0);
+        assert_eq!(meter.integrated(), SILENCE_DB);
+        assert_eq!(meter.short_term(), SILENCE_DB);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use c_str_macro::c_str;
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper, TrackAttributeKey};
+use rosc::OscPacket;
+
+use crate::feedback::{Feedback, Lane};
+use crate::osc_routes::{MidiActivity, TrackMidiActivityArgs, TrackMidiActivityRoute};
+use crate::polling::{PollError, PollSource};
+use crate::tracks::RecordInput;
+use crate::utils::get_track_guid;
+use crate::OscRoute;
+
+const MIDI_ACTIVITY_INTERVAL: Duration = Duration::from_millis(50);
+// How long an input indicator stays lit after the last event, so single events show up
+const INPUT_HOLD: Duration = Duration::from_millis(150);
+
+// MIDI device index in I_RECINPUT meaning every device
+const ALL_MIDI_DEVICES: i32 = 63;
+// Device indexes reported by MIDI_GetRecentInputEvent carry flags above the index
+const RECENT_EVENT_DEVICE_MASK: i32 = 0xffff;
+
+// Activity last reported for each track, by GUID, answered by queries
+static ACTIVITY: LazyLock<Mutex<HashMap<String, MidiActivity>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn activity(reaper: &Reaper, track: MediaTrack) -> MidiActivity {
+    ACTIVITY
+        .lock()
+        .unwrap()
+        .get(&get_track_guid(reaper, track))
+        .copied()
+        .unwrap_or_default()
+}
+
+// The MIDI device a track records from, or None when its input isn't MIDI. Some(None)
+// means all devices.
+fn midi_input_device(reaper: &Reaper, track: MediaTrack) -> Option<Option<i32>> {
+    let input = unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::RecInput) };
+    match RecordInput::from_raw(input as i32) {
+        RecordInput::Midi { device, .. } => Some((device != ALL_MIDI_DEVICES).then_some(device)),
+        _ => None,
+    }
+}
+
+/// Whether an unmuted MIDI note on the track sounds at `position`
+fn plays_midi_note(reaper: &Reaper, track: MediaTrack, position: f64) -> bool {
+    let low = reaper.low();
+    unsafe {
+        for i in 0..low.CountTrackMediaItems(track.as_ptr()) {
+            let item = low.GetTrackMediaItem(track.as_ptr(), i);
+            let start = low.GetMediaItemInfo_Value(item, c_str!("D_POSITION").as_ptr());
+            let length = low.GetMediaItemInfo_Value(item, c_str!("D_LENGTH").as_ptr());
+            if position < start
+                || position >= start + length
+                || low.GetMediaItemInfo_Value(item, c_str!("B_MUTE").as_ptr()) != 0.0
+            {
+                continue;
+            }
+            let take = low.GetActiveTake(item);
+            if take.is_null() || !low.TakeIsMIDI(take) {
+                continue;
+            }
+            let ppq = low.MIDI_GetPPQPosFromProjTime(take, position);
+            let (mut notes, mut ccs, mut sysexes) = (0, 0, 0);
+            low.MIDI_CountEvts(take, &mut notes, &mut ccs, &mut sysexes);
+            for note in 0..notes {
+                let (mut selected, mut muted) = (false, false);
+                let (mut note_start, mut note_end) = (0.0, 0.0);
+                let (mut channel, mut pitch, mut velocity) = (0, 0, 0);
+                let found = low.MIDI_GetNote(
+                    take,
+                    note,
+                    &mut selected,
+                    &mut muted,
+                    &mut note_start,
+                    &mut note_end,
+                    &mut channel,
+                    &mut pitch,
+                    &mut velocity,
+                );
+                if found && !muted && note_start <= ppq && ppq < note_end {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Sends `/track/{guid}/midi-activity` whenever a track's MIDI input or output
+/// indicator turns on or off. Input activity comes from the events REAPER received on
+/// the track's MIDI input device; output activity from the notes the track plays.
+pub struct MidiActivityPollSource {
+    reaper: Reaper,
+    last_poll: Instant,
+    // Sequence number of the newest MIDI input event already seen
+    last_event: i32,
+    // When each MIDI input device last received an event
+    device_events: HashMap<i32, Instant>,
+}
+
+impl MidiActivityPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_poll: Instant::now(),
+            last_event: 0,
+            device_events: HashMap::new(),
+        }
+    }
+
+    fn read_input_events(&mut self) {
+        let now = Instant::now();
+        let mut newest = None;
+        for idx in 0.. {
+            let mut buf = [0 as std::os::raw::c_char; 4];
+            let mut buf_size = buf.len() as i32;
+            let (mut timestamp, mut device, mut position, mut loop_count) = (0, 0, 0.0, 0);
+            let seq = unsafe {
+                self.reaper.low().MIDI_GetRecentInputEvent(
+                    idx,
+                    buf.as_mut_ptr(),
+                    &mut buf_size,
+                    &mut timestamp,
+                    &mut device,
+                    &mut position,
+                    &mut loop_count,
+                )
+            };
+            // Events come newest first; stop at the first one already seen
+            if seq == 0 || seq == self.last_event {
+                break;
+            }
+            newest.get_or_insert(seq);
+            self.device_events
+                .insert(device & RECENT_EVENT_DEVICE_MASK, now);
+        }
+        if let Some(seq) = newest {
+            self.last_event = seq;
+        }
+    }
+
+    fn input_active(&self, device: Option<i32>) -> bool {
+        let recent = |at: &Instant| at.elapsed() < INPUT_HOLD;
+        match device {
+            Some(device) => self.device_events.get(&device).is_some_and(recent),
+            None => self.device_events.values().any(recent),
+        }
+    }
+}
+
+impl PollSource for MidiActivityPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_poll.elapsed() < MIDI_ACTIVITY_INTERVAL {
+            return Ok(());
+        }
+        self.last_poll = Instant::now();
+        self.read_input_events();
+        let reaper = &self.reaper;
+        let play_state = reaper.get_play_state_ex(CurrentProject);
+        let position = reaper.get_play_position_ex(CurrentProject).get();
+        let mut activity = ACTIVITY.lock().unwrap();
+        for track in (0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+        {
+            let current = MidiActivity {
+                input: midi_input_device(reaper, track).is_some_and(|d| self.input_active(d)),
+                output: play_state.is_playing && plays_midi_note(reaper, track, position),
+            };
+            let guid = get_track_guid(reaper, track);
+            if activity.get(&guid).copied().unwrap_or_default() == current {
+                continue;
+            }
+            activity.insert(guid, current);
+            osc_sender
+                .send(
+                    OscPacket::Message(TrackMidiActivityRoute::build_message(
+                        TrackMidiActivityArgs {
+                            track,
+                            activity: current,
+                        },
+                        reaper,
+                    ))
+                    .into(),
+                )
+                .map_err(PollError::Send)?;
+        }
+        Ok(())
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
@@ -0,0 +1,93 @@
+use std::net::{SocketAddrV4, UdpSocket};
+use std::sync::Mutex;
+
+use reaper_medium::Reaper;
+use rosc::{encoder, OscMessage, OscPacket};
+
+/// Where the debug monitor echoes traffic while `/arpad/monitor` is enabled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MonitorTarget {
+    /// Print every message to the REAPER console
+    Console,
+    /// Forward a copy of every message to a debug OSC client
+    Client(SocketAddrV4),
+}
+
+impl std::fmt::Display for MonitorTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonitorTarget::Console => write!(f, "console"),
+            MonitorTarget::Client(addr) => write!(f, "{}", addr),
+        }
+    }
+}
+
+static TARGET: Mutex<Option<MonitorTarget>> = Mutex::new(None);
+
+// Outgoing messages are logged from the sender thread, but the REAPER console may
+// only be written from the main thread, so console lines are queued until flush().
+static PENDING_CONSOLE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn target() -> Option<MonitorTarget> {
+    *TARGET.lock().unwrap()
+}
+
+pub fn set_target(target: Option<MonitorTarget>) {
+    *TARGET.lock().unwrap() = target;
+}
+
+/// Called on the main thread for every packet received from a client
+pub fn log_incoming(reaper: &Reaper, sock: &UdpSocket, packet: &OscPacket) {
+    match target() {
+        None => {}
+        Some(MonitorTarget::Console) => {
+            for msg in flatten(packet) {
+                reaper.show_console_msg(format!("arpad <- {:?}\n", msg));
+            }
+        }
+        Some(MonitorTarget::Client(addr)) => forward(sock, addr, "in", packet),
+    }
+}
+
+/// Called on the sender thread for every feedback packet sent to the device
+pub fn log_outgoing(sock: &UdpSocket, packet: &OscPacket) {
+    match target() {
+        None => {}
+        Some(MonitorTarget::Console) => {
+            let mut pending = PENDING_CONSOLE.lock().unwrap();
+            for msg in flatten(packet) {
+                pending.push(format!("arpad -> {:?}\n", msg));
+            }
+        }
+        Some(MonitorTarget::Client(addr)) => forward(sock, addr, "out", packet),
+    }
+}
+
+/// Writes queued outgoing log lines to the REAPER console. Must be called on the main thread.
+pub fn flush(reaper: &Reaper) {
+    let lines = std::mem::take(&mut *PENDING_CONSOLE.lock().unwrap());
+    for line in lines {
+        reaper.show_console_msg(line);
+    }
+}
+
+fn flatten(packet: &OscPacket) -> Vec<&OscMessage> {
+    match packet {
+        OscPacket::Message(msg) => vec![msg],
+        OscPacket::Bundle(bundle) => bundle.content.iter().flat_map(flatten).collect(),
+    }
+}
+
+// Echoed messages are prefixed with /arpad/monitor/{direction} so the debug client
+// can tell incoming control from outgoing feedback.
+fn forward(sock: &UdpSocket, addr: SocketAddrV4, direction: &str, packet: &OscPacket) {
+    for msg in flatten(packet) {
+        let echo = OscPacket::Message(OscMessage {
+            addr: format!("/arpad/monitor/{}{}", direction, msg.addr),
+            args: msg.args.clone(),
+        });
+        if let Ok(buf) = encoder::encode(&echo) {
+            let _ = sock.send_to(buf.as_slice(), addr);
+        }
+    }
+}
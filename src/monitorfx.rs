@@ -0,0 +1,29 @@
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper, TrackFxLocation};
+
+use crate::RouteError;
+
+// REAPER keeps the monitoring FX chain as the input FX chain of the master track. It
+// only affects what is heard, never what is rendered.
+
+pub fn count(reaper: &Reaper) -> u32 {
+    let master = reaper.get_master_track(CurrentProject);
+    unsafe { reaper.low().TrackFX_GetRecCount(master.as_ptr()) }.max(0) as u32
+}
+
+/// The master track and location of a monitoring FX
+pub fn location(
+    reaper: &Reaper,
+    fx_index: u32,
+) -> Result<(MediaTrack, TrackFxLocation), RouteError> {
+    if fx_index >= count(reaper) {
+        return Err(RouteError::ValueNotFound(format!(
+            "Monitor FX {}",
+            fx_index
+        )));
+    }
+    Ok((
+        reaper.get_master_track(CurrentProject),
+        TrackFxLocation::InputFxChain(fx_index),
+    ))
+}
@@ -1,8 +1,61 @@
+use std::ffi::CStr;
+use std::net::SocketAddrV4;
+use std::str::FromStr;
+
+use c_str_macro::c_str;
+use reaper_medium::ProjectContext::CurrentProject;
+
+use crate::announce;
+use crate::autocolor::{self, ColorRule};
+use crate::bank::{self, SlotTarget};
+use crate::buttons::{self, ButtonMode, Switch};
+use crate::click::{self, ClickBeat};
+use crate::clients;
+use crate::config;
+use crate::cuemix;
+use crate::envelopes::{self, EnvelopeFlag};
+use crate::feedback::{Feedback, TrackFeedback, TrackParam};
+use crate::items::{self, ItemAttribute};
+use crate::lasttouched;
+use crate::lockout;
+use crate::markers;
+use crate::master;
+use crate::metering::{self, LoudnessMeasure};
+use crate::midi;
+use crate::monitor::{self, MonitorTarget};
+use crate::monitorfx;
+use crate::pickup;
+use crate::playhead;
+use crate::playrate;
+use crate::project;
+use crate::quantize;
+use crate::render::{self, RenderKind, RenderState};
+use crate::sends;
+use crate::setlist;
+use crate::smoothing;
+use crate::solo;
+use crate::strip;
+use crate::subscriptions;
+use crate::surfaces;
+use crate::sync;
+use crate::tags;
+use crate::talkback;
+use crate::tempo::{self, TempoMarker};
+use crate::tracks::{self, NewTrackOptions, TrackSetting};
+use crate::transfer;
+use crate::undo;
+use crate::utils::{
+    find_fx_param_by_name, get_config_var, get_fx_named_config, get_toggle_action_state,
+    immediate_bundle, mode_value_to_volume, named_command_id, normalized_to_volume, run_action,
+    set_fx_named_config, set_toggle_action_state, set_track_send_info_value, volume_to_mode_value,
+    volume_to_normalized, VolumeMode,
+};
+use crate::vu;
 use crate::{
     get_track_by_guid, get_track_guid, OscRoute, Reaper, ReceiverError, RouteError,
     TrackAttributeKey,
 };
-use rosc::{OscMessage, OscType};
+use rosc::{OscMessage, OscPacket, OscType};
 
 /// @osc-doc
 /// @readonly
@@ -22,6 +75,8 @@ impl OscRoute for TrackIndexRoute {
     type SendParams = TrackIndexArgs;
     type ReceiveParams = TrackIndexParams;
 
+    const READONLY: bool = true;
+
     fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
         match segments {
             ["track", track_guid, "index"] => Some(TrackIndexParams {
@@ -74,6 +129,17 @@ pub struct TrackNameArgs {
     pub name: String,
 }
 
+impl TrackNameRoute {
+    fn track_feedback(args: TrackNameArgs, reaper: &Reaper) -> TrackFeedback {
+        TrackFeedback::new(
+            reaper,
+            args.track,
+            TrackParam::Name,
+            vec![OscType::String(args.name)],
+        )
+    }
+}
+
 impl OscRoute for TrackNameRoute {
     type SendParams = TrackNameArgs;
     type ReceiveParams = TrackNameParams;
@@ -103,11 +169,11 @@ impl OscRoute for TrackNameRoute {
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
-        OscMessage {
-            addr: format!("/track/{}/name", track_guid).to_string(),
-            args: vec![OscType::String(args.name)],
-        }
+        Self::track_feedback(args, reaper).into_message()
+    }
+
+    fn build_feedback(args: Self::SendParams, reaper: &Reaper) -> Feedback {
+        Feedback::Track(Self::track_feedback(args, reaper))
     }
 
     fn collect_send_params(
@@ -188,17 +254,90 @@ impl OscRoute for TrackSelectedRoute {
     }
 }
 
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /track/index/{index}/select
+/// Arguments:
+/// - index (int): index of the track to make the only selected one, as reported by /track/{track_guid}/index
+pub struct TrackIndexSelectRoute;
+
+pub struct TrackIndexSelectParams {
+    index: u32,
+}
+
+impl OscRoute for TrackIndexSelectRoute {
+    type SendParams = u32;
+    type ReceiveParams = TrackIndexSelectParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", "index", index, "select"] => Some(TrackIndexSelectParams {
+                index: index.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        // Indexes count from 1, as the master track takes 0
+        let track = params
+            .index
+            .checked_sub(1)
+            .and_then(|i| reaper.get_track(CurrentProject, i))
+            .ok_or_else(|| RouteError::ValueNotFound(format!("Track {}", params.index)))?;
+        unsafe {
+            reaper.set_only_track_selected(Some(track));
+        }
+        Ok(())
+    }
+
+    fn build_message(index: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/track/index/{}/select", index),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.index)
+    }
+}
+
 /// @osc-doc
 /// OSC Address: /track/{track_guid}/volume
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - volume (float): volume of the track, normalized to 0 to 1.0
+/// - volume (float): volume of the track, normalized to 0 to 1.0 following REAPER's fader taper; linear gain or dB instead when the surface's volume mode says so
 pub struct TrackVolumeRoute;
 
 pub struct TrackVolumeParams {
     track_guid: String,
 }
 
+impl TrackVolumeRoute {
+    fn track_feedback(args: reaper_medium::SetSurfaceVolumeArgs, reaper: &Reaper) -> TrackFeedback {
+        let vol_norm = volume_to_mode_value(reaper, surfaces::volume_mode(), args.volume);
+        TrackFeedback::new(
+            reaper,
+            args.track,
+            TrackParam::Volume,
+            vec![OscType::Float(vol_norm as f32)],
+        )
+    }
+}
+
 impl OscRoute for TrackVolumeRoute {
     type SendParams = reaper_medium::SetSurfaceVolumeArgs;
     type ReceiveParams = TrackVolumeParams;
@@ -221,30 +360,21 @@ impl OscRoute for TrackVolumeRoute {
         let volume_raw = msg.args[0].clone().float().ok_or_else(|| {
             ReceiverError::BadValue("Invalid volume value, expected a float".to_string())
         })?;
-        let slider_value = reaper_medium::VolumeSliderValue::new(
-            volume_raw as f64 * reaper_medium::VolumeSliderValue::TWELVE_DB.get(),
-        );
-        let volume_db = reaper.slider2db(slider_value);
-        let volume_linear = volume_db.to_linear_volume_value();
-        unsafe {
-            reaper.csurf_on_volume_change_ex(
-                track,
-                reaper_medium::ValueChange::Absolute(volume_linear),
-                reaper_medium::GangBehavior::DenyGang,
-            );
-            Ok(())
+        let volume_linear =
+            mode_value_to_volume(reaper, surfaces::volume_mode(), volume_raw as f64)
+                .ok_or_else(|| ReceiverError::BadValue("Invalid volume value".to_string()))?;
+        if pickup::accepts_volume(reaper, track, volume_linear) {
+            smoothing::set_volume(reaper, track, volume_linear);
         }
+        Ok(())
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
-        let vol_db = args.volume.to_db_ex(reaper_medium::Db::MINUS_150_DB);
-        let vol_lin = reaper.db2slider(vol_db);
-        let vol_norm = vol_lin.get() / reaper_medium::VolumeSliderValue::TWELVE_DB.get();
-        OscMessage {
-            addr: format!("/track/{}/volume", track_guid).to_string(),
-            args: vec![OscType::Float(vol_norm as f32)],
-        }
+        Self::track_feedback(args, reaper).into_message()
+    }
+
+    fn build_feedback(args: Self::SendParams, reaper: &Reaper) -> Feedback {
+        Feedback::Track(Self::track_feedback(args, reaper))
     }
 
     fn collect_send_params(
@@ -273,6 +403,17 @@ pub struct TrackPanParams {
     track_guid: String,
 }
 
+impl TrackPanRoute {
+    fn track_feedback(args: reaper_medium::SetSurfacePanArgs, reaper: &Reaper) -> TrackFeedback {
+        TrackFeedback::new(
+            reaper,
+            args.track,
+            TrackParam::Pan,
+            vec![OscType::Float(args.pan.into_inner() as f32)],
+        )
+    }
+}
+
 impl OscRoute for TrackPanRoute {
     type SendParams = reaper_medium::SetSurfacePanArgs;
     type ReceiveParams = TrackPanParams;
@@ -292,22 +433,21 @@ impl OscRoute for TrackPanRoute {
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            reaper.set_media_track_info_value(
-                track,
-                TrackAttributeKey::Pan,
-                msg.args[0].clone().float().unwrap() as f64,
-            )?;
+        let pan = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid pan value, expected a float".to_string())
+        })?;
+        if pickup::accepts_pan(reaper, track, pan as f64) {
+            smoothing::set_pan(reaper, track, pan as f64);
         }
         Ok(())
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
-        OscMessage {
-            addr: format!("/track/{}/pan", track_guid).to_string(),
-            args: vec![OscType::Float(args.pan.into_inner() as f32)],
-        }
+        Self::track_feedback(args, reaper).into_message()
+    }
+
+    fn build_feedback(args: Self::SendParams, reaper: &Reaper) -> Feedback {
+        Feedback::Track(Self::track_feedback(args, reaper))
     }
 
     fn collect_send_params(
@@ -325,17 +465,194 @@ impl OscRoute for TrackPanRoute {
     }
 }
 
+/// Track pan modes by name, with their I_PANMODE value
+const PAN_MODES: [(&str, i32); 5] = [
+    ("default", -1),
+    ("classic", 0),
+    ("balance", 3),
+    ("stereo", 5),
+    ("dual", 6),
+];
+const STEREO_PAN_MODE: i32 = 5;
+const DUAL_PAN_MODE: i32 = 6;
+
+fn track_pan_mode(reaper: &Reaper, track: reaper_medium::MediaTrack) -> i32 {
+    unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::PanMode) as i32 }
+}
+
+fn track_pan_value(
+    reaper: &Reaper,
+    track_guid: &str,
+    key: TrackAttributeKey,
+) -> Result<f64, RouteError> {
+    let track = get_track_by_guid(reaper, track_guid)?;
+    Ok(unsafe { reaper.get_media_track_info_value(track, key) })
+}
+
+fn set_track_pan_value(
+    reaper: &Reaper,
+    track_guid: &str,
+    key: TrackAttributeKey,
+    value: f64,
+) -> Result<(), ReceiverError> {
+    let track = get_track_by_guid(reaper, track_guid)?;
+    if !(-1.0..=1.0).contains(&value) {
+        return Err(ReceiverError::BadValue(
+            "Invalid pan value, expected a float from -1.0 to 1.0".to_string(),
+        ));
+    }
+    unsafe {
+        reaper.set_media_track_info_value(track, key, value)?;
+    }
+    Ok(())
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/pan-mode
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - mode (string): "default" (the project's), "classic" (3.x balance), "balance", "stereo" (pan and width) or "dual" (pan-l and pan-r)
+    pub struct TrackPanModeRoute;
+    address: ["track", track_guid, "pan-mode"];
+    params: TrackPanModeParams { track_guid: String };
+    value: String;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let mode = track_pan_mode(reaper, track);
+        Ok(PAN_MODES
+            .iter()
+            .find(|(_, value)| *value == mode)
+            .map_or("default", |(name, _)| name)
+            .to_string())
+    }
+    set(reaper, params, mode) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (_, mode) = PAN_MODES.iter().find(|(name, _)| *name == mode).ok_or_else(|| {
+            ReceiverError::BadValue(
+                "Invalid pan mode, expected \"default\", \"classic\", \"balance\", \"stereo\" or \"dual\""
+                    .to_string(),
+            )
+        })?;
+        unsafe {
+            reaper.set_media_track_info_value(track, TrackAttributeKey::PanMode, *mode as f64)?;
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/width
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - width (float): stereo width from -1.0 to 1.0, used in the "stereo" pan mode
+    pub struct TrackWidthRoute;
+    address: ["track", track_guid, "width"];
+    params: TrackWidthParams { track_guid: String };
+    value: f64;
+    get(reaper, params) {
+        track_pan_value(reaper, &params.track_guid, TrackAttributeKey::Width)
+    }
+    set(reaper, params, width) {
+        set_track_pan_value(reaper, &params.track_guid, TrackAttributeKey::Width, width)
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/pan-l
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - pan (float): pan of the left channel from -1.0 to 1.0, used in the "dual" pan mode
+    pub struct TrackPanLeftRoute;
+    address: ["track", track_guid, "pan-l"];
+    params: TrackPanLeftParams { track_guid: String };
+    value: f64;
+    get(reaper, params) {
+        track_pan_value(reaper, &params.track_guid, TrackAttributeKey::DualPanL)
+    }
+    set(reaper, params, pan) {
+        set_track_pan_value(reaper, &params.track_guid, TrackAttributeKey::DualPanL, pan)
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/pan-r
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - pan (float): pan of the right channel from -1.0 to 1.0, used in the "dual" pan mode
+    pub struct TrackPanRightRoute;
+    address: ["track", track_guid, "pan-r"];
+    params: TrackPanRightParams { track_guid: String };
+    value: f64;
+    get(reaper, params) {
+        track_pan_value(reaper, &params.track_guid, TrackAttributeKey::DualPanR)
+    }
+    set(reaper, params, pan) {
+        set_track_pan_value(reaper, &params.track_guid, TrackAttributeKey::DualPanR, pan)
+    }
+}
+
+/// Feedback accompanying a pan change: the pan mode, then the width or the dual pan
+/// values when the track uses them. REAPER reports changes to any of these as pan
+/// changes.
+pub fn pan_mode_feedback(reaper: &Reaper, track: reaper_medium::MediaTrack) -> Vec<OscMessage> {
+    let track_guid = get_track_guid(reaper, track);
+    let mut messages = Vec::new();
+    let mode_params = TrackPanModeParams {
+        track_guid: track_guid.clone(),
+    };
+    if let Ok(params) = TrackPanModeRoute::collect_send_params(&mode_params, reaper) {
+        messages.push(TrackPanModeRoute::build_message(params, reaper));
+    }
+    match track_pan_mode(reaper, track) {
+        STEREO_PAN_MODE => {
+            let params = TrackWidthParams { track_guid };
+            if let Ok(params) = TrackWidthRoute::collect_send_params(&params, reaper) {
+                messages.push(TrackWidthRoute::build_message(params, reaper));
+            }
+        }
+        DUAL_PAN_MODE => {
+            let left = TrackPanLeftParams {
+                track_guid: track_guid.clone(),
+            };
+            if let Ok(params) = TrackPanLeftRoute::collect_send_params(&left, reaper) {
+                messages.push(TrackPanLeftRoute::build_message(params, reaper));
+            }
+            let right = TrackPanRightParams { track_guid };
+            if let Ok(params) = TrackPanRightRoute::collect_send_params(&right, reaper) {
+                messages.push(TrackPanRightRoute::build_message(params, reaper));
+            }
+        }
+        _ => {}
+    }
+    messages
+}
+
 /// @osc-doc
 /// OSC Address: /track/{track_guid}/mute
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - mute (bool): true means track is muted
+/// - mute (bool): true means track is muted; how incoming values are applied depends on the mute button mode
 pub struct TrackMuteRoute;
 
 pub struct TrackMuteParams {
     track_guid: String,
 }
 
+impl TrackMuteRoute {
+    fn track_feedback(args: reaper_medium::SetSurfaceMuteArgs, reaper: &Reaper) -> TrackFeedback {
+        TrackFeedback::new(
+            reaper,
+            args.track,
+            TrackParam::Mute,
+            vec![OscType::Bool(args.is_mute)],
+        )
+    }
+}
+
 impl OscRoute for TrackMuteRoute {
     type SendParams = reaper_medium::SetSurfaceMuteArgs;
     type ReceiveParams = TrackMuteParams;
@@ -355,22 +672,22 @@ impl OscRoute for TrackMuteRoute {
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            reaper.csurf_on_mute_change_ex(
-                track,
-                msg.args[0].clone().bool().unwrap(),
-                reaper_medium::GangBehavior::DenyGang,
-            );
+        let current = unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Mute) };
+        let guid = get_track_guid(reaper, track);
+        if let Some(mute) = buttons::next_state(Switch::Mute, &guid, msg, current != 0.0)? {
+            unsafe {
+                reaper.csurf_on_mute_change_ex(track, mute, reaper_medium::GangBehavior::DenyGang);
+            }
         }
         Ok(())
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
-        OscMessage {
-            addr: format!("/track/{}/mute", track_guid).to_string(),
-            args: vec![OscType::Bool(args.is_mute)],
-        }
+        Self::track_feedback(args, reaper).into_message()
+    }
+
+    fn build_feedback(args: Self::SendParams, reaper: &Reaper) -> Feedback {
+        Feedback::Track(Self::track_feedback(args, reaper))
     }
 
     fn collect_send_params(
@@ -392,7 +709,7 @@ impl OscRoute for TrackMuteRoute {
 /// OSC Address: /track/{track_guid}/solo
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - solo (bool): true means track is soloed
+/// - solo (bool): true means track is soloed; how incoming values are applied depends on the solo button mode
 pub struct TrackSoloRoute;
 
 pub struct TrackSoloParams {
@@ -418,12 +735,10 @@ impl OscRoute for TrackSoloRoute {
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            reaper.csurf_on_solo_change_ex(
-                track,
-                msg.args[0].clone().bool().unwrap(),
-                reaper_medium::GangBehavior::DenyGang,
-            );
+        let current = unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Solo) };
+        let guid = get_track_guid(reaper, track);
+        if let Some(solo) = buttons::next_state(Switch::Solo, &guid, msg, current != 0.0)? {
+            solo::set_solo(reaper, track, solo);
         }
         Ok(())
     }
@@ -455,7 +770,7 @@ impl OscRoute for TrackSoloRoute {
 /// OSC Address: /track/{track_guid}/rec-arm
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - rec_arm (bool): true means track is armed for recording
+/// - rec_arm (bool): true means track is armed for recording; how incoming values are applied depends on the rec-arm button mode
 pub struct TrackRecArmRoute;
 
 pub struct TrackRecArmParams {
@@ -481,13 +796,22 @@ impl OscRoute for TrackRecArmRoute {
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            let mode = if msg.args[0].clone().bool().unwrap() {
+        let current =
+            unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::RecArm) };
+        let guid = get_track_guid(reaper, track);
+        if let Some(armed) = buttons::next_state(Switch::RecArm, &guid, msg, current != 0.0)? {
+            let mode = if armed {
                 reaper_medium::RecordArmMode::Armed
             } else {
                 reaper_medium::RecordArmMode::Unarmed
             };
-            reaper.csurf_on_rec_arm_change_ex(track, mode, reaper_medium::GangBehavior::DenyGang);
+            unsafe {
+                reaper.csurf_on_rec_arm_change_ex(
+                    track,
+                    mode,
+                    reaper_medium::GangBehavior::DenyGang,
+                );
+            }
         }
         Ok(())
     }
@@ -515,35 +839,67 @@ impl OscRoute for TrackRecArmRoute {
     }
 }
 
+/// Input monitoring modes in I_RECMON order
+const MONITOR_MODES: [&str; 3] = ["off", "on", "auto"];
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/monitor
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - mode (string): input monitoring, "off", "on" or "auto" (monitoring while armed and not playing back)
+    pub struct TrackMonitorRoute;
+    address: ["track", track_guid, "monitor"];
+    params: TrackMonitorParams { track_guid: String };
+    value: String;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let mode = unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::RecMon) };
+        Ok(MONITOR_MODES
+            .get(mode as usize)
+            .unwrap_or(&"off")
+            .to_string())
+    }
+    set(reaper, params, mode) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let mode = MONITOR_MODES.iter().position(|m| *m == mode).ok_or_else(|| {
+            ReceiverError::BadValue(
+                "Invalid monitor mode, expected \"off\", \"on\" or \"auto\"".to_string(),
+            )
+        })?;
+        unsafe {
+            reaper.set_media_track_info_value(track, TrackAttributeKey::RecMon, mode as f64)?;
+        }
+        Ok(())
+    }
+}
+
 /// @osc-doc
-/// @readonly
-/// OSC Address: /track/{track_guid}/send/{send_index}/guid
+/// OSC Address: /track/{track_guid}/rec-input
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - send_index (int): index of the send on the track
-/// - guid (string): unique identifier for the send
-pub struct TrackSendGuidRoute;
+/// - kind (string): "none", "mono", "stereo", "multichannel" or "midi"
+/// - input (int): first audio input channel, counting from 0, or the MIDI device index with 63 meaning all devices
+/// - midi_channel (int): MIDI channel from 1 to 16, or 0 for all channels; optional when setting, 0 for audio inputs
+pub struct TrackRecInputRoute;
 
-pub struct TrackSendGuidParams {
+pub struct TrackRecInputParams {
     track_guid: String,
-    send_index: i32,
 }
 
-pub struct TrackSendGuidArgs {
+pub struct TrackRecInputArgs {
     pub track: reaper_medium::MediaTrack,
-    pub send_index: i32,
-    pub send_guid: String,
+    pub input: tracks::RecordInput,
 }
 
-impl OscRoute for TrackSendGuidRoute {
-    type SendParams = TrackSendGuidArgs;
-    type ReceiveParams = TrackSendGuidParams;
+impl OscRoute for TrackRecInputRoute {
+    type SendParams = TrackRecInputArgs;
+    type ReceiveParams = TrackRecInputParams;
 
     fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
         match segments {
-            ["track", track_guid, "send", send_index, "guid"] => Some(TrackSendGuidParams {
+            ["track", track_guid, "rec-input"] => Some(TrackRecInputParams {
                 track_guid: track_guid.to_string(),
-                send_index: send_index.parse().ok()?,
             }),
             _ => None,
         }
@@ -551,19 +907,54 @@ impl OscRoute for TrackSendGuidRoute {
 
     fn receive(
         params: Self::ReceiveParams,
-        _: &OscMessage,
+        msg: &OscMessage,
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
-        let _ = get_track_by_guid(reaper, &params.track_guid)?;
-        // This route is read-only, so we don't need to do anything here.
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let kind = msg.args.first().and_then(|arg| arg.clone().string());
+        let input = msg
+            .args
+            .get(1)
+            .and_then(|arg| arg.clone().int())
+            .unwrap_or(0);
+        let midi_channel = msg
+            .args
+            .get(2)
+            .and_then(|arg| arg.clone().int())
+            .unwrap_or(0);
+        let input = kind
+            .and_then(|kind| tracks::RecordInput::from_parts(&kind, input, midi_channel))
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid record input, expected a kind, an input and a MIDI channel"
+                        .to_string(),
+                )
+            })?;
+        unsafe {
+            reaper.set_media_track_info_value(
+                track,
+                TrackAttributeKey::RecInput,
+                input.to_raw() as f64,
+            )?;
+        }
         Ok(())
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
+        let (input, midi_channel) = match args.input {
+            tracks::RecordInput::None => (0, 0),
+            tracks::RecordInput::Mono(channel)
+            | tracks::RecordInput::Stereo(channel)
+            | tracks::RecordInput::Multichannel(channel) => (channel, 0),
+            tracks::RecordInput::Midi { device, channel } => (device, channel),
+        };
         OscMessage {
-            addr: format!("/track/{}/send/{}/guid", track_guid, args.send_index).to_string(),
-            args: vec![OscType::String(args.send_guid)],
+            addr: format!("/track/{}/rec-input", get_track_guid(reaper, args.track)),
+            args: vec![
+                OscType::String(args.input.kind().to_string()),
+                OscType::Int(input),
+                OscType::Int(midi_channel),
+            ],
         }
     }
 
@@ -571,49 +962,110 @@ impl OscRoute for TrackSendGuidRoute {
         params: &Self::ReceiveParams,
         reaper: &Reaper,
     ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let raw = unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::RecInput) };
+        Ok(TrackRecInputArgs {
+            track,
+            input: tracks::RecordInput::from_raw(raw as i32),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/phase
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - inverted (bool): true means the track's polarity is flipped
+    pub struct TrackPhaseRoute;
+    address: ["track", track_guid, "phase"];
+    params: TrackPhaseParams { track_guid: String };
+    value: bool;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Phase) } != 0.0)
+    }
+    set(reaper, params, inverted) {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
         unsafe {
-            let send_track = reaper
-                .get_track_send_info_desttrack(
-                    track,
-                    reaper_medium::TrackSendDirection::Send,
-                    params.send_index as u32,
-                )
-                .map_err(|_| {
-                    RouteError::ValueNotFound("Failed to retrieve send track".to_string())
-                })?;
-            let send_guid = get_track_guid(reaper, send_track);
-            Ok(TrackSendGuidArgs {
+            reaper.set_media_track_info_value(
                 track,
-                send_index: params.send_index,
-                send_guid,
-            })
+                TrackAttributeKey::Phase,
+                if inverted { 1.0 } else { 0.0 },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// REAPER tracks carry an even number of channels in this range
+const MIN_TRACK_CHANNELS: i32 = 2;
+const MAX_TRACK_CHANNELS: i32 = 128;
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/nchan
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - channels (int): number of track channels, an even number from 2 to 128
+    pub struct TrackChannelCountRoute;
+    address: ["track", track_guid, "nchan"];
+    params: TrackChannelCountParams { track_guid: String };
+    value: i32;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Nchan) } as i32)
+    }
+    set(reaper, params, channels) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        if !(MIN_TRACK_CHANNELS..=MAX_TRACK_CHANNELS).contains(&channels) || channels % 2 != 0 {
+            return Err(ReceiverError::BadValue(
+                "Invalid channel count, expected an even number from 2 to 128".to_string(),
+            ));
+        }
+        unsafe {
+            reaper.set_media_track_info_value(track, TrackAttributeKey::Nchan, channels as f64)?;
         }
+        Ok(())
     }
 }
 
+/// Track automation modes in I_AUTOMODE order
+const AUTOMATION_MODES: [&str; 6] = ["trim", "read", "touch", "write", "latch", "latch-preview"];
+
 /// @osc-doc
-/// OSC Address: /track/{track_guid}/send/{send_index}/volume
+/// OSC Address: /track/{track_guid}/automation-mode
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - send_index (int): index of the send on the track
-/// - volume (float): volume of the send, normalized to 0 to 1.
-pub struct TrackSendVolumeRoute;
+/// - mode (string): "trim", "read", "touch", "write", "latch" or "latch-preview"; the mode's number from 0 to 5 in that order is accepted too
+pub struct TrackAutomationModeRoute;
 
-pub struct TrackSendVolumeParams {
+pub struct TrackAutomationModeParams {
     track_guid: String,
-    send_index: i32,
 }
 
-impl OscRoute for TrackSendVolumeRoute {
-    type SendParams = reaper_medium::ExtSetSendVolumeArgs;
-    type ReceiveParams = TrackSendVolumeParams;
+pub struct TrackAutomationModeArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub mode: i32,
+}
+
+impl TrackAutomationModeRoute {
+    pub fn collect(reaper: &Reaper, track: reaper_medium::MediaTrack) -> TrackAutomationModeArgs {
+        TrackAutomationModeArgs {
+            track,
+            mode: unsafe { reaper.low().GetTrackAutomationMode(track.as_ptr()) },
+        }
+    }
+}
+
+impl OscRoute for TrackAutomationModeRoute {
+    type SendParams = TrackAutomationModeArgs;
+    type ReceiveParams = TrackAutomationModeParams;
 
     fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
         match segments {
-            ["track", track_guid, "send", send_index, "volume"] => Some(TrackSendVolumeParams {
+            ["track", track_guid, "automation-mode"] => Some(TrackAutomationModeParams {
                 track_guid: track_guid.to_string(),
-                send_index: send_index.parse().ok()?,
             }),
             _ => None,
         }
@@ -625,29 +1077,36 @@ impl OscRoute for TrackSendVolumeRoute {
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let mode = match msg.args.first() {
+            Some(OscType::String(name)) => AUTOMATION_MODES.iter().position(|m| m == name),
+            Some(OscType::Int(mode)) => usize::try_from(*mode).ok(),
+            _ => None,
+        }
+        .filter(|&mode| mode < AUTOMATION_MODES.len())
+        .ok_or_else(|| {
+            ReceiverError::BadValue(
+                "Invalid automation mode, expected a mode name or a number from 0 to 5".to_string(),
+            )
+        })?;
         unsafe {
-            let track_send_ref = reaper_medium::TrackSendRef::Send(
-                u32::try_from(params.send_index)
-                    .map_err(|_| ReceiverError::BadValue("Invalid send index".to_string()))?,
-            );
-            let volume =
-                reaper_medium::ReaperVolumeValue::new(msg.args[0].clone().float().unwrap() as f64)
-                    .map_err(|_| ReceiverError::BadValue("Invalid volume value".to_string()))?;
-            reaper.set_track_send_ui_vol(
-                track,
-                track_send_ref,
-                volume,
-                reaper_medium::EditMode::NormalTweak,
-            )?
+            reaper
+                .low()
+                .SetTrackAutomationMode(track.as_ptr(), mode as i32);
         }
         Ok(())
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
+        let mode = usize::try_from(args.mode)
+            .ok()
+            .and_then(|mode| AUTOMATION_MODES.get(mode))
+            .unwrap_or(&"trim");
         OscMessage {
-            addr: format!("/track/{}/send/{}/volume", track_guid, args.send_index).to_string(),
-            args: vec![OscType::Float(args.volume.into_inner() as f32)],
+            addr: format!(
+                "/track/{}/automation-mode",
+                get_track_guid(reaper, args.track)
+            ),
+            args: vec![OscType::String(mode.to_string())],
         }
     }
 
@@ -656,15 +1115,162 @@ impl OscRoute for TrackSendVolumeRoute {
         reaper: &Reaper,
     ) -> Result<Self::SendParams, RouteError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            let volume = reaper.get_track_send_info_value(
-                track,
-                reaper_medium::TrackSendCategory::Send,
-                params.send_index as u32,
-                reaper_medium::TrackSendAttributeKey::Vol,
-            );
-            Ok(reaper_medium::ExtSetSendVolumeArgs {
-                track,
+        Ok(Self::collect(reaper, track))
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/send/{send_index}/guid
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - send_index (int): index of the send on the track
+/// - guid (string): unique identifier for the send
+pub struct TrackSendGuidRoute;
+
+pub struct TrackSendGuidParams {
+    track_guid: String,
+    send_index: i32,
+}
+
+pub struct TrackSendGuidArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub send_index: i32,
+    pub send_guid: String,
+}
+
+impl OscRoute for TrackSendGuidRoute {
+    type SendParams = TrackSendGuidArgs;
+    type ReceiveParams = TrackSendGuidParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "send", send_index, "guid"] => Some(TrackSendGuidParams {
+                track_guid: track_guid.to_string(),
+                send_index: send_index.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        _: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let _ = get_track_by_guid(reaper, &params.track_guid)?;
+        // This route is read-only, so we don't need to do anything here.
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/send/{}/guid", track_guid, args.send_index).to_string(),
+            args: vec![OscType::String(args.send_guid)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        unsafe {
+            let send_track = reaper
+                .get_track_send_info_desttrack(
+                    track,
+                    reaper_medium::TrackSendDirection::Send,
+                    params.send_index as u32,
+                )
+                .map_err(|_| {
+                    RouteError::ValueNotFound("Failed to retrieve send track".to_string())
+                })?;
+            let send_guid = get_track_guid(reaper, send_track);
+            Ok(TrackSendGuidArgs {
+                track,
+                send_index: params.send_index,
+                send_guid,
+            })
+        }
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/send/{send_index}/volume
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - send_index (int): index of the send on the track
+/// - volume (float): volume of the send, normalized to 0 to 1.
+pub struct TrackSendVolumeRoute;
+
+pub struct TrackSendVolumeParams {
+    track_guid: String,
+    send_index: i32,
+}
+
+impl OscRoute for TrackSendVolumeRoute {
+    type SendParams = reaper_medium::ExtSetSendVolumeArgs;
+    type ReceiveParams = TrackSendVolumeParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "send", send_index, "volume"] => Some(TrackSendVolumeParams {
+                track_guid: track_guid.to_string(),
+                send_index: send_index.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        unsafe {
+            let track_send_ref = reaper_medium::TrackSendRef::Send(
+                u32::try_from(params.send_index)
+                    .map_err(|_| ReceiverError::BadValue("Invalid send index".to_string()))?,
+            );
+            let volume =
+                reaper_medium::ReaperVolumeValue::new(msg.args[0].clone().float().unwrap() as f64)
+                    .map_err(|_| ReceiverError::BadValue("Invalid volume value".to_string()))?;
+            reaper.set_track_send_ui_vol(
+                track,
+                track_send_ref,
+                volume,
+                reaper_medium::EditMode::NormalTweak,
+            )?
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/send/{}/volume", track_guid, args.send_index).to_string(),
+            args: vec![OscType::Float(args.volume.into_inner() as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        unsafe {
+            let volume = reaper.get_track_send_info_value(
+                track,
+                reaper_medium::TrackSendCategory::Send,
+                params.send_index as u32,
+                reaper_medium::TrackSendAttributeKey::Vol,
+            );
+            Ok(reaper_medium::ExtSetSendVolumeArgs {
+                track,
                 send_index: params.send_index as u32,
                 volume: reaper_medium::ReaperVolumeValue::new_panic(volume),
             })
@@ -753,27 +1359,189 @@ impl OscRoute for TrackSendPanRoute {
 }
 
 /// @osc-doc
-/// OSC Address: /track/{track_guid}/color
+/// @readonly
+/// OSC Address: /track/{track_guid}/send/page/{page}
 /// Arguments:
 /// - track_guid (string): unique identifier for the track
-/// - color (int): color of the track, represented as an RGB integer
-pub struct TrackColorRoute;
-pub struct TrackColorParams {
+/// - page (int): page of sends, from 0; each page holds the surface's send_page_size sends, 8 by default
+/// - page_count (int): number of pages of sends on the track
+/// - send_count (int): number of sends on the track; followed in the same bundle by the guid, volume and pan messages of each send on the page
+pub struct TrackSendPageRoute;
+
+pub struct TrackSendPageParams {
     track_guid: String,
+    page: u32,
 }
-pub struct TrackColorArgs {
+
+pub struct TrackSendPageArgs {
     pub track: reaper_medium::MediaTrack,
-    pub color: i32,
+    pub page: u32,
+    pub page_count: u32,
+    pub send_count: u32,
+    pub sends: Vec<OscMessage>,
 }
 
-impl OscRoute for TrackColorRoute {
-    type SendParams = TrackColorArgs;
-    type ReceiveParams = TrackColorParams;
+impl OscRoute for TrackSendPageRoute {
+    type SendParams = TrackSendPageArgs;
+    type ReceiveParams = TrackSendPageParams;
+
+    const READONLY: bool = true;
 
     fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
         match segments {
-            ["track", track_guid, "color"] => Some(TrackColorParams {
+            ["track", track_guid, "send", "page", page] => Some(TrackSendPageParams {
+                track_guid: track_guid.to_string(),
+                page: page.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!(
+                "/track/{}/send/page/{}",
+                get_track_guid(reaper, args.track),
+                args.page
+            ),
+            args: vec![
+                OscType::Int(args.page_count as i32),
+                OscType::Int(args.send_count as i32),
+            ],
+        }
+    }
+
+    fn build_packet(mut args: Self::SendParams, reaper: &Reaper) -> OscPacket {
+        let sends = std::mem::take(&mut args.sends);
+        let mut content = vec![OscPacket::Message(Self::build_message(args, reaper))];
+        content.extend(sends.into_iter().map(OscPacket::Message));
+        immediate_bundle(content)
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let send_count =
+            unsafe { reaper.get_track_num_sends(track, reaper_medium::TrackSendCategory::Send) };
+        let page_size = sends::page_size();
+        let page_count = send_count.div_ceil(page_size);
+        let first = params.page * page_size;
+        let mut sends = Vec::new();
+        for send_index in first..send_count.min(first + page_size) {
+            let guid = TrackSendGuidParams {
+                track_guid: params.track_guid.clone(),
+                send_index: send_index as i32,
+            };
+            if let Ok(args) = TrackSendGuidRoute::collect_send_params(&guid, reaper) {
+                sends.push(TrackSendGuidRoute::build_message(args, reaper));
+            }
+            let volume = TrackSendVolumeParams {
+                track_guid: params.track_guid.clone(),
+                send_index: send_index as i32,
+            };
+            if let Ok(args) = TrackSendVolumeRoute::collect_send_params(&volume, reaper) {
+                sends.push(TrackSendVolumeRoute::build_message(args, reaper));
+            }
+            let pan = TrackSendPanParams {
+                track_guid: params.track_guid.clone(),
+                send_index: send_index as i32,
+            };
+            if let Ok(args) = TrackSendPanRoute::collect_send_params(&pan, reaper) {
+                sends.push(TrackSendPanRoute::build_message(args, reaper));
+            }
+        }
+        Ok(TrackSendPageArgs {
+            track,
+            page: params.page,
+            page_count,
+            send_count,
+            sends,
+        })
+    }
+}
+
+/// A per-send switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendSwitch {
+    Mute,
+    Phase,
+    Mono,
+}
+
+impl SendSwitch {
+    pub const ALL: [SendSwitch; 3] = [SendSwitch::Mute, SendSwitch::Phase, SendSwitch::Mono];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendSwitch::Mute => "mute",
+            SendSwitch::Phase => "phase",
+            SendSwitch::Mono => "mono",
+        }
+    }
+
+    fn from_name(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|switch| switch.as_str() == s)
+    }
+
+    fn attribute(&self) -> &'static CStr {
+        match self {
+            SendSwitch::Mute => c_str!("B_MUTE"),
+            SendSwitch::Phase => c_str!("B_PHASE"),
+            SendSwitch::Mono => c_str!("B_MONO"),
+        }
+    }
+
+    /// Whether the switch is on for a send of the track
+    pub fn get(&self, reaper: &Reaper, track: reaper_medium::MediaTrack, send_index: u32) -> bool {
+        unsafe {
+            reaper.low().GetTrackSendInfo_Value(
+                track.as_ptr(),
+                reaper_medium::TrackSendCategory::Send.to_raw(),
+                send_index as i32,
+                self.attribute().as_ptr(),
+            ) != 0.0
+        }
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/send/{send_index}/{switch}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - send_index (int): index of the send on the track
+/// - switch (string): "mute", "phase" (polarity inverted) or "mono"
+/// - on (bool): whether the switch is on for the send
+pub struct TrackSendSwitchRoute;
+
+pub struct TrackSendSwitchParams {
+    track_guid: String,
+    send_index: u32,
+    switch: SendSwitch,
+}
+
+pub struct TrackSendSwitchArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub send_index: u32,
+    pub switch: SendSwitch,
+    pub on: bool,
+}
+
+impl OscRoute for TrackSendSwitchRoute {
+    type SendParams = TrackSendSwitchArgs;
+    type ReceiveParams = TrackSendSwitchParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "send", send_index, switch] => Some(TrackSendSwitchParams {
                 track_guid: track_guid.to_string(),
+                send_index: send_index.parse().ok()?,
+                switch: SendSwitch::from_name(switch)?,
             }),
             _ => None,
         }
@@ -785,26 +1553,36 @@ impl OscRoute for TrackColorRoute {
         reaper: &Reaper,
     ) -> Result<(), ReceiverError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            let int_arg = msg.args[0].clone().int().ok_or_else(|| {
-                ReceiverError::BadValue("Invalid color value, expected an integer".to_string())
+        let on = msg
+            .args
+            .first()
+            .and_then(|arg| arg.clone().bool())
+            .ok_or_else(|| {
+                ReceiverError::BadValue(format!(
+                    "Invalid send {} value, expected a bool",
+                    params.switch.as_str()
+                ))
             })?;
-            reaper.get_set_media_track_info_set_custom_color(
-                track,
-                reaper_medium::NativeColorValue {
-                    color: reaper_medium::NativeColor::new(int_arg),
-                    is_used: true,
-                },
-            );
-        }
+        set_track_send_info_value(
+            reaper,
+            track,
+            reaper_medium::TrackSendCategory::Send,
+            params.send_index,
+            params.switch.attribute(),
+            if on { 1.0 } else { 0.0 },
+        )?;
         Ok(())
     }
 
     fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
-        let track_guid = get_track_guid(reaper, args.track);
         OscMessage {
-            addr: format!("/track/{}/color", track_guid).to_string(),
-            args: vec![OscType::Int(args.color)],
+            addr: format!(
+                "/track/{}/send/{}/{}",
+                get_track_guid(reaper, args.track),
+                args.send_index,
+                args.switch.as_str()
+            ),
+            args: vec![OscType::Bool(args.on)],
         }
     }
 
@@ -813,12 +1591,7531 @@ impl OscRoute for TrackColorRoute {
         reaper: &Reaper,
     ) -> Result<Self::SendParams, RouteError> {
         let track = get_track_by_guid(reaper, &params.track_guid)?;
-        unsafe {
-            let color = reaper.get_set_media_track_info_get_custom_color(track);
-            Ok(TrackColorArgs {
-                track,
-                color: color.color.to_raw(),
-            })
+        let count =
+            unsafe { reaper.get_track_num_sends(track, reaper_medium::TrackSendCategory::Send) };
+        if params.send_index >= count {
+            return Err(RouteError::ValueNotFound(format!(
+                "Send {} on track {}",
+                params.send_index, params.track_guid
+            )));
         }
+        Ok(TrackSendSwitchArgs {
+            track,
+            send_index: params.send_index,
+            switch: params.switch,
+            on: params.switch.get(reaper, track, params.send_index),
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/color
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - color (int): color of the track, represented as an RGB integer
+pub struct TrackColorRoute;
+pub struct TrackColorParams {
+    track_guid: String,
+}
+pub struct TrackColorArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub color: i32,
+}
+
+impl TrackColorRoute {
+    fn track_feedback(args: TrackColorArgs, reaper: &Reaper) -> TrackFeedback {
+        TrackFeedback::new(
+            reaper,
+            args.track,
+            TrackParam::Color,
+            vec![OscType::Int(args.color)],
+        )
+    }
+}
+
+impl OscRoute for TrackColorRoute {
+    type SendParams = TrackColorArgs;
+    type ReceiveParams = TrackColorParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "color"] => Some(TrackColorParams {
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        unsafe {
+            let int_arg = msg.args[0].clone().int().ok_or_else(|| {
+                ReceiverError::BadValue("Invalid color value, expected an integer".to_string())
+            })?;
+            reaper.get_set_media_track_info_set_custom_color(
+                track,
+                reaper_medium::NativeColorValue {
+                    color: reaper_medium::NativeColor::new(int_arg),
+                    is_used: true,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        Self::track_feedback(args, reaper).into_message()
+    }
+
+    fn build_feedback(args: Self::SendParams, reaper: &Reaper) -> Feedback {
+        Feedback::Track(Self::track_feedback(args, reaper))
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        unsafe {
+            let color = reaper.get_set_media_track_info_get_custom_color(track);
+            Ok(TrackColorArgs {
+                track,
+                color: color.color.to_raw(),
+            })
+        }
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /arpad/monitor
+/// Arguments:
+/// - enabled (bool): true echoes all incoming and outgoing messages for debugging
+/// - target (string): optional "ip:port" of a debug client; defaults to the REAPER console
+pub struct ArpadMonitorRoute;
+
+pub struct ArpadMonitorParams;
+
+impl OscRoute for ArpadMonitorRoute {
+    type SendParams = Option<MonitorTarget>;
+    type ReceiveParams = ArpadMonitorParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "monitor"] => Some(ArpadMonitorParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let enabled = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid monitor value, expected a bool".to_string())
+        })?;
+        if !enabled {
+            monitor::set_target(None);
+            return Ok(());
+        }
+        let target = match msg.args.get(1) {
+            Some(arg) => {
+                let addr = arg.clone().string().ok_or_else(|| {
+                    ReceiverError::BadValue("Invalid monitor target, expected a string".to_string())
+                })?;
+                MonitorTarget::Client(SocketAddrV4::from_str(&addr).map_err(|_| {
+                    ReceiverError::BadValue(format!("Invalid monitor target address: {}", addr))
+                })?)
+            }
+            None => MonitorTarget::Console,
+        };
+        monitor::set_target(Some(target));
+        Ok(())
+    }
+
+    fn build_message(target: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/monitor".to_string(),
+            args: vec![
+                OscType::Bool(target.is_some()),
+                OscType::String(target.map(|t| t.to_string()).unwrap_or_default()),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(monitor::target())
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/spatial/{param}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - param (string): one of azimuth, elevation or spread
+/// - value (float): normalized 0 to 1.0 value of the first matching parameter on the track's multichannel panner
+pub struct TrackSpatialRoute;
+
+pub struct TrackSpatialParams {
+    track_guid: String,
+    param: String,
+}
+
+pub struct TrackSpatialArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub param: String,
+    pub value: f64,
+}
+
+const SPATIAL_PARAMS: [&str; 3] = ["azimuth", "elevation", "spread"];
+
+impl OscRoute for TrackSpatialRoute {
+    type SendParams = TrackSpatialArgs;
+    type ReceiveParams = TrackSpatialParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "spatial", param] if SPATIAL_PARAMS.contains(param) => {
+                Some(TrackSpatialParams {
+                    track_guid: track_guid.to_string(),
+                    param: param.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (fx, param_index) = find_fx_param_by_name(reaper, track, &params.param)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("No {} parameter", params.param)))?;
+        let value = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid spatial value, expected a float".to_string())
+        })?;
+        unsafe {
+            reaper.track_fx_set_param_normalized(
+                track,
+                fx,
+                param_index,
+                reaper_medium::ReaperNormalizedFxParamValue::new(value as f64),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/spatial/{}", track_guid, args.param),
+            args: vec![OscType::Float(args.value as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (fx, param_index) = find_fx_param_by_name(reaper, track, &params.param)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("No {} parameter", params.param)))?;
+        unsafe {
+            let value = reaper.track_fx_get_param_normalized(track, fx, param_index);
+            Ok(TrackSpatialArgs {
+                track,
+                param: params.param.clone(),
+                value: value.get(),
+            })
+        }
+    }
+}
+
+const GROUP_FLAGS: [(&str, &CStr); 10] = [
+    ("volume-lead", c_str!("VOLUME_LEAD")),
+    ("volume-follow", c_str!("VOLUME_FOLLOW")),
+    ("pan-lead", c_str!("PAN_LEAD")),
+    ("pan-follow", c_str!("PAN_FOLLOW")),
+    ("mute-lead", c_str!("MUTE_LEAD")),
+    ("mute-follow", c_str!("MUTE_FOLLOW")),
+    ("solo-lead", c_str!("SOLO_LEAD")),
+    ("solo-follow", c_str!("SOLO_FOLLOW")),
+    ("rec-arm-lead", c_str!("RECARM_LEAD")),
+    ("rec-arm-follow", c_str!("RECARM_FOLLOW")),
+];
+
+fn group_flag_name(flag: &str) -> Option<&'static CStr> {
+    GROUP_FLAGS
+        .iter()
+        .find(|(name, _)| *name == flag)
+        .map(|(_, c_name)| *c_name)
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/group/{flag}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - flag (string): one of volume-lead, volume-follow, pan-lead, pan-follow, mute-lead, mute-follow, solo-lead, solo-follow, rec-arm-lead, rec-arm-follow
+/// - mask (int): bitmask of track groups 1-32 the track has this flag set for
+/// - high_mask (int): bitmask of track groups 33-64; optional when setting, leaving those groups alone
+pub struct TrackGroupRoute;
+
+pub struct TrackGroupParams {
+    track_guid: String,
+    flag: String,
+}
+
+pub struct TrackGroupArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub flag: String,
+    pub mask: u32,
+    pub high_mask: u32,
+}
+
+impl OscRoute for TrackGroupRoute {
+    type SendParams = TrackGroupArgs;
+    type ReceiveParams = TrackGroupParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "group", flag] => {
+                group_flag_name(flag)?;
+                Some(TrackGroupParams {
+                    track_guid: track_guid.to_string(),
+                    flag: flag.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let mask = msg.args[0].clone().int().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid group mask, expected an integer".to_string())
+        })?;
+        let high_mask = match msg.args.get(1) {
+            Some(arg) => Some(arg.clone().int().ok_or_else(|| {
+                ReceiverError::BadValue("Invalid high group mask, expected an integer".to_string())
+            })?),
+            None => None,
+        };
+        let group_name = group_flag_name(&params.flag).unwrap();
+        unsafe {
+            let low = reaper.low();
+            low.GetSetTrackGroupMembership(
+                track.as_ptr(),
+                group_name.as_ptr(),
+                u32::MAX,
+                mask as u32,
+            );
+            if let Some(high_mask) = high_mask {
+                low.GetSetTrackGroupMembershipHigh(
+                    track.as_ptr(),
+                    group_name.as_ptr(),
+                    u32::MAX,
+                    high_mask as u32,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/group/{}", track_guid, args.flag),
+            args: vec![
+                OscType::Int(args.mask as i32),
+                OscType::Int(args.high_mask as i32),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (mask, high_mask) =
+            get_group_masks(reaper, track, group_flag_name(&params.flag).unwrap());
+        Ok(TrackGroupArgs {
+            track,
+            flag: params.flag.clone(),
+            mask,
+            high_mask,
+        })
+    }
+}
+
+// Masks of groups 1-32 and 33-64 the track has a flag set for
+fn get_group_masks(
+    reaper: &Reaper,
+    track: reaper_medium::MediaTrack,
+    group_name: &CStr,
+) -> (u32, u32) {
+    unsafe {
+        let low = reaper.low();
+        (
+            low.GetSetTrackGroupMembership(track.as_ptr(), group_name.as_ptr(), 0, 0),
+            low.GetSetTrackGroupMembershipHigh(track.as_ptr(), group_name.as_ptr(), 0, 0),
+        )
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /groups
+/// Arguments:
+/// - track_count (int): number of tracks in the matrix; followed by a bundle per track with one /track/{track_guid}/group/{flag} message per flag, so large projects don't make one oversized reply
+pub struct GroupsRoute;
+
+pub struct GroupsParams;
+
+pub struct GroupsArgs {
+    pub track_count: i32,
+    pub memberships: Vec<TrackGroupArgs>,
+}
+
+impl OscRoute for GroupsRoute {
+    type SendParams = GroupsArgs;
+    type ReceiveParams = GroupsParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["groups"] => Some(GroupsParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/groups".to_string(),
+            args: vec![OscType::Int(args.track_count)],
+        }
+    }
+
+    fn build_packet(args: Self::SendParams, reaper: &Reaper) -> OscPacket {
+        immediate_bundle(Self::build_reply(args, reaper))
+    }
+
+    fn build_reply(args: Self::SendParams, reaper: &Reaper) -> Vec<OscPacket> {
+        let GroupsArgs {
+            track_count,
+            memberships,
+        } = args;
+        let header = Self::build_message(
+            GroupsArgs {
+                track_count,
+                memberships: Vec::new(),
+            },
+            reaper,
+        );
+        let mut reply = vec![OscPacket::Message(header)];
+        // Memberships come in runs of one track's flags
+        let mut memberships = memberships.into_iter().peekable();
+        while let Some(first) = memberships.next() {
+            let track = first.track;
+            let mut content = vec![OscPacket::Message(TrackGroupRoute::build_message(
+                first, reaper,
+            ))];
+            while let Some(next) = memberships.next_if(|m| m.track == track) {
+                content.push(OscPacket::Message(TrackGroupRoute::build_message(
+                    next, reaper,
+                )));
+            }
+            reply.push(immediate_bundle(content));
+        }
+        reply
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track_count = reaper.count_tracks(CurrentProject);
+        let mut memberships = Vec::new();
+        for i in 0..track_count {
+            let track = reaper.get_track(CurrentProject, i).unwrap();
+            for (flag, group_name) in GROUP_FLAGS {
+                let (mask, high_mask) = get_group_masks(reaper, track, group_name);
+                memberships.push(TrackGroupArgs {
+                    track,
+                    flag: flag.to_string(),
+                    mask,
+                    high_mask,
+                });
+            }
+        }
+        Ok(GroupsArgs {
+            track_count: track_count as i32,
+            memberships,
+        })
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /render/{kind}
+/// Arguments:
+/// - kind (string): "project" renders with the most recent render settings, "queue" renders all queued renders
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't start a second render
+pub struct RenderRoute;
+
+pub struct RenderParams {
+    kind: RenderKind,
+}
+
+impl OscRoute for RenderRoute {
+    type SendParams = RenderKind;
+    type ReceiveParams = RenderParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["render", kind] => Some(RenderParams {
+                kind: RenderKind::from_segment(kind)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        _: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        render::request(params.kind);
+        Ok(())
+    }
+
+    fn build_message(kind: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/render/{}", kind.as_str()),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.kind)
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /render/status
+/// Arguments:
+/// - kind (string): "project" or "queue"
+/// - state (string): "started" when a render begins, "complete" once it has finished
+pub struct RenderStatusRoute;
+
+pub struct RenderStatusParams;
+
+#[derive(Clone, Copy)]
+pub struct RenderStatusArgs {
+    pub kind: RenderKind,
+    pub state: RenderState,
+}
+
+impl OscRoute for RenderStatusRoute {
+    type SendParams = RenderStatusArgs;
+    type ReceiveParams = RenderStatusParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["render", "status"] => Some(RenderStatusParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/render/status".to_string(),
+            args: vec![
+                OscType::String(args.kind.as_str().to_string()),
+                OscType::String(args.state.as_str().to_string()),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        render::last_status()
+            .ok_or_else(|| RouteError::ValueNotFound("No render has been started".to_string()))
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /render/progress
+/// Arguments:
+/// - kind (string): "project" or "queue"
+/// - elapsed (float): seconds the render has taken so far; sent every second while rendering. REAPER doesn't tell how far a render has got, so this shows the render is still running rather than how much is left
+pub struct RenderProgressRoute;
+
+pub struct RenderProgressParams;
+
+#[derive(Clone, Copy)]
+pub struct RenderProgressArgs {
+    pub kind: RenderKind,
+    pub elapsed: f64,
+}
+
+impl OscRoute for RenderProgressRoute {
+    type SendParams = RenderProgressArgs;
+    type ReceiveParams = RenderProgressParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["render", "progress"] => Some(RenderProgressParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/render/progress".to_string(),
+            args: vec![
+                OscType::String(args.kind.as_str().to_string()),
+                OscType::Float(args.elapsed as f32),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        render::progress()
+            .ok_or_else(|| RouteError::ValueNotFound("No render is running".to_string()))
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /action/{command_id}
+/// Arguments:
+/// - command_id (int): id of a REAPER action in the main section, as shown in the action list; it must be listed in the surface's `actions`
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't run the action twice
+pub struct ActionRoute;
+
+pub struct ActionParams {
+    command_id: u32,
+}
+
+impl OscRoute for ActionRoute {
+    type SendParams = u32;
+    type ReceiveParams = ActionParams;
+
+    const UNDOABLE: bool = false;
+    const MAY_BLOCK: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["action", command_id] => Some(ActionParams {
+                command_id: command_id.parse().ok().filter(|&id| id > 0)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        if !surfaces::permits_action(&params.command_id.to_string()) {
+            return Err(ReceiverError::BadValue(format!(
+                "Surface not permitted to run action {}",
+                params.command_id
+            )));
+        }
+        run_action(reaper, params.command_id);
+        Ok(())
+    }
+
+    fn build_message(command_id: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/action/{}", command_id),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.command_id)
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /action/named/{command_name}
+/// Arguments:
+/// - command_name (string): command id of an extension or script action, e.g. _SWS_ABOUT, which stays the same across REAPER installs unlike numeric ids; the leading underscore is optional. It must be listed in the surface's `actions`
+/// - trigger (bool): optional; false or 0 is ignored
+pub struct NamedActionRoute;
+
+pub struct NamedActionParams {
+    command_name: String,
+}
+
+impl OscRoute for NamedActionRoute {
+    type SendParams = String;
+    type ReceiveParams = NamedActionParams;
+
+    const UNDOABLE: bool = false;
+    const MAY_BLOCK: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["action", "named", command_name] => Some(NamedActionParams {
+                command_name: command_name.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        if !surfaces::permits_action(&params.command_name) {
+            return Err(ReceiverError::BadValue(format!(
+                "Surface not permitted to run action {}",
+                params.command_name
+            )));
+        }
+        let command = named_command_id(reaper, &params.command_name)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("Action {}", params.command_name)))?;
+        run_action(reaper, command);
+        Ok(())
+    }
+
+    fn build_message(command_name: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/action/named/{}", command_name),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.command_name.clone())
+    }
+}
+
+// "Options: Solo in front"
+const SOLO_IN_FRONT_COMMAND: u32 = 40745;
+
+/// @osc-doc
+/// OSC Address: /solo/in-front
+/// Arguments:
+/// - in_front (bool): true means solo-in-front (solo dim) is used instead of muting unsoloed tracks
+pub struct SoloInFrontRoute;
+
+pub struct SoloInFrontParams;
+
+impl OscRoute for SoloInFrontRoute {
+    type SendParams = bool;
+    type ReceiveParams = SoloInFrontParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["solo", "in-front"] => Some(SoloInFrontParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let in_front = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid solo-in-front value, expected a bool".to_string())
+        })?;
+        set_toggle_action_state(reaper, SOLO_IN_FRONT_COMMAND, in_front);
+        Ok(())
+    }
+
+    fn build_message(in_front: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/solo/in-front".to_string(),
+            args: vec![OscType::Bool(in_front)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(get_toggle_action_state(reaper, SOLO_IN_FRONT_COMMAND))
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /solo/in-front/dim
+/// Arguments:
+/// - dim (float): attenuation in dB applied to unsoloed tracks while solo-in-front is enabled
+pub struct SoloDimRoute;
+
+pub struct SoloDimParams;
+
+impl OscRoute for SoloDimRoute {
+    type SendParams = f64;
+    type ReceiveParams = SoloDimParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["solo", "in-front", "dim"] => Some(SoloDimParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let dim_db = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid dim amount, expected a float".to_string())
+        })?;
+        let dim = get_config_var::<i32>(reaper, c_str!("solodimdb10"))
+            .ok_or_else(|| RouteError::ValueNotFound("solodimdb10".to_string()))?;
+        unsafe {
+            *dim = (dim_db * 10.0).round() as i32;
+        }
+        Ok(())
+    }
+
+    fn build_message(dim_db: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/solo/in-front/dim".to_string(),
+            args: vec![OscType::Float(dim_db as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let dim = get_config_var::<i32>(reaper, c_str!("solodimdb10"))
+            .ok_or_else(|| RouteError::ValueNotFound("solodimdb10".to_string()))?;
+        Ok(unsafe { *dim } as f64 / 10.0)
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /meters/reset
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't clear twice. Clears the peak holds and clip indicators of every track, the held peaks of the VU meters and the maximum peaks since play
+pub struct MetersResetRoute;
+
+pub struct MetersResetParams;
+
+impl OscRoute for MetersResetRoute {
+    type SendParams = ();
+    type ReceiveParams = MetersResetParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["meters", "reset"] => Some(MetersResetParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        let master = reaper.get_master_track(CurrentProject);
+        let tracks = (0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+            .chain(std::iter::once(master));
+        for track in tracks {
+            unsafe {
+                let channels = reaper.get_media_track_info_value(track, TrackAttributeKey::Nchan);
+                for ch in 0..channels as i32 {
+                    // Reading the peak hold with clear=true resets it along with the clip indicator
+                    reaper.low().Track_GetPeakHoldDB(track.as_ptr(), ch, true);
+                }
+            }
+        }
+        vu::reset_peak_holds();
+        metering::reset_max_peaks();
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/meters/reset".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct LoudnessArgs {
+    pub measure: LoudnessMeasure,
+    pub db: f64,
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /master/loudness/{measure}
+/// Arguments:
+/// - measure (string): "short-term" (3 second window) or "integrated" (gated, since playback started)
+/// - db (float): unweighted loudness of the master in dB, streamed while arpad is running; gated and windowed like BS.1770 but without K-weighting, so not LUFS
+pub struct MasterLoudnessRoute;
+
+pub struct MasterLoudnessParams {
+    measure: LoudnessMeasure,
+}
+
+impl OscRoute for MasterLoudnessRoute {
+    type SendParams = LoudnessArgs;
+    type ReceiveParams = MasterLoudnessParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["master", "loudness", measure] => Some(MasterLoudnessParams {
+                measure: LoudnessMeasure::from_segment(measure)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/master/loudness/{}", args.measure.as_str()),
+            args: vec![OscType::Float(args.db as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let master_guid = get_track_guid(reaper, reaper.get_master_track(CurrentProject));
+        let db = metering::latest_loudness(&master_guid, params.measure).ok_or_else(|| {
+            RouteError::ValueNotFound("Master loudness not metered yet".to_string())
+        })?;
+        Ok(LoudnessArgs {
+            measure: params.measure,
+            db,
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/loudness/{measure}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - measure (string): "short-term" or "integrated"
+/// - db (float): unweighted loudness of the track in dB, streamed for selected tracks when /loudness/include-selected is enabled
+pub struct TrackLoudnessRoute;
+
+pub struct TrackLoudnessParams {
+    track_guid: String,
+    measure: LoudnessMeasure,
+}
+
+pub struct TrackLoudnessArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub loudness: LoudnessArgs,
+}
+
+impl OscRoute for TrackLoudnessRoute {
+    type SendParams = TrackLoudnessArgs;
+    type ReceiveParams = TrackLoudnessParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "loudness", measure] => Some(TrackLoudnessParams {
+                track_guid: track_guid.to_string(),
+                measure: LoudnessMeasure::from_segment(measure)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!(
+                "/track/{}/loudness/{}",
+                track_guid,
+                args.loudness.measure.as_str()
+            ),
+            args: vec![OscType::Float(args.loudness.db as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let db =
+            metering::latest_loudness(&params.track_guid, params.measure).ok_or_else(|| {
+                RouteError::ValueNotFound("Track loudness is not being metered".to_string())
+            })?;
+        Ok(TrackLoudnessArgs {
+            track,
+            loudness: LoudnessArgs {
+                measure: params.measure,
+                db,
+            },
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /loudness/include-selected
+/// Arguments:
+/// - include (bool): true also streams loudness for the currently selected tracks
+pub struct LoudnessIncludeSelectedRoute;
+
+pub struct LoudnessIncludeSelectedParams;
+
+impl OscRoute for LoudnessIncludeSelectedRoute {
+    type SendParams = bool;
+    type ReceiveParams = LoudnessIncludeSelectedParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["loudness", "include-selected"] => Some(LoudnessIncludeSelectedParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let include = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid include-selected value, expected a bool".to_string())
+        })?;
+        metering::set_include_selected_tracks(include);
+        Ok(())
+    }
+
+    fn build_message(include: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/loudness/include-selected".to_string(),
+            args: vec![OscType::Bool(include)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(metering::include_selected_tracks())
+    }
+}
+
+// Main section toggle actions for each window
+const WINDOWS: [(&str, u32); 4] = [
+    // "View: Toggle mixer visible"
+    ("mixer", 40078),
+    // "Media explorer: Show/hide media explorer"
+    ("media-explorer", 50124),
+    // "View: Show FX browser window"
+    ("fx-browser", 40271),
+    // "View: Toggle fullscreen"
+    ("fullscreen", 40346),
+];
+
+/// @osc-doc
+/// OSC Address: /window/{window}
+/// Arguments:
+/// - window (string): one of mixer, media-explorer, fx-browser or fullscreen
+/// - visible (bool): true shows the window (or enters fullscreen), false hides it
+pub struct WindowRoute;
+
+pub struct WindowParams {
+    window: String,
+    command: u32,
+}
+
+pub struct WindowArgs {
+    pub window: String,
+    pub visible: bool,
+}
+
+impl OscRoute for WindowRoute {
+    type SendParams = WindowArgs;
+    type ReceiveParams = WindowParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["window", window] => {
+                let (_, command) = WINDOWS.iter().find(|(name, _)| name == window)?;
+                Some(WindowParams {
+                    window: window.to_string(),
+                    command: *command,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let visible = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid window visibility, expected a bool".to_string())
+        })?;
+        set_toggle_action_state(reaper, params.command, visible);
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/window/{}", args.window),
+            args: vec![OscType::Bool(args.visible)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(WindowArgs {
+            window: params.window.clone(),
+            visible: get_toggle_action_state(reaper, params.command),
+        })
+    }
+}
+
+// Theme ini keys reported by /theme/color
+const THEME_COLORS: [(&str, &CStr); 5] = [
+    ("background", c_str!("col_main_bg2")),
+    ("text", c_str!("col_main_text")),
+    ("track", c_str!("col_tr1_bg")),
+    ("track-selected", c_str!("selcol_tr1_bg")),
+    ("track-text", c_str!("col_tr1_text")),
+];
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /theme/color/{key}
+/// Arguments:
+/// - key (string): one of background, text, track, track-selected or track-text
+/// - color (int): color from the current theme, represented as an RGB integer like /track/{track_guid}/color
+pub struct ThemeColorRoute;
+
+pub struct ThemeColorParams {
+    key: String,
+    ini_key: &'static CStr,
+}
+
+pub struct ThemeColorArgs {
+    pub key: String,
+    pub color: i32,
+}
+
+impl OscRoute for ThemeColorRoute {
+    type SendParams = ThemeColorArgs;
+    type ReceiveParams = ThemeColorParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["theme", "color", key] => {
+                let (_, ini_key) = THEME_COLORS.iter().find(|(name, _)| name == key)?;
+                Some(ThemeColorParams {
+                    key: key.to_string(),
+                    ini_key,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/theme/color/{}", args.key),
+            args: vec![OscType::Int(args.color)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let color = unsafe { reaper.low().GetThemeColor(params.ini_key.as_ptr(), 0) };
+        if color == -1 {
+            return Err(RouteError::ValueNotFound(format!(
+                "Theme color {}",
+                params.key
+            )));
+        }
+        Ok(ThemeColorArgs {
+            key: params.key.clone(),
+            color,
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /tempomarker/count
+/// Arguments:
+/// - count (int): number of tempo/time signature markers in the project
+pub struct TempoMarkerCountRoute;
+
+pub struct TempoMarkerCountParams;
+
+impl OscRoute for TempoMarkerCountRoute {
+    type SendParams = u32;
+    type ReceiveParams = TempoMarkerCountParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["tempomarker", "count"] => Some(TempoMarkerCountParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(count: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/tempomarker/count".to_string(),
+            args: vec![OscType::Int(count as i32)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(tempo::count_tempo_markers(reaper))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TempoMarkerField {
+    Bpm,
+    Position,
+    TimeSig,
+}
+
+impl TempoMarkerField {
+    fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "bpm" => Some(TempoMarkerField::Bpm),
+            "position" => Some(TempoMarkerField::Position),
+            "timesig" => Some(TempoMarkerField::TimeSig),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            TempoMarkerField::Bpm => "bpm",
+            TempoMarkerField::Position => "position",
+            TempoMarkerField::TimeSig => "timesig",
+        }
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /tempomarker/{index}/{field}
+/// Arguments:
+/// - index (int): index of the tempo marker, in time order
+/// - field (string): one of bpm, position or timesig
+/// - bpm (float): tempo in beats per minute, for the bpm field
+/// - position (float): position of the marker in seconds, for the position field
+/// - numerator (int): time signature numerator, for the timesig field
+/// - denominator (int): time signature denominator, for the timesig field
+pub struct TempoMarkerRoute;
+
+pub struct TempoMarkerParams {
+    index: u32,
+    field: TempoMarkerField,
+}
+
+pub struct TempoMarkerArgs {
+    pub index: u32,
+    pub field: TempoMarkerField,
+    pub marker: TempoMarker,
+}
+
+impl OscRoute for TempoMarkerRoute {
+    type SendParams = TempoMarkerArgs;
+    type ReceiveParams = TempoMarkerParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["tempomarker", index, field] => Some(TempoMarkerParams {
+                index: index.parse().ok()?,
+                field: TempoMarkerField::from_segment(field)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let mut marker = tempo::get_tempo_marker(reaper, params.index)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("Tempo marker {}", params.index)))?;
+        match params.field {
+            TempoMarkerField::Bpm => {
+                marker.bpm = msg.args[0].clone().float().ok_or_else(|| {
+                    ReceiverError::BadValue("Invalid bpm, expected a float".to_string())
+                })? as f64;
+            }
+            TempoMarkerField::Position => {
+                marker.position = msg.args[0].clone().float().ok_or_else(|| {
+                    ReceiverError::BadValue("Invalid position, expected a float".to_string())
+                })? as f64;
+            }
+            TempoMarkerField::TimeSig => {
+                let bad_value = || {
+                    ReceiverError::BadValue(
+                        "Invalid time signature, expected two integers".to_string(),
+                    )
+                };
+                marker.timesig_num = msg
+                    .args
+                    .first()
+                    .and_then(|a| a.clone().int())
+                    .ok_or_else(bad_value)?;
+                marker.timesig_denom = msg
+                    .args
+                    .get(1)
+                    .and_then(|a| a.clone().int())
+                    .ok_or_else(bad_value)?;
+            }
+        }
+        if !tempo::set_tempo_marker(reaper, params.index, marker) {
+            return Err(ReceiverError::BadValue(format!(
+                "Failed to update tempo marker {}",
+                params.index
+            )));
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        let values = match args.field {
+            TempoMarkerField::Bpm => vec![OscType::Float(args.marker.bpm as f32)],
+            TempoMarkerField::Position => vec![OscType::Float(args.marker.position as f32)],
+            TempoMarkerField::TimeSig => vec![
+                OscType::Int(args.marker.timesig_num),
+                OscType::Int(args.marker.timesig_denom),
+            ],
+        };
+        OscMessage {
+            addr: format!("/tempomarker/{}/{}", args.index, args.field.as_str()),
+            args: values,
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let marker = tempo::get_tempo_marker(reaper, params.index)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("Tempo marker {}", params.index)))?;
+        Ok(TempoMarkerArgs {
+            index: params.index,
+            field: params.field,
+            marker,
+        })
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /marker/drop
+/// Arguments:
+/// - name (string): optional name for the marker, dropped at the play position (or edit cursor when stopped)
+pub struct MarkerDropRoute;
+
+pub struct MarkerDropParams;
+
+impl OscRoute for MarkerDropRoute {
+    type SendParams = String;
+    type ReceiveParams = MarkerDropParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["marker", "drop"] => Some(MarkerDropParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let name = match msg.args.first() {
+            Some(arg) => arg.clone().string().ok_or_else(|| {
+                ReceiverError::BadValue("Invalid marker name, expected a string".to_string())
+            })?,
+            None => String::new(),
+        };
+        markers::add_marker(reaper, markers::playhead_position(reaper), &name);
+        Ok(())
+    }
+
+    fn build_message(name: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/marker/drop".to_string(),
+            args: vec![OscType::String(name)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(String::new())
+    }
+}
+
+// StuffMIDIMessage mode that feeds the virtual MIDI keyboard queue
+const VKB_MIDI_MODE: i32 = 0;
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /vkb/note/{pitch}
+/// Arguments:
+/// - pitch (int): MIDI note number 0-127
+/// - velocity (float): note velocity, as an int 0-127 or a float normalized to 0 to 1.0; 0 sends note off
+/// - channel (int): optional MIDI channel 1-16, defaults to 1
+pub struct VkbNoteRoute;
+
+pub struct VkbNoteParams {
+    pitch: u8,
+}
+
+pub struct VkbNoteArgs {
+    pub pitch: u8,
+    pub velocity: u8,
+}
+
+impl OscRoute for VkbNoteRoute {
+    type SendParams = VkbNoteArgs;
+    type ReceiveParams = VkbNoteParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["vkb", "note", pitch] => {
+                let pitch: u8 = pitch.parse().ok()?;
+                (pitch < 128).then_some(VkbNoteParams { pitch })
+            }
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let velocity = match msg.args.first() {
+            Some(OscType::Int(v)) => (*v).clamp(0, 127),
+            Some(OscType::Float(v)) => (v.clamp(0.0, 1.0) * 127.0).round() as i32,
+            Some(OscType::Bool(on)) => {
+                if *on {
+                    100
+                } else {
+                    0
+                }
+            }
+            _ => {
+                return Err(ReceiverError::BadValue(
+                    "Invalid velocity, expected an int or float".to_string(),
+                ))
+            }
+        };
+        let channel = match msg.args.get(1) {
+            Some(arg) => arg
+                .clone()
+                .int()
+                .filter(|c| (1..=16).contains(c))
+                .ok_or_else(|| {
+                    ReceiverError::BadValue("Invalid MIDI channel, expected 1-16".to_string())
+                })?,
+            None => 1,
+        } - 1;
+        // Note on with velocity 0 is treated as note off by REAPER
+        let status = if velocity > 0 { 0x90 } else { 0x80 };
+        unsafe {
+            reaper.low().StuffMIDIMessage(
+                VKB_MIDI_MODE,
+                status | channel,
+                params.pitch as i32,
+                velocity,
+            );
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/vkb/note/{}", args.pitch),
+            args: vec![OscType::Int(args.velocity as i32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(VkbNoteArgs {
+            pitch: params.pitch,
+            velocity: 0,
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/input-peak/{channel}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - channel (int): index of the record input channel, starting at 0
+/// - peak (float): input peak level of the record-armed track, where 1.0 is 0dB
+pub struct TrackInputPeakRoute;
+
+pub struct TrackInputPeakParams {
+    track_guid: String,
+    channel: u32,
+}
+
+pub struct TrackInputPeakArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub channel: u32,
+    pub peak: f64,
+}
+
+impl OscRoute for TrackInputPeakRoute {
+    type SendParams = TrackInputPeakArgs;
+    type ReceiveParams = TrackInputPeakParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "input-peak", channel] => Some(TrackInputPeakParams {
+                track_guid: track_guid.to_string(),
+                channel: channel.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/input-peak/{}", track_guid, args.channel),
+            args: vec![OscType::Float(args.peak as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let channels = metering::record_input_channels(reaper, track).unwrap_or(0);
+        if params.channel >= channels {
+            return Err(RouteError::ValueNotFound(format!(
+                "Input channel {}",
+                params.channel
+            )));
+        }
+        let peak = unsafe {
+            reaper
+                .low()
+                .Track_GetPeakInfo(track.as_ptr(), params.channel as i32)
+        };
+        Ok(TrackInputPeakArgs {
+            track,
+            channel: params.channel,
+            peak,
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /bank/offset
+/// Arguments:
+/// - offset (int): index of the first track shown in the bank
+pub struct BankOffsetRoute;
+
+pub struct BankOffsetParams;
+
+impl OscRoute for BankOffsetRoute {
+    type SendParams = u32;
+    type ReceiveParams = BankOffsetParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["bank", "offset"] => Some(BankOffsetParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let offset = msg.args[0]
+            .clone()
+            .int()
+            .and_then(|o| u32::try_from(o).ok())
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid bank offset, expected a positive integer".to_string(),
+                )
+            })?;
+        bank::set_offset(offset);
+        Ok(())
+    }
+
+    fn build_message(offset: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/bank/offset".to_string(),
+            args: vec![OscType::Int(offset as i32)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(bank::bank().offset)
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /bank/size
+/// Arguments:
+/// - size (int): number of tracks shown in the bank
+pub struct BankSizeRoute;
+
+pub struct BankSizeParams;
+
+impl OscRoute for BankSizeRoute {
+    type SendParams = u32;
+    type ReceiveParams = BankSizeParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["bank", "size"] => Some(BankSizeParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let size = msg.args[0]
+            .clone()
+            .int()
+            .and_then(|s| u32::try_from(s).ok())
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid bank size, expected a positive integer".to_string(),
+                )
+            })?;
+        bank::set_size(size);
+        Ok(())
+    }
+
+    fn build_message(size: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/bank/size".to_string(),
+            args: vec![OscType::Int(size as i32)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(bank::bank().size)
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /bank/{slot}/guid
+/// Arguments:
+/// - slot (int): position within the bank, starting at 0
+/// - track_guid (string): unique identifier of the track shown in the slot (the send destination while spilled), empty if the slot is unused
+pub struct BankSlotGuidRoute;
+
+pub struct BankSlotGuidParams {
+    slot: u32,
+}
+
+pub struct BankSlotGuidArgs {
+    pub slot: u32,
+    pub track_guid: String,
+}
+
+impl OscRoute for BankSlotGuidRoute {
+    type SendParams = BankSlotGuidArgs;
+    type ReceiveParams = BankSlotGuidParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["bank", slot, "guid"] => Some(BankSlotGuidParams {
+                slot: slot.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/bank/{}/guid", args.slot),
+            args: vec![OscType::String(args.track_guid)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(BankSlotGuidArgs {
+            slot: params.slot,
+            track_guid: bank::slot_target(reaper, params.slot)
+                .and_then(|t| t.display_track(reaper))
+                .map(|t| get_track_guid(reaper, t))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /arpad/config/follow-selection
+/// Arguments:
+/// - follow (bool): true re-centers the bank on the track selected in REAPER
+pub struct FollowSelectionRoute;
+
+pub struct FollowSelectionParams;
+
+impl OscRoute for FollowSelectionRoute {
+    type SendParams = bool;
+    type ReceiveParams = FollowSelectionParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "config", "follow-selection"] => Some(FollowSelectionParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let follow = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid follow-selection value, expected a bool".to_string())
+        })?;
+        bank::set_follow_selection(follow);
+        Ok(())
+    }
+
+    fn build_message(follow: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/config/follow-selection".to_string(),
+            args: vec![OscType::Bool(follow)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(bank::bank().follow_selection)
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /bank/{slot}/volume
+/// Arguments:
+/// - slot (int): position within the bank, starting at 0
+/// - volume (float): volume of the track in the slot, or of the spilled send, normalized to 0 to 1.0 following REAPER's fader taper; linear gain or dB instead when the surface's volume mode says so
+pub struct BankSlotVolumeRoute;
+
+pub struct BankSlotVolumeParams {
+    slot: u32,
+}
+
+pub struct BankSlotVolumeArgs {
+    pub slot: u32,
+    pub volume: f64,
+}
+
+impl OscRoute for BankSlotVolumeRoute {
+    type SendParams = BankSlotVolumeArgs;
+    type ReceiveParams = BankSlotVolumeParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["bank", slot, "volume"] => Some(BankSlotVolumeParams {
+                slot: slot.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let target = bank::slot_target(reaper, params.slot)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("Bank slot {}", params.slot)))?;
+        let volume_raw = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid volume value, expected a float".to_string())
+        })?;
+        let volume = mode_value_to_volume(reaper, surfaces::volume_mode(), volume_raw as f64)
+            .ok_or_else(|| ReceiverError::BadValue("Invalid volume value".to_string()))?;
+        unsafe {
+            match target {
+                SlotTarget::Track(track) => {
+                    reaper.csurf_on_volume_change_ex(
+                        track,
+                        reaper_medium::ValueChange::Absolute(volume),
+                        reaper_medium::GangBehavior::DenyGang,
+                    );
+                }
+                SlotTarget::Send { track, send_index } => {
+                    reaper.set_track_send_ui_vol(
+                        track,
+                        reaper_medium::TrackSendRef::Send(send_index),
+                        volume,
+                        reaper_medium::EditMode::NormalTweak,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/bank/{}/volume", args.slot),
+            args: vec![OscType::Float(args.volume as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let target = bank::slot_target(reaper, params.slot)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("Bank slot {}", params.slot)))?;
+        Ok(BankSlotVolumeArgs {
+            slot: params.slot,
+            volume: volume_to_mode_value(reaper, surfaces::volume_mode(), target.volume(reaper)),
+        })
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /spill/{track_guid}
+/// Arguments:
+/// - track_guid (string): unique identifier of the track whose sends replace the tracks in the bank slots; "exit" returns the bank to tracks
+pub struct SpillRoute;
+
+pub struct SpillParams {
+    track_guid: Option<String>,
+}
+
+impl OscRoute for SpillRoute {
+    type SendParams = String;
+    type ReceiveParams = SpillParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["spill", "exit"] => Some(SpillParams { track_guid: None }),
+            ["spill", track_guid] => Some(SpillParams {
+                track_guid: Some(track_guid.to_string()),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        _: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if let Some(track_guid) = &params.track_guid {
+            get_track_by_guid(reaper, track_guid)?;
+        }
+        bank::set_spill(params.track_guid);
+        Ok(())
+    }
+
+    fn build_message(track_guid: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/spill/{}", track_guid),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params
+            .track_guid
+            .clone()
+            .unwrap_or_else(|| "exit".to_string()))
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /spill
+/// Arguments:
+/// - track_guid (string): unique identifier of the track whose sends are spilled onto the bank, empty when not spilled
+pub struct SpillTrackRoute;
+
+pub struct SpillTrackParams;
+
+impl OscRoute for SpillTrackRoute {
+    type SendParams = String;
+    type ReceiveParams = SpillTrackParams;
+
+    const READONLY: bool = true;
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["spill"] => Some(SpillTrackParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(track_guid: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/spill".to_string(),
+            args: vec![OscType::String(track_guid)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(bank::bank().spill.unwrap_or_default())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /master/hwout/count
+/// Arguments:
+/// - count (int): number of hardware outputs on the master track
+pub struct MasterHwOutCountRoute;
+
+pub struct MasterHwOutCountParams;
+
+impl OscRoute for MasterHwOutCountRoute {
+    type SendParams = u32;
+    type ReceiveParams = MasterHwOutCountParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["master", "hwout", "count"] => Some(MasterHwOutCountParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(count: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/master/hwout/count".to_string(),
+            args: vec![OscType::Int(count as i32)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let master = reaper.get_master_track(CurrentProject);
+        Ok(unsafe {
+            reaper.get_track_num_sends(master, reaper_medium::TrackSendCategory::HardwareOutput)
+        })
+    }
+}
+
+fn master_hwout(reaper: &Reaper, index: u32) -> Result<reaper_medium::MediaTrack, RouteError> {
+    let master = reaper.get_master_track(CurrentProject);
+    let count = unsafe {
+        reaper.get_track_num_sends(master, reaper_medium::TrackSendCategory::HardwareOutput)
+    };
+    if index >= count {
+        return Err(RouteError::ValueNotFound(format!(
+            "Hardware output {}",
+            index
+        )));
+    }
+    Ok(master)
+}
+
+/// @osc-doc
+/// OSC Address: /master/hwout/{index}/volume
+/// Arguments:
+/// - index (int): index of the hardware output on the master track
+/// - volume (float): volume of the hardware output, normalized to 0 to 1.0
+pub struct MasterHwOutVolumeRoute;
+
+pub struct MasterHwOutParams {
+    index: u32,
+}
+
+pub struct MasterHwOutVolumeArgs {
+    pub index: u32,
+    pub volume: f64,
+}
+
+impl OscRoute for MasterHwOutVolumeRoute {
+    type SendParams = MasterHwOutVolumeArgs;
+    type ReceiveParams = MasterHwOutParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["master", "hwout", index, "volume"] => Some(MasterHwOutParams {
+                index: index.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let master = master_hwout(reaper, params.index)?;
+        let volume_raw = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid volume value, expected a float".to_string())
+        })?;
+        let volume = normalized_to_volume(reaper, volume_raw as f64);
+        set_track_send_info_value(
+            reaper,
+            master,
+            reaper_medium::TrackSendCategory::HardwareOutput,
+            params.index,
+            c_str!("D_VOL"),
+            volume.get(),
+        )?;
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/master/hwout/{}/volume", args.index),
+            args: vec![OscType::Float(args.volume as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let master = master_hwout(reaper, params.index)?;
+        let volume = unsafe {
+            reaper.get_track_send_info_value(
+                master,
+                reaper_medium::TrackSendCategory::HardwareOutput,
+                params.index,
+                reaper_medium::TrackSendAttributeKey::Vol,
+            )
+        };
+        Ok(MasterHwOutVolumeArgs {
+            index: params.index,
+            volume: volume_to_normalized(
+                reaper,
+                reaper_medium::ReaperVolumeValue::new_panic(volume),
+            ),
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /master/hwout/{index}/mute
+/// Arguments:
+/// - index (int): index of the hardware output on the master track
+/// - mute (bool): true means the hardware output is muted
+pub struct MasterHwOutMuteRoute;
+
+pub struct MasterHwOutMuteArgs {
+    pub index: u32,
+    pub mute: bool,
+}
+
+impl OscRoute for MasterHwOutMuteRoute {
+    type SendParams = MasterHwOutMuteArgs;
+    type ReceiveParams = MasterHwOutParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["master", "hwout", index, "mute"] => Some(MasterHwOutParams {
+                index: index.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let master = master_hwout(reaper, params.index)?;
+        let mute = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid mute value, expected a bool".to_string())
+        })?;
+        set_track_send_info_value(
+            reaper,
+            master,
+            reaper_medium::TrackSendCategory::HardwareOutput,
+            params.index,
+            c_str!("B_MUTE"),
+            if mute { 1.0 } else { 0.0 },
+        )?;
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/master/hwout/{}/mute", args.index),
+            args: vec![OscType::Bool(args.mute)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let master = master_hwout(reaper, params.index)?;
+        let mute = unsafe {
+            reaper.get_track_send_info_value(
+                master,
+                reaper_medium::TrackSendCategory::HardwareOutput,
+                params.index,
+                reaper_medium::TrackSendAttributeKey::Mute,
+            )
+        };
+        Ok(MasterHwOutMuteArgs {
+            index: params.index,
+            mute: mute != 0.0,
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/peak/max
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - peak (float): highest peak level of the track since playback last started, where 1.0 is 0dB
+pub struct TrackMaxPeakRoute;
+
+pub struct TrackMaxPeakParams {
+    track_guid: String,
+}
+
+pub struct TrackMaxPeakArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub peak: f64,
+}
+
+impl OscRoute for TrackMaxPeakRoute {
+    type SendParams = TrackMaxPeakArgs;
+    type ReceiveParams = TrackMaxPeakParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "peak", "max"] => Some(TrackMaxPeakParams {
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/peak/max", track_guid),
+            args: vec![OscType::Float(args.peak as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(TrackMaxPeakArgs {
+            track,
+            peak: metering::max_peak_since_play(&params.track_guid).unwrap_or(0.0),
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/folder-depth
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - depth (int): how many folders the track is nested in, 0 for top-level tracks
+pub struct TrackFolderDepthRoute;
+
+pub struct TrackFolderDepthParams {
+    track_guid: String,
+}
+
+pub struct TrackFolderDepthArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub depth: i32,
+}
+
+impl OscRoute for TrackFolderDepthRoute {
+    type SendParams = TrackFolderDepthArgs;
+    type ReceiveParams = TrackFolderDepthParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "folder-depth"] => Some(TrackFolderDepthParams {
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/folder-depth", track_guid),
+            args: vec![OscType::Int(args.depth)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(TrackFolderDepthArgs {
+            track,
+            depth: unsafe { reaper.low().GetTrackDepth(track.as_ptr()) },
+        })
+    }
+}
+
+pub struct TrackOverview {
+    pub track: reaper_medium::MediaTrack,
+    pub index: i32,
+    pub name: String,
+    pub color: i32,
+    pub folder_depth: i32,
+    pub is_mute: bool,
+    pub is_solo: bool,
+    pub is_armed: bool,
+}
+
+impl TrackOverview {
+    fn collect(reaper: &Reaper, track: reaper_medium::MediaTrack) -> Self {
+        unsafe {
+            let flag =
+                |key: TrackAttributeKey| reaper.get_media_track_info_value(track, key) != 0.0;
+            TrackOverview {
+                track,
+                index: reaper.get_media_track_info_value(track, TrackAttributeKey::TrackNumber)
+                    as i32,
+                name: reaper
+                    .get_set_media_track_info_get_name(track, |name| name.to_str().to_string())
+                    .unwrap_or_default(),
+                color: reaper
+                    .get_set_media_track_info_get_custom_color(track)
+                    .color
+                    .to_raw(),
+                folder_depth: reaper.low().GetTrackDepth(track.as_ptr()),
+                is_mute: flag(TrackAttributeKey::Mute),
+                is_solo: flag(TrackAttributeKey::Solo),
+                is_armed: flag(TrackAttributeKey::RecArm),
+            }
+        }
+    }
+
+    fn into_bundle(self, reaper: &Reaper) -> OscPacket {
+        let track = self.track;
+        immediate_bundle(
+            [
+                TrackIndexRoute::build_message(
+                    TrackIndexArgs {
+                        track,
+                        index: self.index,
+                    },
+                    reaper,
+                ),
+                TrackNameRoute::build_message(
+                    TrackNameArgs {
+                        track,
+                        name: self.name,
+                    },
+                    reaper,
+                ),
+                TrackColorRoute::build_message(
+                    TrackColorArgs {
+                        track,
+                        color: self.color,
+                    },
+                    reaper,
+                ),
+                TrackFolderDepthRoute::build_message(
+                    TrackFolderDepthArgs {
+                        track,
+                        depth: self.folder_depth,
+                    },
+                    reaper,
+                ),
+                TrackMuteRoute::build_message(
+                    reaper_medium::SetSurfaceMuteArgs {
+                        track,
+                        is_mute: self.is_mute,
+                    },
+                    reaper,
+                ),
+                TrackSoloRoute::build_message(
+                    reaper_medium::SetSurfaceSoloArgs {
+                        track,
+                        is_solo: self.is_solo,
+                    },
+                    reaper,
+                ),
+                TrackRecArmRoute::build_message(
+                    reaper_medium::SetSurfaceRecArmArgs {
+                        track,
+                        is_armed: self.is_armed,
+                    },
+                    reaper,
+                ),
+            ]
+            .into_iter()
+            .map(OscPacket::Message)
+            .collect(),
+        )
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /overview
+/// Arguments:
+/// - track_count (int): number of tracks; followed in the same bundle by one bundle per track holding its index, name, color, folder-depth, mute, solo and rec-arm messages
+pub struct OverviewRoute;
+
+pub struct OverviewParams;
+
+impl OscRoute for OverviewRoute {
+    type SendParams = Vec<TrackOverview>;
+    type ReceiveParams = OverviewParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["overview"] => Some(OverviewParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(tracks: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/overview".to_string(),
+            args: vec![OscType::Int(tracks.len() as i32)],
+        }
+    }
+
+    fn build_packet(tracks: Self::SendParams, reaper: &Reaper) -> OscPacket {
+        let header = OscMessage {
+            addr: "/overview".to_string(),
+            args: vec![OscType::Int(tracks.len() as i32)],
+        };
+        let mut content = vec![OscPacket::Message(header)];
+        content.extend(tracks.into_iter().map(|t| t.into_bundle(reaper)));
+        immediate_bundle(content)
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok((0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+            .map(|track| TrackOverview::collect(reaper, track))
+            .collect())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /cue/create
+/// Arguments:
+/// - name (string): optional name for the cue bus; a pre-fader send from every other track feeds the new bus
+pub struct CueCreateRoute;
+
+pub struct CueCreateParams;
+
+impl OscRoute for CueCreateRoute {
+    type SendParams = String;
+    type ReceiveParams = CueCreateParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cue", "create"] => Some(CueCreateParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let name = match msg.args.first() {
+            Some(arg) => arg.clone().string().ok_or_else(|| {
+                ReceiverError::BadValue("Invalid cue name, expected a string".to_string())
+            })?,
+            None => format!("Cue {}", cuemix::cue_buses(reaper).len() + 1),
+        };
+        cuemix::create_cue_bus(reaper, &name)?;
+        Ok(())
+    }
+
+    fn build_message(name: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/cue/create".to_string(),
+            args: vec![OscType::String(name)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(String::new())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /cues
+/// Arguments:
+/// - cue_guids (string...): GUID of every cue bus, in track order
+pub struct CuesRoute;
+
+pub struct CuesParams;
+
+impl OscRoute for CuesRoute {
+    type SendParams = Vec<String>;
+    type ReceiveParams = CuesParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cues"] => Some(CuesParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(guids: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/cues".to_string(),
+            args: guids.into_iter().map(OscType::String).collect(),
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(cuemix::cue_buses(reaper)
+            .into_iter()
+            .map(|track| get_track_guid(reaper, track))
+            .collect())
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /cue/{cue_guid}/send/{track_guid}/level
+/// Arguments:
+/// - cue_guid (string): unique identifier for the cue bus
+/// - track_guid (string): unique identifier for the track feeding the cue bus
+/// - level (float): level of the track in the cue mix, normalized to 0 to 1.0
+pub struct CueSendLevelRoute;
+
+pub struct CueSendLevelParams {
+    cue_guid: String,
+    track_guid: String,
+}
+
+pub struct CueSendLevelArgs {
+    pub cue: reaper_medium::MediaTrack,
+    pub source: reaper_medium::MediaTrack,
+    pub level: f64,
+}
+
+fn find_cue_send(
+    reaper: &Reaper,
+    params: &CueSendLevelParams,
+) -> Result<(reaper_medium::MediaTrack, reaper_medium::MediaTrack, u32), RouteError> {
+    let cue = get_track_by_guid(reaper, &params.cue_guid)?;
+    let source = get_track_by_guid(reaper, &params.track_guid)?;
+    let send_index = cuemix::cue_send_index(reaper, cue, source).ok_or_else(|| {
+        RouteError::ValueNotFound(format!(
+            "Send from {} to cue {}",
+            params.track_guid, params.cue_guid
+        ))
+    })?;
+    Ok((cue, source, send_index))
+}
+
+impl OscRoute for CueSendLevelRoute {
+    type SendParams = CueSendLevelArgs;
+    type ReceiveParams = CueSendLevelParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cue", cue_guid, "send", track_guid, "level"] => Some(CueSendLevelParams {
+                cue_guid: cue_guid.to_string(),
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let (_, source, send_index) = find_cue_send(reaper, &params)?;
+        let level = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid level value, expected a float".to_string())
+        })?;
+        unsafe {
+            reaper.set_track_send_ui_vol(
+                source,
+                reaper_medium::TrackSendRef::Send(send_index),
+                normalized_to_volume(reaper, level as f64),
+                reaper_medium::EditMode::NormalTweak,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!(
+                "/cue/{}/send/{}/level",
+                get_track_guid(reaper, args.cue),
+                get_track_guid(reaper, args.source)
+            ),
+            args: vec![OscType::Float(args.level as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let (cue, source, send_index) = find_cue_send(reaper, params)?;
+        let volume = cuemix::cue_send_volume(reaper, source, send_index);
+        Ok(CueSendLevelArgs {
+            cue,
+            source,
+            level: volume_to_normalized(
+                reaper,
+                reaper_medium::ReaperVolumeValue::new_panic(volume),
+            ),
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /talkback
+/// Arguments:
+/// - held (bool): true while the talk button is held; unmutes the talkback track and dims the cue mixes
+pub struct TalkbackRoute;
+
+pub struct TalkbackParams;
+
+impl OscRoute for TalkbackRoute {
+    type SendParams = bool;
+    type ReceiveParams = TalkbackParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["talkback"] => Some(TalkbackParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let held = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid talkback value, expected a bool".to_string())
+        })?;
+        talkback::set_held(reaper, held)?;
+        Ok(())
+    }
+
+    fn build_message(held: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/talkback".to_string(),
+            args: vec![OscType::Bool(held)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(talkback::is_held())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /cue/next
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't skip a second cue
+pub struct CueNextRoute;
+
+pub struct CueStepParams;
+
+// Whether a message fires a trigger route. Buttons send false or 0 when released,
+// which must not fire the route a second time; a message without arguments fires it.
+fn is_trigger(msg: &OscMessage) -> bool {
+    match msg.args.first() {
+        Some(OscType::Bool(b)) => *b,
+        Some(OscType::Int(i)) => *i != 0,
+        Some(OscType::Long(l)) => *l != 0,
+        Some(OscType::Float(f)) => *f != 0.0,
+        Some(OscType::Double(d)) => *d != 0.0,
+        _ => true,
+    }
+}
+
+impl OscRoute for CueNextRoute {
+    type SendParams = ();
+    type ReceiveParams = CueStepParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cue", "next"] => Some(CueStepParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) {
+            setlist::step(reaper, 1)
+                .ok_or_else(|| RouteError::ValueNotFound("Next cue".to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/cue/next".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /cue/prev
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't skip a second cue
+pub struct CuePrevRoute;
+
+impl OscRoute for CuePrevRoute {
+    type SendParams = ();
+    type ReceiveParams = CueStepParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cue", "prev"] => Some(CueStepParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) {
+            setlist::step(reaper, -1)
+                .ok_or_else(|| RouteError::ValueNotFound("Previous cue".to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/cue/prev".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /cue/goto/{n}
+/// Arguments:
+/// - n (int): number of the cue in the cue list, starting at 1
+/// - trigger (bool): optional; false or 0 is ignored
+pub struct CueGotoRoute;
+
+pub struct CueGotoParams {
+    n: usize,
+}
+
+impl OscRoute for CueGotoRoute {
+    type SendParams = usize;
+    type ReceiveParams = CueGotoParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cue", "goto", n] => Some(CueGotoParams {
+                n: n.parse().ok().filter(|&n| n > 0)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) {
+            setlist::goto(reaper, params.n - 1)
+                .ok_or_else(|| RouteError::ValueNotFound(format!("Cue {}", params.n)))?;
+        }
+        Ok(())
+    }
+
+    fn build_message(n: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/cue/goto/{}", n),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.n)
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /cue/current
+/// Arguments:
+/// - n (int): number of the current cue, starting at 1; 0 before the first cue
+/// - name (string): name of the current cue
+/// - remaining (float): seconds left until the end of the current cue
+pub struct CueCurrentRoute;
+
+pub struct CueCurrentParams;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueCurrentArgs {
+    pub n: i32,
+    pub name: String,
+    pub remaining: f32,
+}
+
+impl CueCurrentArgs {
+    pub fn collect(reaper: &Reaper) -> Self {
+        match setlist::current_cue(reaper) {
+            Some((index, cue)) => {
+                let remaining = (cue.end - markers::playhead_position(reaper)).max(0.0);
+                CueCurrentArgs {
+                    n: index as i32 + 1,
+                    name: cue.name,
+                    // Tenths of a second are enough for a countdown display
+                    remaining: ((remaining * 10.0).round() / 10.0) as f32,
+                }
+            }
+            None => CueCurrentArgs {
+                n: 0,
+                name: String::new(),
+                remaining: 0.0,
+            },
+        }
+    }
+}
+
+impl OscRoute for CueCurrentRoute {
+    type SendParams = CueCurrentArgs;
+    type ReceiveParams = CueCurrentParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["cue", "current"] => Some(CueCurrentParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/cue/current".to_string(),
+            args: vec![
+                OscType::Int(args.n),
+                OscType::String(args.name),
+                OscType::Float(args.remaining),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(CueCurrentArgs::collect(reaper))
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /click/output/hw/{channel}
+/// Arguments:
+/// - channel (int): first hardware output channel of the pair, starting at 0
+/// - enabled (bool): whether the click track is routed to this hardware output
+pub struct ClickHwOutputRoute;
+
+pub struct ClickHwOutputParams {
+    channel: u32,
+}
+
+pub struct ClickHwOutputArgs {
+    pub channel: u32,
+    pub enabled: bool,
+}
+
+impl OscRoute for ClickHwOutputRoute {
+    type SendParams = ClickHwOutputArgs;
+    type ReceiveParams = ClickHwOutputParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["click", "output", "hw", channel] => Some(ClickHwOutputParams {
+                channel: channel.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let enabled = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid click output value, expected a bool".to_string())
+        })?;
+        let track = click::click_track(reaper)?;
+        click::set_hw_output(reaper, track, params.channel, enabled)?;
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/click/output/hw/{}", args.channel),
+            args: vec![OscType::Bool(args.enabled)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = click::click_track(reaper)?;
+        Ok(ClickHwOutputArgs {
+            channel: params.channel,
+            enabled: click::hw_output_index(reaper, track, params.channel).is_some(),
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /click/output/cue/{cue_guid}
+/// Arguments:
+/// - cue_guid (string): unique identifier for the cue bus
+/// - enabled (bool): whether the click track is sent to this cue bus
+pub struct ClickCueOutputRoute;
+
+pub struct ClickCueOutputParams {
+    cue_guid: String,
+}
+
+pub struct ClickCueOutputArgs {
+    pub cue: reaper_medium::MediaTrack,
+    pub enabled: bool,
+}
+
+impl OscRoute for ClickCueOutputRoute {
+    type SendParams = ClickCueOutputArgs;
+    type ReceiveParams = ClickCueOutputParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["click", "output", "cue", cue_guid] => Some(ClickCueOutputParams {
+                cue_guid: cue_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let enabled = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid click output value, expected a bool".to_string())
+        })?;
+        let track = click::click_track(reaper)?;
+        let cue = get_track_by_guid(reaper, &params.cue_guid)?;
+        click::set_cue_output(reaper, track, cue, enabled)?;
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/click/output/cue/{}", get_track_guid(reaper, args.cue)),
+            args: vec![OscType::Bool(args.enabled)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = click::click_track(reaper)?;
+        let cue = get_track_by_guid(reaper, &params.cue_guid)?;
+        Ok(ClickCueOutputArgs {
+            cue,
+            enabled: cuemix::cue_send_index(reaper, cue, track).is_some(),
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /click/volume/{beat}
+/// Arguments:
+/// - beat (string): "accent" for the first beat of each measure, "beat" for the others
+/// - volume (float): metronome volume, normalized to 0 to 1.0
+pub struct ClickVolumeRoute;
+
+pub struct ClickVolumeParams {
+    beat: ClickBeat,
+}
+
+pub struct ClickVolumeArgs {
+    pub beat: ClickBeat,
+    pub volume: f64,
+}
+
+impl OscRoute for ClickVolumeRoute {
+    type SendParams = ClickVolumeArgs;
+    type ReceiveParams = ClickVolumeParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["click", "volume", beat] => Some(ClickVolumeParams {
+                beat: ClickBeat::from_segment(beat)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let volume = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid volume value, expected a float".to_string())
+        })?;
+        let volume = normalized_to_volume(reaper, volume as f64);
+        click::set_volume(reaper, params.beat, volume.get()).ok_or_else(|| {
+            RouteError::ValueNotFound(format!("Metronome volume {}", params.beat.as_str()))
+        })?;
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/click/volume/{}", args.beat.as_str()),
+            args: vec![OscType::Float(args.volume as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let volume = click::volume(reaper, params.beat).ok_or_else(|| {
+            RouteError::ValueNotFound(format!("Metronome volume {}", params.beat.as_str()))
+        })?;
+        Ok(ClickVolumeArgs {
+            beat: params.beat,
+            volume: volume_to_normalized(
+                reaper,
+                reaper_medium::ReaperVolumeValue::new_panic(volume),
+            ),
+        })
+    }
+}
+
+// Metronome flags preference; this bit enables the count-in before recording
+const METRONOME_FLAGS_VAR: &CStr = c_str!("projmetroen");
+const COUNT_IN_RECORD_FLAG: i32 = 4;
+const COUNT_IN_LENGTH_VAR: &CStr = c_str!("projmetrocountin");
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /transport/count-in
+    /// Arguments:
+    /// - measures (int): length of the count-in before recording, in measures; 0 disables the count-in
+    pub struct CountInRoute;
+    address: ["transport", "count-in"];
+    params: CountInParams {};
+    value: i32;
+    get(reaper, params) {
+        let flags = get_config_var::<i32>(reaper, METRONOME_FLAGS_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("projmetroen".to_string()))?;
+        let length = get_config_var::<i32>(reaper, COUNT_IN_LENGTH_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("projmetrocountin".to_string()))?;
+        unsafe {
+            if *flags & COUNT_IN_RECORD_FLAG == 0 {
+                Ok(0)
+            } else {
+                Ok(*length)
+            }
+        }
+    }
+    set(reaper, params, measures) {
+        if measures < 0 {
+            return Err(ReceiverError::BadValue(
+                "Invalid count-in length, expected 0 or more measures".to_string(),
+            ));
+        }
+        let flags = get_config_var::<i32>(reaper, METRONOME_FLAGS_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("projmetroen".to_string()))?;
+        let length = get_config_var::<i32>(reaper, COUNT_IN_LENGTH_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("projmetrocountin".to_string()))?;
+        unsafe {
+            if measures == 0 {
+                *flags &= !COUNT_IN_RECORD_FLAG;
+            } else {
+                *flags |= COUNT_IN_RECORD_FLAG;
+                *length = measures;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Pre-roll preference: bit 0 pre-rolls before playback, bit 1 before recording
+const PREROLL_FLAGS_VAR: &CStr = c_str!("preroll");
+const PREROLL_LENGTH_VAR: &CStr = c_str!("prerollmeas");
+
+#[derive(Clone, Copy)]
+pub enum PrerollMode {
+    Play,
+    Record,
+}
+
+impl PrerollMode {
+    fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "play" => Some(PrerollMode::Play),
+            "record" => Some(PrerollMode::Record),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            PrerollMode::Play => "play",
+            PrerollMode::Record => "record",
+        }
+    }
+
+    fn flag(&self) -> i32 {
+        match self {
+            PrerollMode::Play => 1,
+            PrerollMode::Record => 2,
+        }
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /transport/preroll/{mode}
+/// Arguments:
+/// - mode (string): "play" or "record"
+/// - enabled (bool): whether pre-roll is used before starting playback or recording
+pub struct PrerollRoute;
+
+pub struct PrerollParams {
+    mode: PrerollMode,
+}
+
+pub struct PrerollArgs {
+    pub mode: PrerollMode,
+    pub enabled: bool,
+}
+
+impl OscRoute for PrerollRoute {
+    type SendParams = PrerollArgs;
+    type ReceiveParams = PrerollParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["transport", "preroll", mode] => Some(PrerollParams {
+                mode: PrerollMode::from_segment(mode)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let enabled = msg.args[0].clone().bool().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid pre-roll value, expected a bool".to_string())
+        })?;
+        let flags = get_config_var::<i32>(reaper, PREROLL_FLAGS_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("preroll".to_string()))?;
+        unsafe {
+            if enabled {
+                *flags |= params.mode.flag();
+            } else {
+                *flags &= !params.mode.flag();
+            }
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/transport/preroll/{}", args.mode.as_str()),
+            args: vec![OscType::Bool(args.enabled)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let flags = get_config_var::<i32>(reaper, PREROLL_FLAGS_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("preroll".to_string()))?;
+        Ok(PrerollArgs {
+            mode: params.mode,
+            enabled: unsafe { *flags } & params.mode.flag() != 0,
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /transport/preroll/measures
+    /// Arguments:
+    /// - measures (float): length of the pre-roll, in measures
+    pub struct PrerollMeasuresRoute;
+    address: ["transport", "preroll", "measures"];
+    params: PrerollMeasuresParams {};
+    value: f64;
+    get(reaper, params) {
+        let length = get_config_var::<f64>(reaper, PREROLL_LENGTH_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("prerollmeas".to_string()))?;
+        Ok(unsafe { *length })
+    }
+    set(reaper, params, measures) {
+        let length = get_config_var::<f64>(reaper, PREROLL_LENGTH_VAR)
+            .ok_or_else(|| RouteError::ValueNotFound("prerollmeas".to_string()))?;
+        unsafe {
+            *length = measures.max(0.0);
+        }
+        Ok(())
+    }
+}
+
+// Reads the optional name, color and template arguments shared by the track creation
+// routes; empty names and templates are treated as absent
+fn new_track_options(msg: &OscMessage) -> Result<NewTrackOptions, ReceiverError> {
+    let string_arg = |i: usize, what: &str| -> Result<Option<String>, ReceiverError> {
+        match msg.args.get(i) {
+            Some(arg) => {
+                let s = arg.clone().string().ok_or_else(|| {
+                    ReceiverError::BadValue(format!("Invalid {}, expected a string", what))
+                })?;
+                Ok((!s.is_empty()).then_some(s))
+            }
+            None => Ok(None),
+        }
+    };
+    let color = match msg.args.get(1) {
+        Some(arg) => Some(arg.clone().int().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid color value, expected an integer".to_string())
+        })?),
+        None => None,
+    };
+    Ok(NewTrackOptions {
+        name: string_arg(0, "track name")?,
+        color,
+        template: string_arg(2, "track template")?,
+    })
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /track/create
+/// Arguments:
+/// - name (string): optional name for the new track
+/// - color (int): optional native color for the new track
+/// - template (string): optional track template, as a path or a name in the TrackTemplates directory
+pub struct TrackCreateRoute;
+
+pub struct TrackCreateParams;
+
+impl OscRoute for TrackCreateRoute {
+    type SendParams = ();
+    type ReceiveParams = TrackCreateParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", "create"] => Some(TrackCreateParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        tracks::create_track(reaper, &new_track_options(msg)?)?;
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/track/create".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /track/{track_guid}/duplicate
+/// Arguments:
+/// - track_guid (string): unique identifier for the track to duplicate
+/// - name (string): optional name for the copy
+/// - color (int): optional native color for the copy
+pub struct TrackDuplicateRoute;
+
+pub struct TrackDuplicateParams {
+    track_guid: String,
+}
+
+impl OscRoute for TrackDuplicateRoute {
+    type SendParams = String;
+    type ReceiveParams = TrackDuplicateParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "duplicate"] => Some(TrackDuplicateParams {
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        tracks::duplicate_track(reaper, track, &new_track_options(msg)?)?;
+        Ok(())
+    }
+
+    fn build_message(track_guid: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/track/{}/duplicate", track_guid),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.track_guid.clone())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum ModSetting {
+    Lfo,
+    Rate,
+    Depth,
+    Baseline,
+}
+
+impl ModSetting {
+    fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "lfo" => Some(ModSetting::Lfo),
+            "rate" => Some(ModSetting::Rate),
+            "depth" => Some(ModSetting::Depth),
+            "baseline" => Some(ModSetting::Baseline),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ModSetting::Lfo => "lfo",
+            ModSetting::Rate => "rate",
+            ModSetting::Depth => "depth",
+            ModSetting::Baseline => "baseline",
+        }
+    }
+
+    // Named config parameter holding the setting (see TrackFX_GetNamedConfigParm)
+    fn config_name(&self, param_index: u32) -> String {
+        let key = match self {
+            ModSetting::Lfo => "lfo.active",
+            ModSetting::Rate => "lfo.speed",
+            ModSetting::Depth => "lfo.strength",
+            ModSetting::Baseline => "mod.baseline",
+        };
+        format!("param.{}.{}", param_index, key)
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/fx/{fx_index}/param/{param_index}/mod/{setting}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - fx_index (int): index of the FX in the track's FX chain
+/// - param_index (int): index of the parameter on the FX
+/// - setting (string): "lfo" (bool, LFO enabled), "rate" (float, LFO speed in Hz), "depth" (float, LFO strength from 0 to 1.0) or "baseline" (float, value the modulation is applied around, from 0 to 1.0)
+pub struct TrackFxParamModRoute;
+
+pub struct TrackFxParamModParams {
+    track_guid: String,
+    fx_index: u32,
+    param_index: u32,
+    setting: ModSetting,
+}
+
+pub struct TrackFxParamModArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub fx_index: u32,
+    pub param_index: u32,
+    pub setting: ModSetting,
+    pub value: f64,
+}
+
+impl OscRoute for TrackFxParamModRoute {
+    type SendParams = TrackFxParamModArgs;
+    type ReceiveParams = TrackFxParamModParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "fx", fx_index, "param", param_index, "mod", setting] => {
+                Some(TrackFxParamModParams {
+                    track_guid: track_guid.to_string(),
+                    fx_index: fx_index.parse().ok()?,
+                    param_index: param_index.parse().ok()?,
+                    setting: ModSetting::from_segment(setting)?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let value = match params.setting {
+            ModSetting::Lfo => {
+                let enabled = msg.args[0].clone().bool().ok_or_else(|| {
+                    ReceiverError::BadValue("Invalid LFO value, expected a bool".to_string())
+                })?;
+                if enabled {
+                    // The LFO only runs while parameter modulation is active
+                    set_fx_named_config(
+                        reaper,
+                        track,
+                        params.fx_index,
+                        &format!("param.{}.mod.active", params.param_index),
+                        "1",
+                    )?;
+                }
+                let value = if enabled { "1" } else { "0" };
+                value.to_string()
+            }
+            _ => msg.args[0]
+                .clone()
+                .float()
+                .ok_or_else(|| {
+                    ReceiverError::BadValue(format!(
+                        "Invalid modulation {}, expected a float",
+                        params.setting.as_str()
+                    ))
+                })?
+                .to_string(),
+        };
+        set_fx_named_config(
+            reaper,
+            track,
+            params.fx_index,
+            &params.setting.config_name(params.param_index),
+            &value,
+        )?;
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let value = match args.setting {
+            ModSetting::Lfo => OscType::Bool(args.value != 0.0),
+            _ => OscType::Float(args.value as f32),
+        };
+        OscMessage {
+            addr: format!(
+                "/track/{}/fx/{}/param/{}/mod/{}",
+                get_track_guid(reaper, args.track),
+                args.fx_index,
+                args.param_index,
+                args.setting.as_str()
+            ),
+            args: vec![value],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let name = params.setting.config_name(params.param_index);
+        let value = get_fx_named_config(reaper, track, params.fx_index, &name)
+            .and_then(|v| v.trim().parse().ok())
+            .ok_or_else(|| {
+                RouteError::ValueNotFound(format!("FX {} config {}", params.fx_index, name))
+            })?;
+        Ok(TrackFxParamModArgs {
+            track,
+            fx_index: params.fx_index,
+            param_index: params.param_index,
+            setting: params.setting,
+            value,
+        })
+    }
+}
+
+fn resolve_strip_control(
+    reaper: &Reaper,
+    track: reaper_medium::MediaTrack,
+    control: &str,
+) -> Result<(reaper_medium::TrackFxLocation, u32), RouteError> {
+    let mapping = strip::mapping(reaper, control)
+        .ok_or_else(|| RouteError::ValueNotFound(format!("Strip control {}", control)))?;
+    strip::resolve(reaper, track, &mapping).ok_or_else(|| {
+        RouteError::ValueNotFound(format!(
+            "Parameter {} on FX {} for strip control {}",
+            mapping.param, mapping.fx, control
+        ))
+    })
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/strip/{control}
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - control (string): channel strip control, e.g. "gain", "hpf" or "comp-threshold", mapped to an FX parameter in the config file or the project
+/// - value (float): normalized value of the mapped parameter, from 0 to 1.0
+pub struct TrackStripRoute;
+
+pub struct TrackStripParams {
+    track_guid: String,
+    control: String,
+}
+
+pub struct TrackStripArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub control: String,
+    pub value: f64,
+}
+
+impl OscRoute for TrackStripRoute {
+    type SendParams = TrackStripArgs;
+    type ReceiveParams = TrackStripParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "strip", control] => Some(TrackStripParams {
+                track_guid: track_guid.to_string(),
+                control: control.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (fx, param_index) = resolve_strip_control(reaper, track, &params.control)?;
+        let value = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid strip value, expected a float".to_string())
+        })?;
+        unsafe {
+            reaper.track_fx_set_param_normalized(
+                track,
+                fx,
+                param_index,
+                reaper_medium::ReaperNormalizedFxParamValue::new(value as f64),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let track_guid = get_track_guid(reaper, args.track);
+        OscMessage {
+            addr: format!("/track/{}/strip/{}", track_guid, args.control),
+            args: vec![OscType::Float(args.value as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (fx, param_index) = resolve_strip_control(reaper, track, &params.control)?;
+        let value = unsafe { reaper.track_fx_get_param_normalized(track, fx, param_index) };
+        Ok(TrackStripArgs {
+            track,
+            control: params.control.clone(),
+            value: value.get(),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/input-gain
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - gain (float): input gain in the units of the parameter behind it, dB with REAPER's JS volume adjustment plugin. The parameter is the "input-gain" strip control when mapped, otherwise the first volume adjustment plugin among the track's input FX and then its FX chain
+    pub struct TrackInputGainRoute;
+    address: ["track", track_guid, "input-gain"];
+    params: TrackInputGainParams { track_guid: String };
+    value: f64;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (fx, param_index) = resolve_input_gain(reaper, track)?;
+        let (mut min, mut max) = (0.0, 0.0);
+        Ok(unsafe {
+            reaper.low().TrackFX_GetParam(
+                track.as_ptr(),
+                fx.to_raw(),
+                param_index as i32,
+                &mut min,
+                &mut max,
+            )
+        })
+    }
+    set(reaper, params, gain) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let (fx, param_index) = resolve_input_gain(reaper, track)?;
+        let (mut min, mut max) = (0.0, 0.0);
+        unsafe {
+            let low = reaper.low();
+            low.TrackFX_GetParam(
+                track.as_ptr(),
+                fx.to_raw(),
+                param_index as i32,
+                &mut min,
+                &mut max,
+            );
+            low.TrackFX_SetParam(
+                track.as_ptr(),
+                fx.to_raw(),
+                param_index as i32,
+                gain.clamp(min, max),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn resolve_input_gain(
+    reaper: &Reaper,
+    track: reaper_medium::MediaTrack,
+) -> Result<(reaper_medium::TrackFxLocation, u32), RouteError> {
+    strip::resolve_input_gain(reaper, track).ok_or_else(|| {
+        RouteError::ValueNotFound(format!(
+            "Input gain plugin on track {}",
+            get_track_guid(reaper, track)
+        ))
+    })
+}
+
+/// @osc-doc
+/// OSC Address: /arpad/config/volume-mode
+/// Arguments:
+/// - mode (string): how this surface expresses track volumes: "slider" (REAPER's fader taper, normalized to 0 to 1.0), "linear" (gain) or "db"
+pub struct VolumeModeRoute;
+
+pub struct VolumeModeParams;
+
+impl OscRoute for VolumeModeRoute {
+    type SendParams = VolumeMode;
+    type ReceiveParams = VolumeModeParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "config", "volume-mode"] => Some(VolumeModeParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let mode = msg.args[0]
+            .clone()
+            .string()
+            .and_then(|s| VolumeMode::from_name(&s))
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid volume mode, expected \"slider\", \"linear\" or \"db\"".to_string(),
+                )
+            })?;
+        surfaces::set_volume_mode(mode);
+        Ok(())
+    }
+
+    fn build_message(mode: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/config/volume-mode".to_string(),
+            args: vec![OscType::String(mode.as_str().to_string())],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(surfaces::volume_mode())
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /arpad/config/meter-mode
+/// Arguments:
+/// - mode (string): where the streamed track meters are taken: "post-fader", or "pre-fader" with the track volume taken back out of the peaks; the pan and pan law stay in, so panned tracks read as panned
+pub struct MeterModeRoute;
+
+pub struct MeterModeParams;
+
+impl OscRoute for MeterModeRoute {
+    type SendParams = vu::MeterMode;
+    type ReceiveParams = MeterModeParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "config", "meter-mode"] => Some(MeterModeParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let mode = msg
+            .args
+            .first()
+            .and_then(|arg| arg.clone().string())
+            .and_then(|s| vu::MeterMode::from_name(&s))
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid meter mode, expected \"post-fader\" or \"pre-fader\"".to_string(),
+                )
+            })?;
+        vu::set_meter_mode(mode);
+        Ok(())
+    }
+
+    fn build_message(mode: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/config/meter-mode".to_string(),
+            args: vec![OscType::String(mode.as_str().to_string())],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(vu::meter_mode())
+    }
+}
+
+// Timecode display mode for format_timestr_pos (hours:minutes:seconds:frames)
+const TIMECODE_MODE: i32 = 5;
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /clock
+/// Arguments:
+/// - bar (int): current bar, starting at 1
+/// - beat (float): position within the bar in beats, starting at 1
+/// - timecode (string): position as hours:minutes:seconds:frames
+/// - countdown (float): seconds until the end of the next region, or -1 when no region lies ahead
+pub struct ClockRoute;
+
+pub struct ClockParams;
+
+pub struct ClockArgs {
+    pub bar: i32,
+    pub beat: f64,
+    pub timecode: String,
+    pub countdown: Option<f64>,
+}
+
+impl ClockArgs {
+    pub fn collect(reaper: &Reaper) -> Self {
+        let position = markers::playhead_position(reaper);
+        let mut measure = 0;
+        let beat = unsafe {
+            reaper.low().TimeMap2_timeToBeats(
+                std::ptr::null_mut(),
+                position,
+                &mut measure,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        let mut buf = [0 as std::os::raw::c_char; 64];
+        let timecode = unsafe {
+            reaper.low().format_timestr_pos(
+                position,
+                buf.as_mut_ptr(),
+                buf.len() as i32,
+                TIMECODE_MODE,
+            );
+            CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+        };
+        ClockArgs {
+            bar: measure + 1,
+            beat: beat + 1.0,
+            timecode,
+            countdown: setlist::time_to_region_end(reaper, position),
+        }
+    }
+}
+
+impl OscRoute for ClockRoute {
+    type SendParams = ClockArgs;
+    type ReceiveParams = ClockParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["clock"] => Some(ClockParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/clock".to_string(),
+            args: vec![
+                OscType::Int(args.bar),
+                OscType::Float(args.beat as f32),
+                OscType::String(args.timecode),
+                OscType::Float(args.countdown.unwrap_or(-1.0) as f32),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(ClockArgs::collect(reaper))
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /announce
+/// Arguments:
+/// - sentence (string): human-readable description of a significant state change, e.g. "Vocals muted" or "Recording started", for screen readers and text-to-speech
+pub struct AnnounceRoute;
+
+pub struct AnnounceParams;
+
+impl OscRoute for AnnounceRoute {
+    type SendParams = String;
+    type ReceiveParams = AnnounceParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["announce"] => Some(AnnounceParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(sentence: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/announce".to_string(),
+            args: vec![OscType::String(sentence)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(announce::last())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /project/changed
+/// Arguments:
+/// - name (string): name of the active project
+/// - session_id (string): identifier of the project session, the same as when this project was last active unless it was reopened since; sent before an /overview of the new project
+pub struct ProjectChangedRoute;
+
+pub struct ProjectChangedParams;
+
+pub struct ProjectChangedArgs {
+    pub name: String,
+    pub session_id: String,
+}
+
+impl OscRoute for ProjectChangedRoute {
+    type SendParams = ProjectChangedArgs;
+    type ReceiveParams = ProjectChangedParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", "changed"] => Some(ProjectChangedParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/project/changed".to_string(),
+            args: vec![OscType::String(args.name), OscType::String(args.session_id)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(ProjectChangedArgs {
+            name: project::project_name(reaper),
+            session_id: project::session_id(),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /arpad/session
+    /// Arguments:
+    /// - session_id (string): UUID generated whenever a project is loaded, kept while the project stays open in its tab, so switching back to a project brings its id back; clients holding GUIDs from another session should drop their state. Sent after /project/changed and with every full state
+    pub struct SessionRoute;
+    address: ["arpad", "session"];
+    params: SessionParams {};
+    value: String;
+    get(reaper, params) {
+        Ok(project::session_id())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/resolve/{track_id}
+/// Arguments:
+/// - track_id (string): track GUID as the dashed hex string, REAPER's {...} form, or a unique prefix of the dashed form
+/// - track_guid (string): canonical GUID of the track, as used in all other track addresses
+pub struct TrackResolveRoute;
+
+pub struct TrackResolveParams {
+    track_id: String,
+}
+
+pub struct TrackResolveArgs {
+    pub track_id: String,
+    pub track: reaper_medium::MediaTrack,
+}
+
+impl OscRoute for TrackResolveRoute {
+    type SendParams = TrackResolveArgs;
+    type ReceiveParams = TrackResolveParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", "resolve", track_id] => Some(TrackResolveParams {
+                track_id: track_id.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/track/resolve/{}", args.track_id),
+            args: vec![OscType::String(get_track_guid(reaper, args.track))],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(TrackResolveArgs {
+            track_id: params.track_id.clone(),
+            track: get_track_by_guid(reaper, &params.track_id)?,
+        })
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /arpad/config/button-mode/{switch}
+/// Arguments:
+/// - switch (string): "mute", "solo" or "rec-arm"
+/// - mode (string): "absolute" (the value is the new state), "toggle-on-press" (each press flips the state) or "momentary" (flipped while held)
+pub struct ButtonModeRoute;
+
+pub struct ButtonModeParams {
+    switch: Switch,
+}
+
+impl OscRoute for ButtonModeRoute {
+    type SendParams = (Switch, ButtonMode);
+    type ReceiveParams = ButtonModeParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "config", "button-mode", switch] => Some(ButtonModeParams {
+                switch: Switch::from_name(switch)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        _: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let mode = msg.args[0]
+            .clone()
+            .string()
+            .and_then(|s| ButtonMode::from_name(&s))
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid button mode, expected \"absolute\", \"toggle-on-press\" or \"momentary\""
+                        .to_string(),
+                )
+            })?;
+        buttons::set_mode(params.switch, mode);
+        Ok(())
+    }
+
+    fn build_message((switch, mode): Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/arpad/config/button-mode/{}", switch.as_str()),
+            args: vec![OscType::String(mode.as_str().to_string())],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok((params.switch, buttons::mode(params.switch)))
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /arpad/config/exclusive-solo
+    /// Arguments:
+    /// - exclusive (bool): true means soloing a track over OSC clears the other solos made over OSC
+    pub struct ExclusiveSoloRoute;
+    address: ["arpad", "config", "exclusive-solo"];
+    params: ExclusiveSoloParams {};
+    value: bool;
+    undoable: false;
+    get(reaper, params) {
+        Ok(solo::is_exclusive())
+    }
+    set(reaper, params, exclusive) {
+        solo::set_exclusive(exclusive);
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /arpad/autocolor/rules
+/// Arguments:
+/// - rules (list): pattern (string) and color (int) of each rule, in order of precedence
+pub struct AutocolorRulesRoute;
+
+pub struct AutocolorRulesParams;
+
+impl OscRoute for AutocolorRulesRoute {
+    type SendParams = Vec<ColorRule>;
+    type ReceiveParams = AutocolorRulesParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "autocolor", "rules"] => Some(AutocolorRulesParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(rules: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/autocolor/rules".to_string(),
+            args: rules
+                .into_iter()
+                .flat_map(|r| [OscType::String(r.pattern), OscType::Int(r.color)])
+                .collect(),
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(autocolor::rules(reaper))
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /arpad/autocolor/rule
+/// Arguments:
+/// - pattern (string): case-insensitive track name pattern, where * matches anything
+/// - color (int): color given to tracks whose name matches when they are created or renamed
+pub struct AutocolorRuleRoute;
+
+pub struct AutocolorRuleParams;
+
+impl OscRoute for AutocolorRuleRoute {
+    type SendParams = ();
+    type ReceiveParams = AutocolorRuleParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "autocolor", "rule"] => Some(AutocolorRuleParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let (Some(OscType::String(pattern)), Some(OscType::Int(color))) =
+            (msg.args.first(), msg.args.get(1))
+        else {
+            return Err(ReceiverError::BadValue(
+                "Invalid auto-color rule, expected a pattern and an integer color".to_string(),
+            ));
+        };
+        autocolor::set_rule(
+            reaper,
+            ColorRule {
+                pattern: pattern.clone(),
+                color: *color,
+            },
+        );
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/autocolor/rule".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /arpad/autocolor/remove
+/// Arguments:
+/// - pattern (string): pattern of the rule to remove
+pub struct AutocolorRemoveRoute;
+
+pub struct AutocolorRemoveParams;
+
+impl OscRoute for AutocolorRemoveRoute {
+    type SendParams = ();
+    type ReceiveParams = AutocolorRemoveParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "autocolor", "remove"] => Some(AutocolorRemoveParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let pattern = msg.args[0].clone().string().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid auto-color pattern, expected a string".to_string())
+        })?;
+        if !autocolor::remove_rule(reaper, &pattern) {
+            return Err(RouteError::ValueNotFound(format!("Auto-color rule {}", pattern)).into());
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/autocolor/remove".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /arpad/autocolor/{action}
+/// Arguments:
+/// - action (string): "clear" removes every rule; "apply" recolors all existing tracks by the rules
+/// - trigger (bool): optional; false or 0 is ignored
+pub struct AutocolorActionRoute;
+
+#[derive(Clone, Copy)]
+pub enum AutocolorAction {
+    Clear,
+    Apply,
+}
+
+pub struct AutocolorActionParams {
+    action: AutocolorAction,
+}
+
+impl OscRoute for AutocolorActionRoute {
+    type SendParams = AutocolorAction;
+    type ReceiveParams = AutocolorActionParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        let action = match segments {
+            ["arpad", "autocolor", "clear"] => AutocolorAction::Clear,
+            ["arpad", "autocolor", "apply"] => AutocolorAction::Apply,
+            _ => return None,
+        };
+        Some(AutocolorActionParams { action })
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        match params.action {
+            AutocolorAction::Clear => autocolor::clear(reaper),
+            AutocolorAction::Apply => autocolor::apply_all(reaper),
+        }
+        Ok(())
+    }
+
+    fn build_message(action: Self::SendParams, _: &Reaper) -> OscMessage {
+        let action = match action {
+            AutocolorAction::Clear => "clear",
+            AutocolorAction::Apply => "apply",
+        };
+        OscMessage {
+            addr: format!("/arpad/autocolor/{}", action),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.action)
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/tags
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - tags (list): one string per tag, e.g. "drums" or "fx-return"; sending no tags clears them
+pub struct TrackTagsRoute;
+
+pub struct TrackTagsParams {
+    track_guid: String,
+}
+
+pub struct TrackTagsArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub tags: Vec<String>,
+}
+
+impl OscRoute for TrackTagsRoute {
+    type SendParams = TrackTagsArgs;
+    type ReceiveParams = TrackTagsParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "tags"] => Some(TrackTagsParams {
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let tags = msg
+            .args
+            .iter()
+            .map(|arg| arg.clone().string())
+            .collect::<Option<Vec<String>>>()
+            .ok_or_else(|| ReceiverError::BadValue("Invalid tags, expected strings".to_string()))?;
+        tags::set_tags(reaper, track, &tags);
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/track/{}/tags", get_track_guid(reaper, args.track)),
+            args: args.tags.into_iter().map(OscType::String).collect(),
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(TrackTagsArgs {
+            track,
+            tags: tags::tags(reaper, track),
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /tracks/by-tag/{tag}
+/// Arguments:
+/// - tag (string): tag to look for, compared case-insensitively
+/// - track_guids (list): GUIDs of the tracks carrying the tag, in track order
+pub struct TracksByTagRoute;
+
+pub struct TracksByTagParams {
+    tag: String,
+}
+
+pub struct TracksByTagArgs {
+    pub tag: String,
+    pub tracks: Vec<reaper_medium::MediaTrack>,
+}
+
+impl OscRoute for TracksByTagRoute {
+    type SendParams = TracksByTagArgs;
+    type ReceiveParams = TracksByTagParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["tracks", "by-tag", tag] => Some(TracksByTagParams {
+                tag: tag.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/tracks/by-tag/{}", args.tag),
+            args: args
+                .tracks
+                .into_iter()
+                .map(|track| OscType::String(get_track_guid(reaper, track)))
+                .collect(),
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(TracksByTagArgs {
+            tag: params.tag.clone(),
+            tracks: tags::tracks_with_tag(reaper, &params.tag),
+        })
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /tracks/set
+/// Arguments:
+/// - changes (list): repeated (track_guid string, attribute string, value) triples, where attribute is "volume" (float, in the surface's volume mode), "pan" (float), "mute", "solo" or "rec-arm" (bool), "name" (string) or "color" (int); all changes are applied in one undo step, or none if any triple is invalid or changes an address the surface isn't permitted to change
+pub struct TracksSetRoute;
+
+pub struct TracksSetParams;
+
+fn parse_track_setting(
+    reaper: &Reaper,
+    attribute: &str,
+    value: &OscType,
+) -> Result<TrackSetting, ReceiverError> {
+    let bad_value =
+        || ReceiverError::BadValue(format!("Invalid value for track attribute {}", attribute));
+    let flag = || match value {
+        OscType::Bool(b) => Ok(*b),
+        OscType::Int(i) => Ok(*i != 0),
+        OscType::Float(f) => Ok(*f != 0.0),
+        _ => Err(bad_value()),
+    };
+    Ok(match attribute {
+        "volume" => {
+            let value = value.clone().float().ok_or_else(bad_value)?;
+            TrackSetting::Volume(
+                mode_value_to_volume(reaper, surfaces::volume_mode(), value as f64)
+                    .ok_or_else(bad_value)?
+                    .get(),
+            )
+        }
+        "pan" => TrackSetting::Pan(value.clone().float().ok_or_else(bad_value)? as f64),
+        "mute" => TrackSetting::Mute(flag()?),
+        "solo" => TrackSetting::Solo(flag()?),
+        "rec-arm" => TrackSetting::RecArm(flag()?),
+        "name" => TrackSetting::Name(value.clone().string().ok_or_else(bad_value)?),
+        "color" => TrackSetting::Color(value.clone().int().ok_or_else(bad_value)?),
+        _ => {
+            return Err(ReceiverError::BadValue(format!(
+                "Unknown track attribute {}",
+                attribute
+            )))
+        }
+    })
+}
+
+impl OscRoute for TracksSetRoute {
+    type SendParams = ();
+    type ReceiveParams = TracksSetParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["tracks", "set"] => Some(TracksSetParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if msg.args.len() % 3 != 0 {
+            return Err(ReceiverError::BadValue(
+                "Invalid track changes, expected (guid, attribute, value) triples".to_string(),
+            ));
+        }
+        let changes = msg
+            .args
+            .chunks(3)
+            .map(|change| {
+                let (Some(guid), Some(attribute)) =
+                    (change[0].clone().string(), change[1].clone().string())
+                else {
+                    return Err(ReceiverError::BadValue(
+                        "Invalid track change, expected a guid and an attribute name".to_string(),
+                    ));
+                };
+                // Each change is held to the permissions of the address it stands for
+                if !surfaces::permits(&["track", guid.as_str(), attribute.as_str()]) {
+                    return Err(ReceiverError::BadValue(format!(
+                        "Surface not permitted to change /track/{}/{}",
+                        guid, attribute
+                    )));
+                }
+                let track = get_track_by_guid(reaper, &guid)?;
+                Ok((track, parse_track_setting(reaper, &attribute, &change[2])?))
+            })
+            .collect::<Result<Vec<_>, ReceiverError>>()?;
+        tracks::set_many(reaper, &changes);
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/tracks/set".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /tracks/{state}
+/// Arguments:
+/// - state (string): "armed" lists the tracks armed for recording, "soloed" the soloed tracks
+/// - track_guids (list): GUIDs of the matching tracks, in track order
+pub struct TracksInStateRoute;
+
+#[derive(Clone, Copy)]
+pub enum TrackState {
+    Armed,
+    Soloed,
+}
+
+impl TrackState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TrackState::Armed => "armed",
+            TrackState::Soloed => "soloed",
+        }
+    }
+
+    fn attribute(&self) -> TrackAttributeKey<'static> {
+        match self {
+            TrackState::Armed => TrackAttributeKey::RecArm,
+            TrackState::Soloed => TrackAttributeKey::Solo,
+        }
+    }
+}
+
+pub struct TracksInStateParams {
+    state: TrackState,
+}
+
+pub struct TracksInStateArgs {
+    pub state: TrackState,
+    pub tracks: Vec<reaper_medium::MediaTrack>,
+}
+
+impl OscRoute for TracksInStateRoute {
+    type SendParams = TracksInStateArgs;
+    type ReceiveParams = TracksInStateParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        let state = match segments {
+            ["tracks", "armed"] => TrackState::Armed,
+            ["tracks", "soloed"] => TrackState::Soloed,
+            _ => return None,
+        };
+        Some(TracksInStateParams { state })
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/tracks/{}", args.state.as_str()),
+            args: args
+                .tracks
+                .into_iter()
+                .map(|track| OscType::String(get_track_guid(reaper, track)))
+                .collect(),
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let tracks = (0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+            .filter(|&track| unsafe {
+                reaper.get_media_track_info_value(track, params.state.attribute()) != 0.0
+            })
+            .collect();
+        Ok(TracksInStateArgs {
+            state: params.state,
+            tracks,
+        })
+    }
+}
+
+// Resolves the track and checks the FX exists in its chain
+fn resolve_track_fx(
+    reaper: &Reaper,
+    track_guid: &str,
+    fx_index: u32,
+) -> Result<(reaper_medium::MediaTrack, reaper_medium::TrackFxLocation), RouteError> {
+    let track = get_track_by_guid(reaper, track_guid)?;
+    if fx_index >= unsafe { reaper.track_fx_get_count(track) } {
+        return Err(RouteError::ValueNotFound(format!("FX {}", fx_index)));
+    }
+    Ok((
+        track,
+        reaper_medium::TrackFxLocation::NormalFxChain(fx_index),
+    ))
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /track/{track_guid}/fx/count
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - count (int): number of FX in the track's FX chain
+    pub struct TrackFxCountRoute;
+    address: ["track", track_guid, "fx", "count"];
+    params: TrackFxCountParams { track_guid: String };
+    value: i32;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(unsafe { reaper.track_fx_get_count(track) } as i32)
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /track/{track_guid}/fx/{fx_index}/name
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - fx_index (int): index of the FX in the track's FX chain
+    /// - name (string): name of the FX
+    pub struct TrackFxNameRoute;
+    address: ["track", track_guid, "fx", fx_index, "name"];
+    params: TrackFxNameParams { track_guid: String, fx_index: u32 };
+    value: String;
+    get(reaper, params) {
+        let (track, fx) = resolve_track_fx(reaper, &params.track_guid, params.fx_index)?;
+        let name = unsafe { reaper.track_fx_get_fx_name(track, fx, 256) }
+            .map_err(|_| RouteError::ValueNotFound(format!("FX {} name", params.fx_index)))?;
+        Ok(name.to_str().to_string())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/fx/{fx_index}/enabled
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - fx_index (int): index of the FX in the track's FX chain
+    /// - enabled (bool): false means the FX is bypassed
+    pub struct TrackFxEnabledRoute;
+    address: ["track", track_guid, "fx", fx_index, "enabled"];
+    params: TrackFxEnabledParams { track_guid: String, fx_index: u32 };
+    value: bool;
+    get(reaper, params) {
+        let (track, fx) = resolve_track_fx(reaper, &params.track_guid, params.fx_index)?;
+        Ok(unsafe { reaper.track_fx_get_enabled(track, fx) })
+    }
+    set(reaper, params, enabled) {
+        let (track, fx) = resolve_track_fx(reaper, &params.track_guid, params.fx_index)?;
+        unsafe {
+            reaper.track_fx_set_enabled(track, fx, enabled);
+        }
+        Ok(())
+    }
+}
+
+// TrackFX_Show flags for the floating FX window
+const SHOW_FLOATING: i32 = 3;
+const HIDE_FLOATING: i32 = 2;
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/fx/{fx_index}/open
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - fx_index (int): index of the FX in the track's FX chain
+    /// - open (bool): true shows the FX in a floating window, false closes it. Reads true while the FX is shown, floating or in the FX chain window
+    pub struct TrackFxOpenRoute;
+    address: ["track", track_guid, "fx", fx_index, "open"];
+    params: TrackFxOpenParams { track_guid: String, fx_index: u32 };
+    value: bool;
+    get(reaper, params) {
+        let (track, fx) = resolve_track_fx(reaper, &params.track_guid, params.fx_index)?;
+        Ok(unsafe { reaper.low().TrackFX_GetOpen(track.as_ptr(), fx.to_raw()) })
+    }
+    set(reaper, params, open) {
+        let (track, fx) = resolve_track_fx(reaper, &params.track_guid, params.fx_index)?;
+        let flag = if open { SHOW_FLOATING } else { HIDE_FLOATING };
+        unsafe {
+            reaper.low().TrackFX_Show(track.as_ptr(), fx.to_raw(), flag);
+        }
+        Ok(())
+    }
+}
+
+/// The FX count, then the name and enabled state of every FX on the track, for
+/// redrawing an FX strip after the chain changed
+pub fn fx_chain_feedback(reaper: &Reaper, track: reaper_medium::MediaTrack) -> Vec<OscMessage> {
+    let track_guid = get_track_guid(reaper, track);
+    let count = unsafe { reaper.track_fx_get_count(track) };
+    let mut messages = vec![TrackFxCountRoute::build_message(
+        (
+            TrackFxCountParams {
+                track_guid: track_guid.clone(),
+            },
+            count as i32,
+        ),
+        reaper,
+    )];
+    for fx_index in 0..count {
+        let name = TrackFxNameParams {
+            track_guid: track_guid.clone(),
+            fx_index,
+        };
+        if let Ok(params) = TrackFxNameRoute::collect_send_params(&name, reaper) {
+            messages.push(TrackFxNameRoute::build_message(params, reaper));
+        }
+        let enabled = TrackFxEnabledParams {
+            track_guid: track_guid.clone(),
+            fx_index,
+        };
+        if let Ok(params) = TrackFxEnabledRoute::collect_send_params(&enabled, reaper) {
+            messages.push(TrackFxEnabledRoute::build_message(params, reaper));
+        }
+    }
+    messages
+}
+
+pub struct TrackFxParamParams {
+    track_guid: String,
+    fx_index: u32,
+    param_index: u32,
+}
+
+fn match_fx_param(segments: &[&str], leaf: &str) -> Option<TrackFxParamParams> {
+    match segments {
+        ["track", track_guid, "fx", fx_index, "param", param_index, last] if *last == leaf => {
+            Some(TrackFxParamParams {
+                track_guid: track_guid.to_string(),
+                fx_index: fx_index.parse().ok()?,
+                param_index: param_index.parse().ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn fx_param_addr(
+    reaper: &Reaper,
+    track: reaper_medium::MediaTrack,
+    fx_index: u32,
+    param_index: u32,
+    leaf: &str,
+) -> String {
+    format!(
+        "/track/{}/fx/{}/param/{}/{}",
+        get_track_guid(reaper, track),
+        fx_index,
+        param_index,
+        leaf
+    )
+}
+
+// Resolves the track and checks the parameter exists, so queries for missing
+// parameters fail instead of reporting zero
+fn resolve_fx_param(
+    reaper: &Reaper,
+    params: &TrackFxParamParams,
+) -> Result<(reaper_medium::MediaTrack, reaper_medium::TrackFxLocation), RouteError> {
+    let track = get_track_by_guid(reaper, &params.track_guid)?;
+    let fx = reaper_medium::TrackFxLocation::NormalFxChain(params.fx_index);
+    if params.param_index >= unsafe { reaper.track_fx_get_num_params(track, fx) } {
+        return Err(RouteError::ValueNotFound(format!(
+            "FX {} parameter {}",
+            params.fx_index, params.param_index
+        )));
+    }
+    Ok((track, fx))
+}
+
+/// @osc-doc
+/// OSC Address: /track/{track_guid}/fx/{fx_index}/param/{param_index}/value
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - fx_index (int): index of the FX in the track's FX chain
+/// - param_index (int): index of the parameter on the FX
+/// - value (float): normalized value of the parameter, from 0 to 1.0
+pub struct TrackFxParamValueRoute;
+
+pub struct TrackFxParamValueArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub fx_index: u32,
+    pub param_index: u32,
+    pub value: f64,
+}
+
+impl OscRoute for TrackFxParamValueRoute {
+    type SendParams = TrackFxParamValueArgs;
+    type ReceiveParams = TrackFxParamParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match_fx_param(segments, "value")
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let (track, fx) = resolve_fx_param(reaper, &params)?;
+        let value = msg.args[0].clone().float().ok_or_else(|| {
+            ReceiverError::BadValue("Invalid parameter value, expected a float".to_string())
+        })?;
+        unsafe {
+            reaper.track_fx_set_param_normalized(
+                track,
+                fx,
+                params.param_index,
+                reaper_medium::ReaperNormalizedFxParamValue::new(value as f64),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: fx_param_addr(reaper, args.track, args.fx_index, args.param_index, "value"),
+            args: vec![OscType::Float(args.value as f32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let (track, fx) = resolve_fx_param(reaper, params)?;
+        let value = unsafe { reaper.track_fx_get_param_normalized(track, fx, params.param_index) };
+        Ok(TrackFxParamValueArgs {
+            track,
+            fx_index: params.fx_index,
+            param_index: params.param_index,
+            value: value.get(),
+        })
+    }
+}
+
+pub struct TrackFxParamTextArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub fx_index: u32,
+    pub param_index: u32,
+    pub text: String,
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/fx/{fx_index}/param/{param_index}/name
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - fx_index (int): index of the FX in the track's FX chain
+/// - param_index (int): index of the parameter on the FX
+/// - name (string): name of the parameter
+pub struct TrackFxParamNameRoute;
+
+impl OscRoute for TrackFxParamNameRoute {
+    type SendParams = TrackFxParamTextArgs;
+    type ReceiveParams = TrackFxParamParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match_fx_param(segments, "name")
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: fx_param_addr(reaper, args.track, args.fx_index, args.param_index, "name"),
+            args: vec![OscType::String(args.text)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let (track, fx) = resolve_fx_param(reaper, params)?;
+        let name = unsafe { reaper.track_fx_get_param_name(track, fx, params.param_index, 256) }
+            .map_err(|_| {
+                RouteError::ValueNotFound(format!(
+                    "FX {} parameter {} name",
+                    params.fx_index, params.param_index
+                ))
+            })?;
+        Ok(TrackFxParamTextArgs {
+            track,
+            fx_index: params.fx_index,
+            param_index: params.param_index,
+            text: name.to_str().to_string(),
+        })
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/fx/{fx_index}/param/{param_index}/formatted
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - fx_index (int): index of the FX in the track's FX chain
+/// - param_index (int): index of the parameter on the FX
+/// - formatted (string): value of the parameter as the plugin displays it, e.g. "-6.0 dB"
+pub struct TrackFxParamFormattedRoute;
+
+impl TrackFxParamFormattedRoute {
+    pub fn formatted_value(
+        reaper: &Reaper,
+        track: reaper_medium::MediaTrack,
+        fx_index: u32,
+        param_index: u32,
+    ) -> Option<String> {
+        let fx = reaper_medium::TrackFxLocation::NormalFxChain(fx_index);
+        unsafe { reaper.track_fx_get_formatted_param_value(track, fx, param_index, 256) }
+            .ok()
+            .map(|text| text.to_str().to_string())
+    }
+}
+
+impl OscRoute for TrackFxParamFormattedRoute {
+    type SendParams = TrackFxParamTextArgs;
+    type ReceiveParams = TrackFxParamParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match_fx_param(segments, "formatted")
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: fx_param_addr(
+                reaper,
+                args.track,
+                args.fx_index,
+                args.param_index,
+                "formatted",
+            ),
+            args: vec![OscType::String(args.text)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let (track, _) = resolve_fx_param(reaper, params)?;
+        let text = Self::formatted_value(reaper, track, params.fx_index, params.param_index)
+            .ok_or_else(|| {
+                RouteError::ValueNotFound(format!(
+                    "FX {} parameter {} value",
+                    params.fx_index, params.param_index
+                ))
+            })?;
+        Ok(TrackFxParamTextArgs {
+            track,
+            fx_index: params.fx_index,
+            param_index: params.param_index,
+            text,
+        })
+    }
+}
+
+fn last_touched(reaper: &Reaper) -> Result<lasttouched::LastTouched, RouteError> {
+    lasttouched::get(reaper)
+        .ok_or_else(|| RouteError::ValueNotFound("Last touched FX parameter".to_string()))
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /fx/last-touched/param/value
+    /// Arguments:
+    /// - value (float): normalized value of the FX parameter touched last in REAPER, from 0 to 1.0. Sent whenever it changes or another parameter is touched
+    pub struct LastTouchedParamValueRoute;
+    address: ["fx", "last-touched", "param", "value"];
+    params: LastTouchedParamValueParams {};
+    value: f64;
+    get(reaper, params) {
+        let touched = last_touched(reaper)?;
+        let value = unsafe {
+            reaper.track_fx_get_param_normalized(touched.track, touched.fx, touched.param_index)
+        };
+        Ok(value.get())
+    }
+    set(reaper, params, value) {
+        let touched = last_touched(reaper)?;
+        unsafe {
+            reaper.track_fx_set_param_normalized(
+                touched.track,
+                touched.fx,
+                touched.param_index,
+                reaper_medium::ReaperNormalizedFxParamValue::new(value.clamp(0.0, 1.0)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /fx/last-touched/param/name
+    /// Arguments:
+    /// - name (string): name of the FX parameter touched last in REAPER. Sent whenever another parameter is touched
+    pub struct LastTouchedParamNameRoute;
+    address: ["fx", "last-touched", "param", "name"];
+    params: LastTouchedParamNameParams {};
+    value: String;
+    get(reaper, params) {
+        let touched = last_touched(reaper)?;
+        let name = unsafe {
+            reaper.track_fx_get_param_name(touched.track, touched.fx, touched.param_index, 256)
+        }
+        .map_err(|_| RouteError::ValueNotFound("Last touched FX parameter name".to_string()))?;
+        Ok(name.to_str().to_string())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /monitorfx/count
+    /// Arguments:
+    /// - count (int): number of FX in REAPER's monitoring FX chain
+    pub struct MonitorFxCountRoute;
+    address: ["monitorfx", "count"];
+    params: MonitorFxCountParams {};
+    value: i32;
+    get(reaper, params) {
+        Ok(monitorfx::count(reaper) as i32)
+    }
+}
+
+pub struct MonitorFxParams {
+    fx_index: u32,
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /monitorfx/{fx_index}/name
+/// Arguments:
+/// - fx_index (int): index of the FX in the monitoring FX chain
+/// - name (string): name of the FX
+pub struct MonitorFxNameRoute;
+
+pub struct MonitorFxNameArgs {
+    pub fx_index: u32,
+    pub name: String,
+}
+
+impl OscRoute for MonitorFxNameRoute {
+    type SendParams = MonitorFxNameArgs;
+    type ReceiveParams = MonitorFxParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["monitorfx", fx_index, "name"] => Some(MonitorFxParams {
+                fx_index: fx_index.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/monitorfx/{}/name", args.fx_index),
+            args: vec![OscType::String(args.name)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let (master, fx) = monitorfx::location(reaper, params.fx_index)?;
+        let name = unsafe { reaper.track_fx_get_fx_name(master, fx, 256) }.map_err(|_| {
+            RouteError::ValueNotFound(format!("Monitor FX {} name", params.fx_index))
+        })?;
+        Ok(MonitorFxNameArgs {
+            fx_index: params.fx_index,
+            name: name.to_str().to_string(),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /monitorfx/{fx_index}/bypass
+    /// Arguments:
+    /// - fx_index (int): index of the FX in the monitoring FX chain
+    /// - bypass (bool): true means the FX is bypassed
+    pub struct MonitorFxBypassRoute;
+    address: ["monitorfx", fx_index, "bypass"];
+    params: MonitorFxBypassParams { fx_index: u32 };
+    value: bool;
+    get(reaper, params) {
+        let (master, fx) = monitorfx::location(reaper, params.fx_index)?;
+        Ok(!unsafe { reaper.track_fx_get_enabled(master, fx) })
+    }
+    set(reaper, params, bypass) {
+        let (master, fx) = monitorfx::location(reaper, params.fx_index)?;
+        unsafe {
+            reaper.track_fx_set_enabled(master, fx, !bypass);
+        }
+        Ok(())
+    }
+}
+
+// Resolves the monitoring FX and checks the parameter exists
+fn resolve_monitor_fx_param(
+    reaper: &Reaper,
+    fx_index: u32,
+    param_index: u32,
+) -> Result<(reaper_medium::MediaTrack, reaper_medium::TrackFxLocation), RouteError> {
+    let (master, fx) = monitorfx::location(reaper, fx_index)?;
+    if param_index >= unsafe { reaper.track_fx_get_num_params(master, fx) } {
+        return Err(RouteError::ValueNotFound(format!(
+            "Monitor FX {} parameter {}",
+            fx_index, param_index
+        )));
+    }
+    Ok((master, fx))
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /monitorfx/{fx_index}/param/{param_index}/value
+    /// Arguments:
+    /// - fx_index (int): index of the FX in the monitoring FX chain
+    /// - param_index (int): index of the parameter on the FX
+    /// - value (float): normalized value of the parameter, from 0 to 1.0
+    pub struct MonitorFxParamRoute;
+    address: ["monitorfx", fx_index, "param", param_index, "value"];
+    params: MonitorFxParamParams { fx_index: u32, param_index: u32 };
+    value: f64;
+    get(reaper, params) {
+        let (master, fx) = resolve_monitor_fx_param(reaper, params.fx_index, params.param_index)?;
+        let value = unsafe { reaper.track_fx_get_param_normalized(master, fx, params.param_index) };
+        Ok(value.get())
+    }
+    set(reaper, params, value) {
+        let (master, fx) = resolve_monitor_fx_param(reaper, params.fx_index, params.param_index)?;
+        unsafe {
+            reaper.track_fx_set_param_normalized(
+                master,
+                fx,
+                params.param_index,
+                reaper_medium::ReaperNormalizedFxParamValue::new(value),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /transport/{control}
+/// Arguments:
+/// - control (string): "play", "stop", "pause" or "record"
+/// - state (bool): whether the transport is in that state; sending true presses the button, sending false stops playing or recording and resumes from pause
+pub struct TransportRoute;
+
+#[derive(Clone, Copy)]
+pub enum TransportControl {
+    Play,
+    Stop,
+    Pause,
+    Record,
+}
+
+impl TransportControl {
+    pub const ALL: [TransportControl; 4] = [
+        TransportControl::Play,
+        TransportControl::Stop,
+        TransportControl::Pause,
+        TransportControl::Record,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            TransportControl::Play => "play",
+            TransportControl::Stop => "stop",
+            TransportControl::Pause => "pause",
+            TransportControl::Record => "record",
+        }
+    }
+
+    /// Whether the transport is in this control's state
+    pub fn is_on(&self, state: reaper_medium::PlayState) -> bool {
+        match self {
+            TransportControl::Play => state.is_playing,
+            TransportControl::Stop => !state.is_playing && !state.is_paused,
+            TransportControl::Pause => state.is_paused,
+            TransportControl::Record => state.is_recording,
+        }
+    }
+}
+
+pub struct TransportParams {
+    control: TransportControl,
+}
+
+pub struct TransportArgs {
+    pub control: TransportControl,
+    pub on: bool,
+}
+
+impl OscRoute for TransportRoute {
+    type SendParams = TransportArgs;
+    type ReceiveParams = TransportParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        let control = match segments {
+            ["transport", "play"] => TransportControl::Play,
+            ["transport", "stop"] => TransportControl::Stop,
+            ["transport", "pause"] => TransportControl::Pause,
+            ["transport", "record"] => TransportControl::Record,
+            _ => return None,
+        };
+        Some(TransportParams { control })
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let pressed = buttons::pressed(msg).unwrap_or(true);
+        let state = reaper.get_play_state_ex(CurrentProject);
+        if pressed == params.control.is_on(state) {
+            return Ok(());
+        }
+        match (params.control, pressed) {
+            (TransportControl::Play, true) => reaper.csurf_on_play(),
+            (TransportControl::Stop, true) => reaper.csurf_on_stop(),
+            (TransportControl::Pause, _) => reaper.csurf_on_pause(),
+            (TransportControl::Record, true) => reaper.csurf_on_record(),
+            (TransportControl::Play | TransportControl::Record, false) => reaper.csurf_on_stop(),
+            (TransportControl::Stop, false) => reaper.csurf_on_play(),
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/transport/{}", args.control.as_str()),
+            args: vec![OscType::Bool(args.on)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let state = reaper.get_play_state_ex(CurrentProject);
+        Ok(TransportArgs {
+            control: params.control,
+            on: params.control.is_on(state),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /transport/repeat
+    /// Arguments:
+    /// - repeat (bool): true means playback loops the time selection
+    pub struct TransportRepeatRoute;
+    address: ["transport", "repeat"];
+    params: TransportRepeatParams {};
+    value: bool;
+    get(reaper, params) {
+        Ok(reaper.get_set_repeat_ex_get(CurrentProject))
+    }
+    set(reaper, params, repeat) {
+        reaper.get_set_repeat_ex_set(CurrentProject, repeat);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /transport/playrate
+    /// Arguments:
+    /// - rate (float): master playrate, where 1.0 is normal speed, from 0.25 to 4.0
+    pub struct TransportPlayRateRoute;
+    address: ["transport", "playrate"];
+    params: TransportPlayRateParams {};
+    value: f64;
+    get(reaper, params) {
+        Ok(playrate::play_rate(reaper))
+    }
+    set(reaper, params, rate) {
+        if !playrate::set_play_rate(reaper, rate) {
+            return Err(ReceiverError::BadValue(format!("Invalid playrate {}", rate)));
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /transport/preserve-pitch
+    /// Arguments:
+    /// - preserve (bool): true means audio items keep their pitch when the playrate changes; false makes them follow it like tape
+    pub struct TransportPreservePitchRoute;
+    address: ["transport", "preserve-pitch"];
+    params: TransportPreservePitchParams {};
+    value: bool;
+    get(reaper, params) {
+        Ok(playrate::preserves_pitch(reaper))
+    }
+    set(reaper, params, preserve) {
+        playrate::set_preserve_pitch(reaper, preserve);
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /tempo/tap
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't count as taps. From the second tap on, the project tempo follows the average of the last four tap intervals; a two second pause starts over
+pub struct TempoTapRoute;
+
+pub struct TempoTapParams;
+
+impl OscRoute for TempoTapRoute {
+    type SendParams = ();
+    type ReceiveParams = TempoTapParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["tempo", "tap"] => Some(TempoTapParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) {
+            tempo::tap(reaper);
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/tempo/tap".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /project/tempo
+    /// Arguments:
+    /// - bpm (float): project tempo in beats per minute, at the play position while playing and at the edit cursor otherwise; setting it adds an undo point
+    pub struct ProjectTempoRoute;
+    address: ["project", "tempo"];
+    params: ProjectTempoParams {};
+    value: f64;
+    get(reaper, params) {
+        Ok(tempo::current_bpm(reaper))
+    }
+    set(reaper, params, bpm) {
+        if !tempo::set_bpm(reaper, bpm) {
+            return Err(ReceiverError::BadValue(format!("Invalid tempo {}", bpm)));
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /project/name
+    /// Arguments:
+    /// - name (string): name of the active project, as shown in REAPER's title bar
+    pub struct ProjectNameRoute;
+    address: ["project", "name"];
+    params: ProjectNameParams {};
+    value: String;
+    get(reaper, params) {
+        Ok(project::project_name(reaper))
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /project/path
+    /// Arguments:
+    /// - path (string): full path of the active project's file; empty while the project was never saved
+    pub struct ProjectPathRoute;
+    address: ["project", "path"];
+    params: ProjectPathParams {};
+    value: String;
+    get(reaper, params) {
+        Ok(project::project_file(reaper)
+            .map(|file| file.display().to_string())
+            .unwrap_or_default())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /project/length
+    /// Arguments:
+    /// - length (float): length of the project in seconds, up to the end of its last item, marker or region
+    pub struct ProjectLengthRoute;
+    address: ["project", "length"];
+    params: ProjectLengthParams {};
+    value: f64;
+    get(reaper, params) {
+        Ok(unsafe { reaper.low().GetProjectLength(std::ptr::null_mut()) })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /project/track-count
+    /// Arguments:
+    /// - count (int): number of tracks in the project, not counting the master
+    pub struct ProjectTrackCountRoute;
+    address: ["project", "track-count"];
+    params: ProjectTrackCountParams {};
+    value: i32;
+    get(reaper, params) {
+        Ok(reaper.count_tracks(CurrentProject) as i32)
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /project/dirty
+    /// Arguments:
+    /// - dirty (bool): whether the active project has unsaved changes; sent whenever this changes
+    pub struct ProjectDirtyRoute;
+    address: ["project", "dirty"];
+    params: ProjectDirtyParams {};
+    value: bool;
+    get(reaper, params) {
+        Ok(project::is_dirty(reaper))
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /project/undo
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored so button releases don't undo twice. Undoes the last change in REAPER's undo history, whoever made it
+pub struct ProjectUndoRoute;
+
+pub struct ProjectUndoParams;
+
+impl OscRoute for ProjectUndoRoute {
+    type SendParams = ();
+    type ReceiveParams = ProjectUndoParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", "undo"] => Some(ProjectUndoParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) && !project::undo(reaper) {
+            return Err(RouteError::ValueNotFound("Change to undo".to_string()).into());
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/project/undo".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /project/redo
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored. Redoes the last change undone in REAPER's undo history
+pub struct ProjectRedoRoute;
+
+pub struct ProjectRedoParams;
+
+impl OscRoute for ProjectRedoRoute {
+    type SendParams = ();
+    type ReceiveParams = ProjectRedoParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", "redo"] => Some(ProjectRedoParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) && !project::redo(reaper) {
+            return Err(RouteError::ValueNotFound("Change to redo".to_string()).into());
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/project/redo".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /project/save
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored. Saves the active project; a project that was never saved is refused, as saving it would open a dialog on the REAPER machine
+pub struct ProjectSaveRoute;
+
+pub struct ProjectSaveParams;
+
+impl OscRoute for ProjectSaveRoute {
+    type SendParams = ();
+    type ReceiveParams = ProjectSaveParams;
+
+    const UNDOABLE: bool = false;
+    const MAY_BLOCK: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", "save"] => Some(ProjectSaveParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if is_trigger(msg) && !project::save(reaper) {
+            return Err(RouteError::ValueNotFound(
+                "Project file; save the project in REAPER first".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/project/save".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /project/timesig
+/// Arguments:
+/// - numerator (int): time signature numerator, at the play position while playing and at the edit cursor otherwise
+/// - denominator (int): time signature denominator
+pub struct ProjectTimeSigRoute;
+
+pub struct ProjectTimeSigParams;
+
+impl OscRoute for ProjectTimeSigRoute {
+    type SendParams = (i32, i32);
+    type ReceiveParams = ProjectTimeSigParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", "timesig"] => Some(ProjectTimeSigParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message((numerator, denominator): Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/project/timesig".to_string(),
+            args: vec![OscType::Int(numerator), OscType::Int(denominator)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(tempo::current_time_signature(reaper))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarkerKind {
+    Marker,
+    Region,
+}
+
+impl MarkerKind {
+    fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "marker" => Some(MarkerKind::Marker),
+            "region" => Some(MarkerKind::Region),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MarkerKind::Marker => "marker",
+            MarkerKind::Region => "region",
+        }
+    }
+
+    fn list(&self, reaper: &Reaper) -> Vec<markers::ProjectMarker> {
+        markers::markers_of_kind(reaper, *self == MarkerKind::Region)
+    }
+
+    fn get(&self, reaper: &Reaper, index: u32) -> Result<markers::ProjectMarker, RouteError> {
+        self.list(reaper)
+            .into_iter()
+            .nth(index as usize)
+            .ok_or_else(|| RouteError::ValueNotFound(format!("{} {}", self.as_str(), index)))
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /project/{kind}/count
+/// Arguments:
+/// - kind (string): "marker" or "region"
+/// - count (int): number of markers or regions in the project
+pub struct ProjectMarkerCountRoute;
+
+pub struct ProjectMarkerCountParams {
+    kind: MarkerKind,
+}
+
+impl OscRoute for ProjectMarkerCountRoute {
+    type SendParams = (MarkerKind, usize);
+    type ReceiveParams = ProjectMarkerCountParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", kind, "count"] => Some(ProjectMarkerCountParams {
+                kind: MarkerKind::from_segment(kind)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message((kind, count): Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/project/{}/count", kind.as_str()),
+            args: vec![OscType::Int(count as i32)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok((params.kind, params.kind.list(reaper).len()))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MarkerField {
+    Name,
+    Time,
+    End,
+    Number,
+}
+
+impl MarkerField {
+    fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "name" => Some(MarkerField::Name),
+            "time" => Some(MarkerField::Time),
+            "end" => Some(MarkerField::End),
+            "number" => Some(MarkerField::Number),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MarkerField::Name => "name",
+            MarkerField::Time => "time",
+            MarkerField::End => "end",
+            MarkerField::Number => "number",
+        }
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /project/{kind}/{index}/{field}
+/// Arguments:
+/// - kind (string): "marker" or "region"
+/// - index (int): index of the marker or region, in timeline order
+/// - field (string): one of name, time, end (regions only) or number
+/// - name (string): name of the marker or region, for the name field
+/// - time (float): position of the marker or start of the region in seconds, for the time field
+/// - end (float): end of the region in seconds, for the end field
+/// - number (int): number REAPER displays for the marker or region, for the number field
+pub struct ProjectMarkerRoute;
+
+pub struct ProjectMarkerParams {
+    kind: MarkerKind,
+    index: u32,
+    field: MarkerField,
+}
+
+pub struct ProjectMarkerArgs {
+    pub kind: MarkerKind,
+    pub index: u32,
+    pub field: MarkerField,
+    pub marker: markers::ProjectMarker,
+}
+
+impl OscRoute for ProjectMarkerRoute {
+    type SendParams = ProjectMarkerArgs;
+    type ReceiveParams = ProjectMarkerParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        let params = match segments {
+            ["project", kind, index, field] => ProjectMarkerParams {
+                kind: MarkerKind::from_segment(kind)?,
+                index: index.parse().ok()?,
+                field: MarkerField::from_segment(field)?,
+            },
+            _ => return None,
+        };
+        // Markers have no end
+        (params.kind == MarkerKind::Region || params.field != MarkerField::End).then_some(params)
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        let value = match args.field {
+            MarkerField::Name => OscType::String(args.marker.name),
+            MarkerField::Time => OscType::Float(args.marker.position as f32),
+            MarkerField::End => OscType::Float(args.marker.region_end as f32),
+            MarkerField::Number => OscType::Int(args.marker.number),
+        };
+        OscMessage {
+            addr: format!(
+                "/project/{}/{}/{}",
+                args.kind.as_str(),
+                args.index,
+                args.field.as_str()
+            ),
+            args: vec![value],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(ProjectMarkerArgs {
+            kind: params.kind,
+            index: params.index,
+            field: params.field,
+            marker: params.kind.get(reaper, params.index)?,
+        })
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /project/{kind}/goto
+/// Arguments:
+/// - kind (string): "marker" or "region"
+/// - index (int): index of the marker or region to move the edit cursor, and playback, to, in timeline order
+pub struct ProjectMarkerGotoRoute;
+
+pub struct ProjectMarkerGotoParams {
+    kind: MarkerKind,
+}
+
+impl OscRoute for ProjectMarkerGotoRoute {
+    type SendParams = MarkerKind;
+    type ReceiveParams = ProjectMarkerGotoParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["project", kind, "goto"] => Some(ProjectMarkerGotoParams {
+                kind: MarkerKind::from_segment(kind)?,
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let index = msg
+            .args
+            .first()
+            .and_then(|arg| arg.clone().int())
+            .and_then(|index| u32::try_from(index).ok())
+            .ok_or_else(|| {
+                ReceiverError::BadValue(format!(
+                    "Invalid {} index, expected a non-negative int",
+                    params.kind.as_str()
+                ))
+            })?;
+        let marker = params.kind.get(reaper, index)?;
+        markers::locate(reaper, marker.position);
+        Ok(())
+    }
+
+    fn build_message(kind: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/project/{}/goto", kind.as_str()),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.kind)
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /marker/passed
+/// Arguments:
+/// - index (int): number of the marker playback just crossed, as shown in REAPER
+/// - name (string): name of the marker
+pub struct MarkerPassedRoute;
+
+pub struct MarkerPassedParams;
+
+#[derive(Debug, Clone)]
+pub struct MarkerPassedArgs {
+    pub number: i32,
+    pub name: String,
+}
+
+impl OscRoute for MarkerPassedRoute {
+    type SendParams = MarkerPassedArgs;
+    type ReceiveParams = MarkerPassedParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["marker", "passed"] => Some(MarkerPassedParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/marker/passed".to_string(),
+            args: vec![OscType::Int(args.number), OscType::String(args.name)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        playhead::last_passed_marker()
+            .ok_or_else(|| RouteError::ValueNotFound("Passed marker".to_string()))
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /region/{event}
+/// Arguments:
+/// - event (string): "entered" when playback moves into a region, "left" when it moves out of one or stops inside it
+/// - index (int): number of the region, as shown in REAPER
+/// - name (string): name of the region
+pub struct RegionEventRoute;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegionEvent {
+    Entered,
+    Left,
+}
+
+pub struct RegionEventParams;
+
+pub struct RegionEventArgs {
+    pub event: RegionEvent,
+    pub number: i32,
+    pub name: String,
+}
+
+impl OscRoute for RegionEventRoute {
+    type SendParams = RegionEventArgs;
+    type ReceiveParams = RegionEventParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(_: &[&str]) -> Option<Self::ReceiveParams> {
+        // Region events are only sent, never queried
+        None
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        let event = match args.event {
+            RegionEvent::Entered => "entered",
+            RegionEvent::Left => "left",
+        };
+        OscMessage {
+            addr: format!("/region/{}", event),
+            args: vec![OscType::Int(args.number), OscType::String(args.name)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Err(RouteError::ValueNotFound("Region event".to_string()))
+    }
+}
+
+/// MIDI signal indicators of a track
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MidiActivity {
+    pub input: bool,
+    pub output: bool,
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/midi-activity
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - input (bool): true while MIDI arrives on the track's MIDI input device
+/// - output (bool): true while the track plays a MIDI note
+pub struct TrackMidiActivityRoute;
+
+pub struct TrackMidiActivityParams {
+    track_guid: String,
+}
+
+pub struct TrackMidiActivityArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub activity: MidiActivity,
+}
+
+impl OscRoute for TrackMidiActivityRoute {
+    type SendParams = TrackMidiActivityArgs;
+    type ReceiveParams = TrackMidiActivityParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["track", track_guid, "midi-activity"] => Some(TrackMidiActivityParams {
+                track_guid: track_guid.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!(
+                "/track/{}/midi-activity",
+                get_track_guid(reaper, args.track)
+            ),
+            args: vec![
+                OscType::Bool(args.activity.input),
+                OscType::Bool(args.activity.output),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(TrackMidiActivityArgs {
+            track,
+            activity: midi::activity(reaper, track),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /master/volume
+    /// Arguments:
+    /// - volume (float): volume of the master track, in the surface's volume mode like /track/{track_guid}/volume
+    pub struct MasterVolumeRoute;
+    address: ["master", "volume"];
+    params: MasterVolumeParams {};
+    value: f64;
+    get(reaper, params) {
+        let master = reaper.get_master_track(CurrentProject);
+        let volume = unsafe { reaper.get_media_track_info_value(master, TrackAttributeKey::Vol) };
+        Ok(volume_to_mode_value(
+            reaper,
+            surfaces::volume_mode(),
+            reaper_medium::ReaperVolumeValue::new_panic(volume),
+        ))
+    }
+    set(reaper, params, volume) {
+        let volume = mode_value_to_volume(reaper, surfaces::volume_mode(), volume)
+            .ok_or_else(|| ReceiverError::BadValue("Invalid volume value".to_string()))?;
+        let master = reaper.get_master_track(CurrentProject);
+        if !pickup::accepts_volume(reaper, master, volume) {
+            return Ok(());
+        }
+        unsafe {
+            reaper.csurf_on_volume_change_ex(
+                master,
+                reaper_medium::ValueChange::Absolute(volume),
+                reaper_medium::GangBehavior::DenyGang,
+            );
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /master/pan
+    /// Arguments:
+    /// - pan (float): pan of the master track, normalized to -1.0 to 1.0
+    pub struct MasterPanRoute;
+    address: ["master", "pan"];
+    params: MasterPanParams {};
+    value: f64;
+    get(reaper, params) {
+        let master = reaper.get_master_track(CurrentProject);
+        Ok(unsafe { reaper.get_media_track_info_value(master, TrackAttributeKey::Pan) })
+    }
+    set(reaper, params, pan) {
+        let pan = pan.clamp(-1.0, 1.0);
+        let master = reaper.get_master_track(CurrentProject);
+        if !pickup::accepts_pan(reaper, master, pan) {
+            return Ok(());
+        }
+        let pan = reaper_medium::ReaperPanValue::new_panic(pan);
+        unsafe {
+            reaper.csurf_on_pan_change_ex(
+                master,
+                reaper_medium::ValueChange::Absolute(pan),
+                reaper_medium::GangBehavior::DenyGang,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /master/{switch}
+/// Arguments:
+/// - switch (string): "mute" or "solo"
+/// - on (bool): true means the master track is muted or soloed; how incoming values are applied depends on the button mode of the switch
+pub struct MasterSwitchRoute;
+
+pub struct MasterSwitchParams {
+    switch: Switch,
+}
+
+pub struct MasterSwitchArgs {
+    pub switch: Switch,
+    pub on: bool,
+}
+
+impl MasterSwitchRoute {
+    fn attribute(switch: Switch) -> TrackAttributeKey<'static> {
+        // The matcher only yields mute and solo
+        match switch {
+            Switch::Solo => TrackAttributeKey::Solo,
+            _ => TrackAttributeKey::Mute,
+        }
+    }
+}
+
+impl OscRoute for MasterSwitchRoute {
+    type SendParams = MasterSwitchArgs;
+    type ReceiveParams = MasterSwitchParams;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        let switch = match segments {
+            ["master", "mute"] => Switch::Mute,
+            ["master", "solo"] => Switch::Solo,
+            _ => return None,
+        };
+        Some(MasterSwitchParams { switch })
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let master = reaper.get_master_track(CurrentProject);
+        let current =
+            unsafe { reaper.get_media_track_info_value(master, Self::attribute(params.switch)) };
+        let guid = get_track_guid(reaper, master);
+        let Some(on) = buttons::next_state(params.switch, &guid, msg, current != 0.0)? else {
+            return Ok(());
+        };
+        unsafe {
+            match params.switch {
+                Switch::Solo => reaper.csurf_on_solo_change_ex(
+                    master,
+                    on,
+                    reaper_medium::GangBehavior::DenyGang,
+                ),
+                _ => reaper.csurf_on_mute_change_ex(
+                    master,
+                    on,
+                    reaper_medium::GangBehavior::DenyGang,
+                ),
+            };
+        }
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: format!("/master/{}", args.switch.as_str()),
+            args: vec![OscType::Bool(args.on)],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let master = reaper.get_master_track(CurrentProject);
+        let on =
+            unsafe { reaper.get_media_track_info_value(master, Self::attribute(params.switch)) };
+        Ok(MasterSwitchArgs {
+            switch: params.switch,
+            on: on != 0.0,
+        })
+    }
+}
+
+/// Meter channel of `/track/{track_guid}/vu`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VuChannel {
+    /// The louder of both channels
+    Both,
+    Left,
+    Right,
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /track/{track_guid}/vu, /track/{track_guid}/vu/L, /track/{track_guid}/vu/R
+/// Arguments:
+/// - track_guid (string): unique identifier for the track
+/// - level (float): meter level with the configured decay, where 1.0 is 0dB; the louder channel without a suffix. In pre-fader mode only the track volume is taken out, not the pan or pan law, so panned tracks read lower on one side than what comes into the fader
+/// - hold (float): highest level within the configured peak-hold time
+pub struct TrackVuRoute;
+
+pub struct TrackVuParams {
+    track_guid: String,
+    channel: VuChannel,
+}
+
+pub struct TrackVuArgs {
+    pub track: reaper_medium::MediaTrack,
+    pub channel: VuChannel,
+    pub level: vu::VuLevel,
+}
+
+impl OscRoute for TrackVuRoute {
+    type SendParams = TrackVuArgs;
+    type ReceiveParams = TrackVuParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        let (track_guid, channel) = match segments {
+            ["track", track_guid, "vu"] => (track_guid, VuChannel::Both),
+            ["track", track_guid, "vu", "L"] => (track_guid, VuChannel::Left),
+            ["track", track_guid, "vu", "R"] => (track_guid, VuChannel::Right),
+            _ => return None,
+        };
+        Some(TrackVuParams {
+            track_guid: track_guid.to_string(),
+            channel,
+        })
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(args: Self::SendParams, reaper: &Reaper) -> OscMessage {
+        let suffix = match args.channel {
+            VuChannel::Both => "",
+            VuChannel::Left => "/L",
+            VuChannel::Right => "/R",
+        };
+        OscMessage {
+            addr: format!("/track/{}/vu{}", get_track_guid(reaper, args.track), suffix),
+            args: vec![
+                OscType::Float(args.level.level as f32),
+                OscType::Float(args.level.hold as f32),
+            ],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let reading = vu::latest(&get_track_guid(reaper, track));
+        Ok(TrackVuArgs {
+            track,
+            channel: params.channel,
+            level: reading.channel(params.channel),
+        })
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /track/{track_guid}/receive/count
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - count (int): number of sends from other tracks arriving at the track
+    pub struct TrackReceiveCountRoute;
+    address: ["track", track_guid, "receive", "count"];
+    params: TrackReceiveCountParams { track_guid: String };
+    value: i32;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let count = unsafe {
+            reaper.get_track_num_sends(track, reaper_medium::TrackSendCategory::Receive)
+        };
+        Ok(count as i32)
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /track/{track_guid}/receive/{receive_index}/guid
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - receive_index (int): index of the receive on the track
+    /// - guid (string): unique identifier for the track the receive comes from
+    pub struct TrackReceiveGuidRoute;
+    address: ["track", track_guid, "receive", receive_index, "guid"];
+    params: TrackReceiveGuidParams { track_guid: String, receive_index: u32 };
+    value: String;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let source = unsafe {
+            reaper.get_track_send_info_srctrack(
+                track,
+                reaper_medium::TrackSendDirection::Receive,
+                params.receive_index,
+            )
+        }
+        .map_err(|_| RouteError::ValueNotFound("Failed to retrieve receive track".to_string()))?;
+        Ok(get_track_guid(reaper, source))
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/receive/{receive_index}/volume
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - receive_index (int): index of the receive on the track
+    /// - volume (float): volume of the receive, linear where 1.0 is 0dB
+    pub struct TrackReceiveVolumeRoute;
+    address: ["track", track_guid, "receive", receive_index, "volume"];
+    params: TrackReceiveVolumeParams { track_guid: String, receive_index: u32 };
+    value: f64;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(unsafe {
+            reaper.get_track_send_info_value(
+                track,
+                reaper_medium::TrackSendCategory::Receive,
+                params.receive_index,
+                reaper_medium::TrackSendAttributeKey::Vol,
+            )
+        })
+    }
+    set(reaper, params, volume) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let volume = reaper_medium::ReaperVolumeValue::new(volume)
+            .map_err(|_| ReceiverError::BadValue("Invalid volume value".to_string()))?;
+        unsafe {
+            reaper.set_track_send_ui_vol(
+                track,
+                reaper_medium::TrackSendRef::Receive(params.receive_index),
+                volume,
+                reaper_medium::EditMode::NormalTweak,
+            )?
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/receive/{receive_index}/pan
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - receive_index (int): index of the receive on the track
+    /// - pan (float): pan of the receive, normalized to -1.0 to 1.0
+    pub struct TrackReceivePanRoute;
+    address: ["track", track_guid, "receive", receive_index, "pan"];
+    params: TrackReceivePanParams { track_guid: String, receive_index: u32 };
+    value: f64;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        Ok(unsafe {
+            reaper.get_track_send_info_value(
+                track,
+                reaper_medium::TrackSendCategory::Receive,
+                params.receive_index,
+                reaper_medium::TrackSendAttributeKey::Pan,
+            )
+        })
+    }
+    set(reaper, params, pan) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let pan = reaper_medium::ReaperPanValue::new(pan)
+            .map_err(|_| ReceiverError::BadValue("Invalid pan value".to_string()))?;
+        unsafe {
+            reaper.set_track_send_ui_pan(
+                track,
+                reaper_medium::TrackSendRef::Receive(params.receive_index),
+                pan,
+                reaper_medium::EditMode::NormalTweak,
+            )?
+        }
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/receive/{receive_index}/mute
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - receive_index (int): index of the receive on the track
+    /// - mute (bool): whether the receive is muted
+    pub struct TrackReceiveMuteRoute;
+    address: ["track", track_guid, "receive", receive_index, "mute"];
+    params: TrackReceiveMuteParams { track_guid: String, receive_index: u32 };
+    value: bool;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let muted = unsafe {
+            reaper.get_track_send_info_value(
+                track,
+                reaper_medium::TrackSendCategory::Receive,
+                params.receive_index,
+                reaper_medium::TrackSendAttributeKey::Mute,
+            )
+        };
+        Ok(muted != 0.0)
+    }
+    set(reaper, params, muted) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        set_track_send_info_value(
+            reaper,
+            track,
+            reaper_medium::TrackSendCategory::Receive,
+            params.receive_index,
+            c_str!("B_MUTE"),
+            if muted { 1.0 } else { 0.0 },
+        )?;
+        Ok(())
+    }
+}
+
+/// One track-to-track send in the routing matrix
+pub struct MatrixSend {
+    pub source: reaper_medium::MediaTrack,
+    pub dest: reaper_medium::MediaTrack,
+    /// Linear volume, 1.0 being 0 dB
+    pub level: f64,
+    pub mode: &'static str,
+}
+
+impl MatrixSend {
+    fn collect(reaper: &Reaper, source: reaper_medium::MediaTrack, index: u32) -> Option<Self> {
+        unsafe {
+            let dest = reaper
+                .get_track_send_info_desttrack(
+                    source,
+                    reaper_medium::TrackSendDirection::Send,
+                    index,
+                )
+                .ok()?;
+            let value = |key| {
+                reaper.get_track_send_info_value(
+                    source,
+                    reaper_medium::TrackSendCategory::Send,
+                    index,
+                    key,
+                )
+            };
+            // I_SENDMODE: 0 post-fader, 1 pre-FX, 3 pre-fader (post-FX)
+            let mode = match value(reaper_medium::TrackSendAttributeKey::SendMode) as i32 {
+                1 => "pre-fx",
+                3 => "pre-fader",
+                _ => "post-fader",
+            };
+            Some(MatrixSend {
+                source,
+                dest,
+                level: value(reaper_medium::TrackSendAttributeKey::Vol),
+                mode,
+            })
+        }
+    }
+
+    fn into_message(self, reaper: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/matrix/send".to_string(),
+            args: vec![
+                OscType::String(get_track_guid(reaper, self.source)),
+                OscType::String(get_track_guid(reaper, self.dest)),
+                OscType::Float(self.level as f32),
+                OscType::String(self.mode.to_string()),
+            ],
+        }
+    }
+}
+
+/// @osc-doc
+/// @readonly
+/// OSC Address: /matrix
+/// Arguments:
+/// - send_count (int): number of track-to-track sends in the project; followed in the same bundle by one bundle per source track holding a /matrix/send message per send with the source GUID, destination GUID, linear level (1.0 is 0dB) and mode ("post-fader", "pre-fader" (after the FX) or "pre-fx")
+pub struct MatrixRoute;
+
+pub struct MatrixParams;
+
+impl OscRoute for MatrixRoute {
+    type SendParams = Vec<Vec<MatrixSend>>;
+    type ReceiveParams = MatrixParams;
+
+    const READONLY: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["matrix"] => Some(MatrixParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, _: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        Ok(())
+    }
+
+    fn build_message(sends: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/matrix".to_string(),
+            args: vec![OscType::Int(
+                sends.iter().map(Vec::len).sum::<usize>() as i32
+            )],
+        }
+    }
+
+    fn build_packet(sends: Self::SendParams, reaper: &Reaper) -> OscPacket {
+        let header = OscMessage {
+            addr: "/matrix".to_string(),
+            args: vec![OscType::Int(
+                sends.iter().map(Vec::len).sum::<usize>() as i32
+            )],
+        };
+        let mut content = vec![OscPacket::Message(header)];
+        content.extend(sends.into_iter().map(|track_sends| {
+            immediate_bundle(
+                track_sends
+                    .into_iter()
+                    .map(|send| OscPacket::Message(send.into_message(reaper)))
+                    .collect(),
+            )
+        }));
+        immediate_bundle(content)
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        reaper: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok((0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+            .map(|track| {
+                let count = unsafe {
+                    reaper.get_track_num_sends(track, reaper_medium::TrackSendCategory::Send)
+                };
+                (0..count)
+                    .filter_map(|index| MatrixSend::collect(reaper, track, index))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|track_sends| !track_sends.is_empty())
+            .collect())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /master/mono
+    /// Arguments:
+    /// - mono (bool): true means the master is summed to mono (L+R) for monitoring
+    pub struct MasterMonoRoute;
+    address: ["master", "mono"];
+    params: MasterMonoParams {};
+    value: bool;
+    get(reaper, params) {
+        Ok(master::is_mono(reaper))
+    }
+    set(reaper, params, mono) {
+        master::set_mono(reaper, mono);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /master/dim
+    /// Arguments:
+    /// - dim (bool): true turns every hardware output of the master down by the dim amount; false restores their volumes
+    pub struct MasterDimRoute;
+    address: ["master", "dim"];
+    params: MasterDimParams {};
+    value: bool;
+    get(reaper, params) {
+        Ok(master::is_dimmed())
+    }
+    set(reaper, params, dim) {
+        master::set_dimmed(reaper, dim)?;
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /master/dim-amount
+    /// Arguments:
+    /// - amount (float): attenuation in dB applied by /master/dim, from -inf to 0; defaults to -20
+    pub struct MasterDimAmountRoute;
+    address: ["master", "dim-amount"];
+    params: MasterDimAmountParams {};
+    value: f64;
+    get(reaper, params) {
+        Ok(master::dim_amount())
+    }
+    set(reaper, params, amount) {
+        if amount.is_nan() || amount > 0.0 {
+            return Err(ReceiverError::BadValue(
+                "Invalid dim amount, expected a non-positive float".to_string(),
+            ));
+        }
+        master::set_dim_amount(reaper, amount)?;
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /transport/time
+    /// Arguments:
+    /// - seconds (float): play position while playing, otherwise the edit cursor; setting it moves the edit cursor, and playback along with it
+    pub struct TransportTimeRoute;
+    address: ["transport", "time"];
+    params: TransportTimeParams {};
+    value: f64;
+    undoable: false;
+    get(reaper, params) {
+        Ok(markers::playhead_position(reaper))
+    }
+    set(reaper, params, seconds) {
+        if seconds < 0.0 {
+            return Err(ReceiverError::BadValue(format!("Invalid position {}", seconds)));
+        }
+        markers::locate(reaper, seconds);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /transport/beats
+    /// Arguments:
+    /// - beats (string): play position while playing, otherwise the edit cursor, as bars.beats following the tempo map, e.g. "17.3.50"
+    pub struct TransportBeatsRoute;
+    address: ["transport", "beats"];
+    params: TransportBeatsParams {};
+    value: String;
+    get(reaper, params) {
+        Ok(markers::format_beats(reaper, markers::playhead_position(reaper)))
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// @readonly
+    /// OSC Address: /transport/samples
+    /// Arguments:
+    /// - samples (long): play position while playing, otherwise the edit cursor, in samples at the project sample rate
+    pub struct TransportSamplesRoute;
+    address: ["transport", "samples"];
+    params: TransportSamplesParams {};
+    value: i64;
+    get(reaper, params) {
+        Ok(markers::position_samples(reaper, markers::playhead_position(reaper)))
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /transport/locate
+/// Arguments:
+/// - position (string): timecode as hours:minutes:seconds:frames (e.g. "01:00:15:12") at the project frame rate, or bars.beats (e.g. "17.3" or "17.3.50") through the tempo map; moves the edit cursor, and the play position while playing
+pub struct TransportLocateRoute;
+
+pub struct TransportLocateParams;
+
+impl OscRoute for TransportLocateRoute {
+    type SendParams = ();
+    type ReceiveParams = TransportLocateParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["transport", "locate"] => Some(TransportLocateParams),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        _: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        let position = msg
+            .args
+            .first()
+            .and_then(|arg| arg.clone().string())
+            .and_then(|text| markers::parse_position(reaper, &text))
+            .ok_or_else(|| {
+                ReceiverError::BadValue(
+                    "Invalid locate position, expected timecode or bars.beats".to_string(),
+                )
+            })?;
+        markers::locate(reaper, position);
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/transport/locate".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+fn pattern_args(msg: &OscMessage) -> Result<Vec<String>, ReceiverError> {
+    msg.args
+        .iter()
+        .map(|arg| {
+            arg.clone().string().ok_or_else(|| {
+                ReceiverError::BadValue("Invalid address pattern, expected a string".to_string())
+            })
+        })
+        .collect()
+}
+
+fn subscribing_client() -> Result<SocketAddrV4, ReceiverError> {
+    clients::current_client()
+        .ok_or_else(|| ReceiverError::BadValue("Unknown client address".to_string()))
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /subscribe
+/// Arguments:
+/// - patterns (string...): address patterns of the feedback to send to the address this message came from; `*` matches any part of a segment and a pattern covers every address below it, e.g. "/track/*/volume" or "/transport"
+pub struct SubscribeRoute;
+
+pub struct SubscribeParams;
+
+impl OscRoute for SubscribeRoute {
+    type SendParams = ();
+    type ReceiveParams = SubscribeParams;
+
+    const ALLOWED_IN_LOCKOUT: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["subscribe"] => Some(SubscribeParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let patterns = pattern_args(msg)?;
+        if patterns.is_empty() {
+            return Err(ReceiverError::BadValue(
+                "Missing address pattern, expected at least one string".to_string(),
+            ));
+        }
+        subscriptions::subscribe(subscribing_client()?, patterns);
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/subscribe".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /unsubscribe
+/// Arguments:
+/// - patterns (string...): patterns previously passed to /subscribe by the same address; without any, every subscription of that address is removed
+pub struct UnsubscribeRoute;
+
+pub struct UnsubscribeParams;
+
+impl OscRoute for UnsubscribeRoute {
+    type SendParams = ();
+    type ReceiveParams = UnsubscribeParams;
+
+    const ALLOWED_IN_LOCKOUT: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["unsubscribe"] => Some(UnsubscribeParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let patterns = pattern_args(msg)?;
+        subscriptions::unsubscribe(subscribing_client()?, &patterns);
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/unsubscribe".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /transport/{play-quantized|stop-quantized}
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored. play-quantized starts playback from the first bar line at or after the edit cursor; stop-quantized stops playback when it reaches the next bar line, following the tempo map
+pub struct TransportQuantizedRoute;
+
+pub struct TransportQuantizedParams {
+    play: bool,
+}
+
+impl OscRoute for TransportQuantizedRoute {
+    type SendParams = bool;
+    type ReceiveParams = TransportQuantizedParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["transport", "play-quantized"] => Some(TransportQuantizedParams { play: true }),
+            ["transport", "stop-quantized"] => Some(TransportQuantizedParams { play: false }),
+            _ => None,
+        }
+    }
+
+    fn receive(
+        params: Self::ReceiveParams,
+        msg: &OscMessage,
+        reaper: &Reaper,
+    ) -> Result<(), ReceiverError> {
+        if !is_trigger(msg) {
+            return Ok(());
+        }
+        if params.play {
+            quantize::play(reaper);
+        } else {
+            quantize::stop(reaper);
+        }
+        Ok(())
+    }
+
+    fn build_message(play: Self::SendParams, _: &Reaper) -> OscMessage {
+        let action = if play { "play" } else { "stop" };
+        OscMessage {
+            addr: format!("/transport/{}-quantized", action),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        params: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(params.play)
+    }
+}
+
+/// @osc-doc
+/// OSC Address: /arpad/lockout
+/// Arguments:
+/// - locked (bool): true makes the plugin ignore every message that would change the project until unlocked; queries, feedback and subscriptions keep working. While locked, only the client or surface that set the lockout, or an admin surface, may change it
+pub struct LockoutRoute;
+
+pub struct LockoutParams;
+
+impl OscRoute for LockoutRoute {
+    type SendParams = bool;
+    type ReceiveParams = LockoutParams;
+
+    const ALLOWED_IN_LOCKOUT: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "lockout"] => Some(LockoutParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let locked = msg
+            .args
+            .first()
+            .and_then(|arg| arg.clone().bool())
+            .ok_or_else(|| {
+                ReceiverError::BadValue("Invalid lockout value, expected a bool".to_string())
+            })?;
+        let client = clients::current_client();
+        let namespace = surfaces::current_namespace();
+        if !lockout::may_change(client, &namespace, surfaces::is_admin()) {
+            return Err(ReceiverError::BadValue(
+                "Lockout can only be changed by the sender that set it or an admin surface"
+                    .to_string(),
+            ));
+        }
+        lockout::set_locked_by(locked, client, &namespace);
+        Ok(())
+    }
+
+    fn build_message(locked: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/lockout".to_string(),
+            args: vec![OscType::Bool(locked)],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(lockout::is_locked())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /arpad/sync
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored. Sends the sender the current value of every track, send, transport, tempo and master address, as replies to queries
+pub struct SyncRoute;
+
+pub struct SyncParams;
+
+impl OscRoute for SyncRoute {
+    type SendParams = ();
+    type ReceiveParams = SyncParams;
+
+    const ALLOWED_IN_LOCKOUT: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "sync"] => Some(SyncParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        if is_trigger(msg) {
+            sync::request();
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/sync".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /arpad/undo-last
+/// Arguments:
+/// - trigger (bool): optional; false or 0 is ignored. Sets back the value the sending surface changed most recently, leaving edits made in REAPER and through other surfaces alone. Quick runs of changes to one address, like a fader move, are undone together; the last 100 changes are kept
+pub struct UndoLastRoute;
+
+pub struct UndoLastParams;
+
+impl OscRoute for UndoLastRoute {
+    type SendParams = ();
+    type ReceiveParams = UndoLastParams;
+
+    const UNDOABLE: bool = false;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "undo-last"] => Some(UndoLastParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        if is_trigger(msg) {
+            undo::request();
+        }
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/undo-last".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+/// @osc-doc
+/// @writeonly
+/// OSC Address: /arpad/blob/ack
+/// Arguments:
+/// - id (int): transfer id of the fragment received
+/// - index (int): index of the fragment received. Fragments not acknowledged are sent again
+pub struct BlobAckRoute;
+
+pub struct BlobAckParams;
+
+impl OscRoute for BlobAckRoute {
+    type SendParams = ();
+    type ReceiveParams = BlobAckParams;
+
+    const ALLOWED_IN_LOCKOUT: bool = true;
+
+    fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+        match segments {
+            ["arpad", "blob", "ack"] => Some(BlobAckParams),
+            _ => None,
+        }
+    }
+
+    fn receive(_: Self::ReceiveParams, msg: &OscMessage, _: &Reaper) -> Result<(), ReceiverError> {
+        let [OscType::Int(id), OscType::Int(index)] = msg.args.as_slice() else {
+            return Err(ReceiverError::BadValue(
+                "Invalid acknowledgment, expected a transfer id and a fragment index".to_string(),
+            ));
+        };
+        transfer::acknowledge(*id, *index);
+        Ok(())
+    }
+
+    fn build_message(_: Self::SendParams, _: &Reaper) -> OscMessage {
+        OscMessage {
+            addr: "/arpad/blob/ack".to_string(),
+            args: vec![],
+        }
+    }
+
+    fn collect_send_params(
+        _: &Self::ReceiveParams,
+        _: &Reaper,
+    ) -> Result<Self::SendParams, RouteError> {
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /arpad/config/project
+    /// Arguments:
+    /// - config (string): settings saved in the active project, in the format of arpad.toml, overriding the config file while the project is active. Only the [talkback], [click], [strip], [buttons] and [smoothing] sections are taken. An empty string removes the overrides
+    pub struct ProjectConfigRoute;
+    address: ["arpad", "config", "project"];
+    params: ProjectConfigParams {};
+    value: String;
+    undoable: false;
+    get(reaper, params) {
+        Ok(config::project_block(reaper).unwrap_or_default())
+    }
+    set(reaper, params, block) {
+        config::set_project_block(reaper, &block)
+            .map_err(|e| ReceiverError::BadValue(format!("Invalid project config: {}", e)))
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/envelope/{env_name}/visible
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - env_name (string): envelope name as REAPER shows it, e.g. "Volume", or its chunk name, e.g. "VOLENV2"
+    /// - visible (bool): true shows the envelope in the arrange view
+    pub struct TrackEnvelopeVisibleRoute;
+    address: ["track", track_guid, "envelope", env_name, "visible"];
+    params: TrackEnvelopeVisibleParams { track_guid: String, env_name: String };
+    value: bool;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let env = envelopes::find(reaper, track, &params.env_name)?;
+        Ok(envelopes::flag(reaper, env, EnvelopeFlag::Visible))
+    }
+    set(reaper, params, visible) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let env = envelopes::find(reaper, track, &params.env_name)?;
+        envelopes::set_flag(reaper, env, EnvelopeFlag::Visible, visible);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/envelope/{env_name}/armed
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - env_name (string): envelope name as REAPER shows it, e.g. "Volume", or its chunk name, e.g. "VOLENV2"
+    /// - armed (bool): true arms the envelope for writing automation
+    pub struct TrackEnvelopeArmedRoute;
+    address: ["track", track_guid, "envelope", env_name, "armed"];
+    params: TrackEnvelopeArmedParams { track_guid: String, env_name: String };
+    value: bool;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let env = envelopes::find(reaper, track, &params.env_name)?;
+        Ok(envelopes::flag(reaper, env, EnvelopeFlag::Armed))
+    }
+    set(reaper, params, armed) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let env = envelopes::find(reaper, track, &params.env_name)?;
+        envelopes::set_flag(reaper, env, EnvelopeFlag::Armed, armed);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/envelope/{env_name}/value
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - env_name (string): envelope name as REAPER shows it, e.g. "Volume", or its chunk name, e.g. "VOLENV2"
+    /// - value (float): envelope value at the edit cursor: gain for volume, where 1.0 is 0dB, -1.0 to 1.0 for pan and width, and 0 to 1.0 for FX parameters. Setting it writes a point at the edit cursor
+    pub struct TrackEnvelopeValueRoute;
+    address: ["track", track_guid, "envelope", env_name, "value"];
+    params: TrackEnvelopeValueParams { track_guid: String, env_name: String };
+    value: f64;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let env = envelopes::find(reaper, track, &params.env_name)?;
+        Ok(envelopes::value_at_cursor(reaper, env))
+    }
+    set(reaper, params, value) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let env = envelopes::find(reaper, track, &params.env_name)?;
+        envelopes::write_at_cursor(reaper, env, value);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/item/{item_index}/mute
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - item_index (int): index of the item on the track, from the earliest
+    /// - mute (bool): true mutes the item
+    pub struct TrackItemMuteRoute;
+    address: ["track", track_guid, "item", item_index, "mute"];
+    params: TrackItemMuteParams { track_guid: String, item_index: u32 };
+    value: bool;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let item = items::item(reaper, track, params.item_index)?;
+        Ok(items::get(reaper, item, ItemAttribute::Mute) != 0.0)
+    }
+    set(reaper, params, mute) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let item = items::item(reaper, track, params.item_index)?;
+        items::set(reaper, item, ItemAttribute::Mute, if mute { 1.0 } else { 0.0 });
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/item/{item_index}/position
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - item_index (int): index of the item on the track, from the earliest
+    /// - position (float): start of the item in seconds from the project start. Moving an item can change its index
+    pub struct TrackItemPositionRoute;
+    address: ["track", track_guid, "item", item_index, "position"];
+    params: TrackItemPositionParams { track_guid: String, item_index: u32 };
+    value: f64;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let item = items::item(reaper, track, params.item_index)?;
+        Ok(items::get(reaper, item, ItemAttribute::Position))
+    }
+    set(reaper, params, position) {
+        if position < 0.0 {
+            return Err(ReceiverError::BadValue(
+                "Invalid item position, expected seconds from the project start".to_string(),
+            ));
+        }
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let item = items::item(reaper, track, params.item_index)?;
+        items::set(reaper, item, ItemAttribute::Position, position);
+        Ok(())
+    }
+}
+
+define_osc_route! {
+    /// @osc-doc
+    /// OSC Address: /track/{track_guid}/item/{item_index}/selected
+    /// Arguments:
+    /// - track_guid (string): unique identifier for the track
+    /// - item_index (int): index of the item on the track, from the earliest
+    /// - selected (bool): true adds the item to the selection, false removes it
+    pub struct TrackItemSelectedRoute;
+    address: ["track", track_guid, "item", item_index, "selected"];
+    params: TrackItemSelectedParams { track_guid: String, item_index: u32 };
+    value: bool;
+    get(reaper, params) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let item = items::item(reaper, track, params.item_index)?;
+        Ok(items::get(reaper, item, ItemAttribute::Selected) != 0.0)
+    }
+    set(reaper, params, selected) {
+        let track = get_track_by_guid(reaper, &params.track_guid)?;
+        let item = items::item(reaper, track, params.item_index)?;
+        items::set(reaper, item, ItemAttribute::Selected, if selected { 1.0 } else { 0.0 });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(args: Vec<OscType>) -> OscMessage {
+        OscMessage {
+            addr: "/cue/next".to_string(),
+            args,
+        }
+    }
+
+    #[test]
+    fn button_releases_are_not_triggers() {
+        assert!(!is_trigger(&message(vec![OscType::Float(0.0)])));
+        assert!(!is_trigger(&message(vec![OscType::Int(0)])));
+        assert!(!is_trigger(&message(vec![OscType::Bool(false)])));
+    }
+
+    #[test]
+    fn presses_and_bare_messages_are_triggers() {
+        assert!(is_trigger(&message(vec![])));
+        assert!(is_trigger(&message(vec![OscType::Float(1.0)])));
+        assert!(is_trigger(&message(vec![OscType::Int(1)])));
+        assert!(is_trigger(&message(vec![OscType::Bool(true)])));
+    }
+
+    #[test]
+    fn routes_without_setters_are_readonly() {
+        assert!(GroupsRoute::READONLY);
+        assert!(ProjectNameRoute::READONLY);
+        assert!(!MonitorFxBypassRoute::READONLY);
+        assert!(!TrackMuteRoute::READONLY);
     }
 }
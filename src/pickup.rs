@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use reaper_medium::{MediaTrack, Reaper, ReaperVolumeValue, TrackAttributeKey};
+
+use crate::surfaces;
+use crate::utils::{get_track_guid, volume_to_normalized};
+
+// How close a fader has to come to REAPER's value to pick it up without crossing it,
+// as a fraction of the fader's travel
+const PICKUP_WINDOW: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Param {
+    Volume,
+    Pan,
+}
+
+// Last value each surface sent for a control, keyed by surface namespace, track GUID
+// and parameter, in fader travel from 0 to 1.0
+static LAST_RECEIVED: LazyLock<Mutex<HashMap<(String, String, Param), f64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether a volume received from the current surface should be applied
+pub fn accepts_volume(reaper: &Reaper, track: MediaTrack, volume: ReaperVolumeValue) -> bool {
+    let current = ReaperVolumeValue::new_panic(unsafe {
+        reaper.get_media_track_info_value(track, TrackAttributeKey::Vol)
+    });
+    accepts(
+        reaper,
+        track,
+        Param::Volume,
+        volume_to_normalized(reaper, current),
+        volume_to_normalized(reaper, volume),
+    )
+}
+
+/// Whether a pan received from the current surface should be applied
+pub fn accepts_pan(reaper: &Reaper, track: MediaTrack, pan: f64) -> bool {
+    let current = unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Pan) };
+    // Pan travels from -1.0 to 1.0
+    accepts(
+        reaper,
+        track,
+        Param::Pan,
+        (current + 1.0) / 2.0,
+        (pan + 1.0) / 2.0,
+    )
+}
+
+// Surfaces without pickup always apply. With pickup, a value applies once the fader
+// comes within the window of REAPER's value or crosses it since the previous value, and
+// keeps applying while the fader moves along with it.
+fn accepts(reaper: &Reaper, track: MediaTrack, param: Param, current: f64, received: f64) -> bool {
+    let namespace = surfaces::current_namespace();
+    if !surfaces::profile_for_namespace(&namespace).is_some_and(|p| p.pickup) {
+        return true;
+    }
+    let key = (namespace, get_track_guid(reaper, track), param);
+    let previous = LAST_RECEIVED.lock().unwrap().insert(key, received);
+    (received - current).abs() <= PICKUP_WINDOW
+        || previous.is_some_and(|previous| (previous - current) * (received - current) <= 0.0)
+}
@@ -0,0 +1,137 @@
+use std::sync::Mutex;
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::Reaper;
+use rosc::OscPacket;
+
+use crate::feedback::{Feedback, Lane};
+use crate::markers::{project_markers, ProjectMarker};
+use crate::osc_routes::{
+    MarkerPassedArgs, MarkerPassedRoute, RegionEvent, RegionEventArgs, RegionEventRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::OscRoute;
+
+// A forward step longer than this between two polls is a seek, not playback, so the
+// markers skipped over aren't reported as passed
+const MAX_PLAYBACK_STEP: f64 = 1.0;
+
+static LAST_PASSED_MARKER: Mutex<Option<MarkerPassedArgs>> = Mutex::new(None);
+
+/// The marker playback crossed most recently
+pub fn last_passed_marker() -> Option<MarkerPassedArgs> {
+    LAST_PASSED_MARKER.lock().unwrap().clone()
+}
+
+/// Follows the play position during playback and reports what it crosses
+pub struct PlayPositionPollSource {
+    reaper: Reaper,
+    // Play position at the previous poll; None while stopped
+    prev: Option<f64>,
+    // Regions the play position was inside at the previous poll
+    regions: Vec<ProjectMarker>,
+}
+
+impl PlayPositionPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            prev: None,
+            regions: Vec::new(),
+        }
+    }
+
+    fn send_region_event(
+        &self,
+        osc_sender: &Sender<Feedback>,
+        event: RegionEvent,
+        region: &ProjectMarker,
+    ) -> Result<(), PollError> {
+        let args = RegionEventArgs {
+            event,
+            number: region.number,
+            name: region.name.clone(),
+        };
+        osc_sender
+            .send(OscPacket::Message(RegionEventRoute::build_message(args, &self.reaper)).into())
+            .map_err(PollError::Send)
+    }
+
+    /// Reports the regions left and entered since the previous poll. Stopping counts as
+    /// leaving every region.
+    fn update_regions(
+        &mut self,
+        osc_sender: &Sender<Feedback>,
+        markers: &[ProjectMarker],
+        position: Option<f64>,
+    ) -> Result<(), PollError> {
+        let current: Vec<ProjectMarker> = match position {
+            Some(position) => markers
+                .iter()
+                .filter(|m| m.is_region && m.position <= position && position < m.region_end)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        for region in self.regions.iter().filter(|r| !current.contains(r)) {
+            self.send_region_event(osc_sender, RegionEvent::Left, region)?;
+        }
+        for region in current.iter().filter(|r| !self.regions.contains(r)) {
+            self.send_region_event(osc_sender, RegionEvent::Entered, region)?;
+        }
+        self.regions = current;
+        Ok(())
+    }
+
+    fn send_passed_markers(
+        &self,
+        osc_sender: &Sender<Feedback>,
+        markers: &[ProjectMarker],
+        prev: f64,
+        position: f64,
+    ) -> Result<(), PollError> {
+        for marker in markers
+            .iter()
+            .filter(|m| !m.is_region && m.position > prev && m.position <= position)
+        {
+            let args = MarkerPassedArgs {
+                number: marker.number,
+                name: marker.name.clone(),
+            };
+            *LAST_PASSED_MARKER.lock().unwrap() = Some(args.clone());
+            osc_sender
+                .send(
+                    OscPacket::Message(MarkerPassedRoute::build_message(args, &self.reaper)).into(),
+                )
+                .map_err(PollError::Send)?;
+        }
+        Ok(())
+    }
+}
+
+impl PollSource for PlayPositionPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if !self.reaper.get_play_state_ex(CurrentProject).is_playing {
+            self.prev = None;
+            if !self.regions.is_empty() {
+                self.update_regions(osc_sender, &[], None)?;
+            }
+            return Ok(());
+        }
+        let markers = project_markers(&self.reaper);
+        let position = self.reaper.get_play_position_ex(CurrentProject).get();
+        self.update_regions(osc_sender, &markers, Some(position))?;
+        let Some(prev) = self.prev.replace(position) else {
+            return Ok(());
+        };
+        if position <= prev || position - prev > MAX_PLAYBACK_STEP {
+            return Ok(());
+        }
+        self.send_passed_markers(osc_sender, &markers, prev, position)
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::Reaper;
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{TransportPreservePitchParams, TransportPreservePitchRoute};
+use crate::polling::{PollError, PollSource};
+use crate::utils::{get_toggle_action_state, set_toggle_action_state};
+use crate::OscRoute;
+
+// "Transport: Toggle preserve pitch in audio items when changing master playrate"
+const PRESERVE_PITCH_COMMAND: u32 = 40671;
+
+// Range of REAPER's playrate slider
+const MIN_PLAY_RATE: f64 = 0.25;
+const MAX_PLAY_RATE: f64 = 4.0;
+
+const PRESERVE_PITCH_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The master playrate of the active project, where 1.0 is normal speed
+pub fn play_rate(reaper: &Reaper) -> f64 {
+    unsafe { reaper.low().Master_GetPlayRate(std::ptr::null_mut()) }
+}
+
+/// Sets the master playrate as REAPER's playrate slider does. Returns false for rates
+/// the slider can't reach.
+pub fn set_play_rate(reaper: &Reaper, rate: f64) -> bool {
+    if !(MIN_PLAY_RATE..=MAX_PLAY_RATE).contains(&rate) {
+        return false;
+    }
+    unsafe { reaper.low().CSurf_OnPlayRateChange(rate) };
+    true
+}
+
+/// Whether audio items keep their pitch when the playrate changes, or follow it like
+/// tape
+pub fn preserves_pitch(reaper: &Reaper) -> bool {
+    get_toggle_action_state(reaper, PRESERVE_PITCH_COMMAND)
+}
+
+pub fn set_preserve_pitch(reaper: &Reaper, preserve: bool) {
+    set_toggle_action_state(reaper, PRESERVE_PITCH_COMMAND, preserve);
+}
+
+/// Sends `/transport/preserve-pitch` when it is switched, from a surface or from
+/// REAPER itself. REAPER has no control surface callback for it.
+pub struct PreservePitchPollSource {
+    reaper: Reaper,
+    last_check: Instant,
+    // None until the first check, which records the state without sending it
+    preserve: Option<bool>,
+}
+
+impl PreservePitchPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_check: Instant::now(),
+            preserve: None,
+        }
+    }
+}
+
+impl PollSource for PreservePitchPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_check.elapsed() < PRESERVE_PITCH_CHECK_INTERVAL {
+            return Ok(());
+        }
+        self.last_check = Instant::now();
+        let preserve = preserves_pitch(&self.reaper);
+        if self
+            .preserve
+            .replace(preserve)
+            .is_none_or(|prev| prev == preserve)
+        {
+            return Ok(());
+        }
+        osc_sender
+            .send(
+                OscPacket::Message(TransportPreservePitchRoute::build_message(
+                    (TransportPreservePitchParams {}, preserve),
+                    &self.reaper,
+                ))
+                .into(),
+            )
+            .map_err(PollError::Send)
+    }
+}
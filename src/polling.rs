@@ -6,6 +6,7 @@ use reaper_medium::Reaper;
 use crossbeam_channel::Sender;
 use rosc::OscPacket;
 
+use crate::feedback::{Feedback, Lane};
 use crate::osc_routes::{self};
 use crate::utils::get_track_guid;
 use crate::OscRoute;
@@ -13,7 +14,7 @@ use crate::OscRoute;
 #[derive(Debug)]
 pub enum PollError {
     Reaper(reaper_medium::ReaperFunctionError),
-    Send(crossbeam_channel::SendError<OscPacket>),
+    Send(crossbeam_channel::SendError<Feedback>),
 }
 
 pub struct PollManager {
@@ -37,9 +38,15 @@ impl PollManager {
         self.sources.push(source);
     }
 
-    /// Called in the main run loop
-    pub fn poll_all(&mut self, osc_sender: &Sender<OscPacket>) {
+    /// Called in the main run loop. Meter sources are skipped while their lane is
+    /// backed up, so a slow network delays meters instead of REAPER's main thread.
+    pub fn poll_all(&mut self, control_sender: &Sender<Feedback>, meter_sender: &Sender<Feedback>) {
         for source in self.sources.iter_mut() {
+            let osc_sender = match source.lane() {
+                Lane::Control => control_sender,
+                Lane::Meters if meter_sender.is_full() => continue,
+                Lane::Meters => meter_sender,
+            };
             source
                 .poll_and_send(osc_sender)
                 .map_err(|e| {
@@ -54,7 +61,12 @@ impl PollManager {
 pub trait PollSource {
     /// Called periodically to check for changes and send feedback
     /// Returns true if feedback was sent
-    fn poll_and_send(&mut self, osc_sender: &Sender<OscPacket>) -> Result<(), PollError>;
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError>;
+
+    /// The lane the source's feedback is sent in
+    fn lane(&self) -> Lane {
+        Lane::Control
+    }
 }
 
 struct TrackColorPollSource {
@@ -72,7 +84,7 @@ impl TrackColorPollSource {
 }
 
 impl PollSource for TrackColorPollSource {
-    fn poll_and_send(&mut self, osc_sender: &Sender<OscPacket>) -> Result<(), PollError> {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
         for i in 0..self.reaper.count_tracks(CurrentProject) {
             let track = self.reaper.get_track(CurrentProject, i).unwrap();
             let guid = get_track_guid(&self.reaper, track);
@@ -82,29 +94,31 @@ impl PollSource for TrackColorPollSource {
                 if *prev_color != color {
                     self.prev_colors.insert(guid.clone(), color);
                     osc_sender
-                        .send(OscPacket::Message(
-                            osc_routes::TrackColorRoute::build_message(
+                        .send(
+                            OscPacket::Message(osc_routes::TrackColorRoute::build_message(
                                 osc_routes::TrackColorArgs {
                                     track,
                                     color: color.to_raw(),
                                 },
                                 &self.reaper,
-                            ),
-                        ))
+                            ))
+                            .into(),
+                        )
                         .map_err(PollError::Send)?;
                 }
             } else {
                 self.prev_colors.insert(guid.clone(), color);
                 osc_sender
-                    .send(OscPacket::Message(
-                        osc_routes::TrackColorRoute::build_message(
+                    .send(
+                        OscPacket::Message(osc_routes::TrackColorRoute::build_message(
                             osc_routes::TrackColorArgs {
                                 track,
                                 color: color.to_raw(),
                             },
                             &self.reaper,
-                        ),
-                    ))
+                        ))
+                        .into(),
+                    )
                     .map_err(PollError::Send)?;
             }
         }
@@ -0,0 +1,102 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::Reaper;
+use rosc::OscPacket;
+use serde::Deserialize;
+
+use crate::feedback::{Feedback, Lane};
+use crate::markers;
+use crate::osc_routes::{
+    TransportBeatsParams, TransportBeatsRoute, TransportSamplesParams, TransportSamplesRoute,
+    TransportTimeParams, TransportTimeRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::utils::immediate_bundle;
+use crate::OscRoute;
+
+/// The `[position]` section of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PositionConfig {
+    /// How many times per second the play position is sent while playing; 0 disables it
+    pub rate: f64,
+}
+
+impl Default for PositionConfig {
+    fn default() -> Self {
+        Self { rate: 10.0 }
+    }
+}
+
+static CONFIG: OnceLock<PositionConfig> = OnceLock::new();
+
+pub fn init(config: PositionConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// Sends `/transport/time`, `/transport/beats` and `/transport/samples` together while
+/// playing, and once more when playback stops so displays show where it stopped
+pub struct PositionPollSource {
+    reaper: Reaper,
+    interval: Option<Duration>,
+    last_sent: Instant,
+    was_playing: bool,
+}
+
+impl PositionPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        let rate = CONFIG.get().cloned().unwrap_or_default().rate;
+        Self {
+            reaper,
+            interval: (rate > 0.0).then(|| Duration::from_secs_f64(1.0 / rate)),
+            last_sent: Instant::now(),
+            was_playing: false,
+        }
+    }
+}
+
+impl PollSource for PositionPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let Some(interval) = self.interval else {
+            return Ok(());
+        };
+        let playing = self.reaper.get_play_state_ex(CurrentProject).is_playing;
+        let stopped = std::mem::replace(&mut self.was_playing, playing) && !playing;
+        if !stopped && (!playing || self.last_sent.elapsed() < interval) {
+            return Ok(());
+        }
+        self.last_sent = Instant::now();
+        let reaper = &self.reaper;
+        let position = markers::playhead_position(reaper);
+        let content = vec![
+            OscPacket::Message(TransportTimeRoute::build_message(
+                (TransportTimeParams {}, position),
+                reaper,
+            )),
+            OscPacket::Message(TransportBeatsRoute::build_message(
+                (
+                    TransportBeatsParams {},
+                    markers::format_beats(reaper, position),
+                ),
+                reaper,
+            )),
+            OscPacket::Message(TransportSamplesRoute::build_message(
+                (
+                    TransportSamplesParams {},
+                    markers::position_samples(reaper, position),
+                ),
+                reaper,
+            )),
+        ];
+        osc_sender
+            .send(immediate_bundle(content).into())
+            .map_err(PollError::Send)
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::Reaper;
+use rosc::OscPacket;
+
+use crate::config::Config;
+use crate::feedback::Feedback;
+use crate::osc_routes::{
+    OverviewParams, OverviewRoute, ProjectChangedArgs, ProjectChangedRoute, ProjectDirtyParams,
+    ProjectDirtyRoute, SessionParams, SessionRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::registry;
+use crate::sync;
+use crate::utils::guid_to_string;
+use crate::OscRoute;
+
+const PROJECT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const DIRTY_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+// Identifies the project session clients are currently looking at. Each open project
+// keeps its id until it is closed or reopened.
+static SESSION_ID: Mutex<String> = Mutex::new(String::new());
+
+pub fn session_id() -> String {
+    SESSION_ID.lock().unwrap().clone()
+}
+
+// A fresh UUID, so ids of different REAPER instances and runs never collide
+fn new_session_id(reaper: &Reaper) -> String {
+    let mut guid = reaper_low::raw::GUID {
+        Data1: 0,
+        Data2: 0,
+        Data3: 0,
+        Data4: [0; 8],
+    };
+    unsafe {
+        reaper.low().genGuid(&mut guid);
+    }
+    guid_to_string(guid)
+}
+
+/// The active project's name, as shown in REAPER's title bar
+pub fn project_name(reaper: &Reaper) -> String {
+    let mut buf = [0 as std::os::raw::c_char; 512];
+    unsafe {
+        reaper
+            .low()
+            .GetProjectName(std::ptr::null_mut(), buf.as_mut_ptr(), buf.len() as i32);
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+/// The file of the active project, or None while it was never saved
+pub fn project_file(reaper: &Reaper) -> Option<PathBuf> {
+    let mut buf = [0 as std::os::raw::c_char; 1024];
+    let path = unsafe {
+        reaper
+            .low()
+            .EnumProjects(-1, buf.as_mut_ptr(), buf.len() as i32);
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    };
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Whether the active project has changes that weren't saved
+pub fn is_dirty(reaper: &Reaper) -> bool {
+    unsafe { reaper.low().IsProjectDirty(std::ptr::null_mut()) != 0 }
+}
+
+/// Undoes the last change of the active project; false if there was nothing to undo
+pub fn undo(reaper: &Reaper) -> bool {
+    unsafe { reaper.low().Undo_DoUndo2(std::ptr::null_mut()) != 0 }
+}
+
+/// Redoes the last undone change of the active project; false if there was nothing
+/// to redo
+pub fn redo(reaper: &Reaper) -> bool {
+    unsafe { reaper.low().Undo_DoRedo2(std::ptr::null_mut()) != 0 }
+}
+
+/// Saves the active project as REAPER's Save command does. Returns false for a project
+/// that was never saved, which would open a save dialog no remote user can dismiss.
+pub fn save(reaper: &Reaper) -> bool {
+    if project_file(reaper).is_none() {
+        return false;
+    }
+    unsafe { reaper.low().Main_SaveProject(std::ptr::null_mut(), false) };
+    true
+}
+
+// The projects open in REAPER's tabs
+fn open_projects(reaper: &Reaper) -> Vec<usize> {
+    (0..)
+        .map(|i| unsafe { reaper.low().EnumProjects(i, std::ptr::null_mut(), 0) })
+        .take_while(|proj| !proj.is_null())
+        .map(|proj| proj as usize)
+        .collect()
+}
+
+#[derive(PartialEq)]
+struct ActiveProject {
+    ptr: usize,
+    change_count: i32,
+}
+
+impl ActiveProject {
+    fn current(reaper: &Reaper) -> Self {
+        unsafe {
+            let proj = reaper.low().EnumProjects(-1, std::ptr::null_mut(), 0);
+            ActiveProject {
+                ptr: proj as usize,
+                change_count: reaper.low().GetProjectStateChangeCount(proj),
+            }
+        }
+    }
+
+    // A reopened project keeps its tab but starts counting changes again. The path
+    // isn't compared, as Save As and saving an untitled project change it without
+    // switching projects.
+    fn is_other_session(&self, prev: &ActiveProject) -> bool {
+        self.ptr != prev.ptr || self.change_count < prev.change_count
+    }
+}
+
+/// Sends `/project/changed` when the active project changes or is reopened, followed
+/// by the overview and the full state of the new project so clients can rebuild
+/// their state.
+pub struct ProjectPollSource {
+    reaper: Reaper,
+    prev: Option<ActiveProject>,
+    // Path and session id of each open project, so switching back to a tab brings its
+    // session back instead of making clients drop what they know about the project
+    sessions: HashMap<usize, (Option<PathBuf>, String)>,
+    last_check: Instant,
+}
+
+impl ProjectPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            prev: None,
+            sessions: HashMap::new(),
+            last_check: Instant::now(),
+        }
+    }
+}
+
+impl PollSource for ProjectPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.prev.is_some() && self.last_check.elapsed() < PROJECT_CHECK_INTERVAL {
+            return Ok(());
+        }
+        self.last_check = Instant::now();
+        let current = ActiveProject::current(&self.reaper);
+        let ptr = current.ptr;
+        let path = project_file(&self.reaper);
+        let changed = match &self.prev {
+            Some(prev) => current.is_other_session(prev),
+            None => true,
+        };
+        // A project reopened in its own tab is a new session
+        let reopened = self.prev.as_ref().is_some_and(|prev| prev.ptr == ptr);
+        let is_startup = self.prev.is_none();
+        self.prev = Some(current);
+        if !changed {
+            // Saving can change the path without starting a new session
+            if let Some(session) = self.sessions.get_mut(&ptr) {
+                session.0 = path;
+            }
+            return Ok(());
+        }
+        // A closed project's pointer can be reused by one opened later, which the path
+        // check below mostly catches
+        let open = open_projects(&self.reaper);
+        self.sessions.retain(|proj, _| open.contains(proj));
+        let id = self
+            .sessions
+            .get(&ptr)
+            .filter(|(known_path, _)| !reopened && *known_path == path)
+            .map(|(_, id)| id.clone())
+            .unwrap_or_else(|| new_session_id(&self.reaper));
+        self.sessions.insert(ptr, (path, id.clone()));
+        *SESSION_ID.lock().unwrap() = id;
+        registry::invalidate();
+        Config::apply_project(&self.reaper);
+        if is_startup {
+            return Ok(());
+        }
+        let args = ProjectChangedArgs {
+            name: project_name(&self.reaper),
+            session_id: session_id(),
+        };
+        osc_sender
+            .send(OscPacket::Message(ProjectChangedRoute::build_message(args, &self.reaper)).into())
+            .map_err(PollError::Send)?;
+        osc_sender
+            .send(
+                OscPacket::Message(SessionRoute::build_message(
+                    (SessionParams {}, session_id()),
+                    &self.reaper,
+                ))
+                .into(),
+            )
+            .map_err(PollError::Send)?;
+        if let Ok(overview) = OverviewRoute::collect_send_params(&OverviewParams, &self.reaper) {
+            osc_sender
+                .send(OverviewRoute::build_packet(overview, &self.reaper).into())
+                .map_err(PollError::Send)?;
+        }
+        // Every surface and client redraws from the new project's full state
+        sync::request_all();
+        Ok(())
+    }
+}
+
+/// Sends `/project/dirty` when the active project gains unsaved changes or is saved,
+/// so surfaces can light a save button. REAPER has no control surface callback for it.
+pub struct ProjectDirtyPollSource {
+    reaper: Reaper,
+    last_check: Instant,
+    // None until the first check, which records the state without sending it
+    dirty: Option<bool>,
+}
+
+impl ProjectDirtyPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_check: Instant::now(),
+            dirty: None,
+        }
+    }
+}
+
+impl PollSource for ProjectDirtyPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_check.elapsed() < DIRTY_CHECK_INTERVAL {
+            return Ok(());
+        }
+        self.last_check = Instant::now();
+        let dirty = is_dirty(&self.reaper);
+        if self.dirty.replace(dirty).is_none_or(|prev| prev == dirty) {
+            return Ok(());
+        }
+        osc_sender
+            .send(
+                OscPacket::Message(ProjectDirtyRoute::build_message(
+                    (ProjectDirtyParams {}, dirty),
+                    &self.reaper,
+                ))
+                .into(),
+            )
+            .map_err(PollError::Send)
+    }
+}
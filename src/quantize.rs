@@ -0,0 +1,95 @@
+use std::sync::Mutex;
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::Reaper;
+
+use crate::feedback::Feedback;
+use crate::markers;
+use crate::polling::{PollError, PollSource};
+
+// Positions this close to a bar line count as on it
+const BAR_EPSILON: f64 = 1e-6;
+
+// Bar line playback stops at, set by a quantized stop; None when none is pending
+static PENDING_STOP: Mutex<Option<f64>> = Mutex::new(None);
+
+/// The first bar line at or after `position` seconds, following the tempo map
+fn bar_at_or_after(reaper: &Reaper, position: f64) -> f64 {
+    let mut measure = 0;
+    let beat = unsafe {
+        reaper.low().TimeMap2_timeToBeats(
+            std::ptr::null_mut(),
+            position,
+            &mut measure,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if beat < BAR_EPSILON {
+        return position;
+    }
+    let next = measure + 1;
+    unsafe {
+        reaper
+            .low()
+            .TimeMap2_beatsToTime(std::ptr::null_mut(), 0.0, &next)
+    }
+}
+
+/// Starts playback from the first bar line at or after the edit cursor. Ignored while
+/// playing.
+pub fn play(reaper: &Reaper) {
+    if reaper.get_play_state_ex(CurrentProject).is_playing {
+        return;
+    }
+    let cursor = reaper.get_cursor_position_ex(CurrentProject).get();
+    markers::locate(reaper, bar_at_or_after(reaper, cursor));
+    reaper.csurf_on_play();
+}
+
+/// Stops playback when it reaches the next bar line. Ignored while stopped.
+pub fn stop(reaper: &Reaper) {
+    if !reaper.get_play_state_ex(CurrentProject).is_playing {
+        return;
+    }
+    let position = reaper.get_play_position_ex(CurrentProject).get();
+    *PENDING_STOP.lock().unwrap() = Some(bar_at_or_after(reaper, position + BAR_EPSILON));
+}
+
+/// Carries out quantized stops. Playback stops on the first poll at or past the bar
+/// line, so it runs over by up to one poll interval. Playback jumping backwards, e.g.
+/// wrapping around a loop ending on the bar line, counts as reaching it.
+pub struct QuantizedTransportPollSource {
+    reaper: Reaper,
+    prev: Option<f64>,
+}
+
+impl QuantizedTransportPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self { reaper, prev: None }
+    }
+}
+
+impl PollSource for QuantizedTransportPollSource {
+    fn poll_and_send(&mut self, _: &Sender<Feedback>) -> Result<(), PollError> {
+        let mut pending = PENDING_STOP.lock().unwrap();
+        if !self.reaper.get_play_state_ex(CurrentProject).is_playing {
+            *pending = None;
+            self.prev = None;
+            return Ok(());
+        }
+        let position = self.reaper.get_play_position_ex(CurrentProject).get();
+        let wrapped = self.prev.is_some_and(|prev| position < prev);
+        self.prev = Some(position);
+        let Some(bar) = *pending else {
+            return Ok(());
+        };
+        if position >= bar || wrapped {
+            *pending = None;
+            self.reaper.csurf_on_stop();
+        }
+        Ok(())
+    }
+}
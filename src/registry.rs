@@ -0,0 +1,49 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper};
+
+use crate::utils::get_track_guid;
+use crate::RouteError;
+
+thread_local! {
+    // Canonical GUID of every track in the current project, master included. Only
+    // touched from the main thread; rebuilt lazily after the track list changes.
+    static TRACKS: RefCell<Option<HashMap<String, MediaTrack>>> = const { RefCell::new(None) };
+}
+
+/// Drops the cached tracks. Called when tracks are added, removed or reordered and
+/// when another project becomes active.
+pub fn invalidate() {
+    TRACKS.with(|tracks| *tracks.borrow_mut() = None);
+}
+
+fn build(reaper: &Reaper) -> HashMap<String, MediaTrack> {
+    std::iter::once(reaper.get_master_track(CurrentProject))
+        .chain(
+            (0..reaper.count_tracks(CurrentProject))
+                .filter_map(|i| reaper.get_track(CurrentProject, i)),
+        )
+        .map(|track| (get_track_guid(reaper, track), track))
+        .collect()
+}
+
+/// Looks a track up in the cached GUID map. When the lookup fails or finds a track
+/// that no longer exists, the map is rebuilt and the lookup tried once more, so a
+/// stale cache never hides a track.
+pub fn find(
+    reaper: &Reaper,
+    lookup: impl Fn(&HashMap<String, MediaTrack>) -> Result<MediaTrack, RouteError>,
+) -> Result<MediaTrack, RouteError> {
+    let cached = TRACKS.with(|tracks| tracks.borrow().as_ref().map(&lookup));
+    if let Some(Ok(track)) = cached {
+        if reaper.validate_ptr_2(CurrentProject, track) {
+            return Ok(track);
+        }
+    }
+    let tracks = build(reaper);
+    let result = lookup(&tracks);
+    TRACKS.with(|cache| *cache.borrow_mut() = Some(tracks));
+    result
+}
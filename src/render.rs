@@ -0,0 +1,176 @@
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{CommandId, Reaper};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{
+    RenderProgressArgs, RenderProgressRoute, RenderStatusArgs, RenderStatusRoute,
+};
+use crate::polling::{PollError, PollSource};
+use crate::OscRoute;
+
+// "File: Render project, using the most recent render settings"
+const RENDER_PROJECT_COMMAND: u32 = 41824;
+// "File: Render all queued renders"
+const RENDER_QUEUE_COMMAND: u32 = 41207;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderKind {
+    Project,
+    Queue,
+}
+
+impl RenderKind {
+    pub fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "project" => Some(RenderKind::Project),
+            "queue" => Some(RenderKind::Queue),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenderKind::Project => "project",
+            RenderKind::Queue => "queue",
+        }
+    }
+
+    fn command_id(&self) -> CommandId {
+        match self {
+            RenderKind::Project => CommandId::new(RENDER_PROJECT_COMMAND),
+            RenderKind::Queue => CommandId::new(RENDER_QUEUE_COMMAND),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderState {
+    Started,
+    Complete,
+}
+
+impl RenderState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenderState::Started => "started",
+            RenderState::Complete => "complete",
+        }
+    }
+}
+
+static PENDING: Mutex<Option<RenderKind>> = Mutex::new(None);
+static LAST_STATUS: Mutex<Option<RenderStatusArgs>> = Mutex::new(None);
+
+/// A render under way, reported on from the timer ticks REAPER keeps firing while it
+/// renders modally
+struct Rendering {
+    kind: RenderKind,
+    started: Instant,
+    last_report: Instant,
+    reaper: Reaper,
+    osc_sender: Sender<Feedback>,
+}
+
+impl Rendering {
+    fn progress(&self) -> RenderProgressArgs {
+        RenderProgressArgs {
+            kind: self.kind,
+            elapsed: self.started.elapsed().as_secs_f64(),
+        }
+    }
+}
+
+thread_local! {
+    static RENDERING: RefCell<Option<Rendering>> = const { RefCell::new(None) };
+}
+
+/// Schedules a render for the next poll cycle. Rendering blocks REAPER's main thread,
+/// so deferring it lets the receive path return and the "started" feedback go out first.
+pub fn request(kind: RenderKind) {
+    *PENDING.lock().unwrap() = Some(kind);
+}
+
+pub fn last_status() -> Option<RenderStatusArgs> {
+    *LAST_STATUS.lock().unwrap()
+}
+
+/// How long the running render has taken so far, or None while nothing renders
+pub fn progress() -> Option<RenderProgressArgs> {
+    RENDERING.with(|r| r.borrow().as_ref().map(Rendering::progress))
+}
+
+/// Sends `/render/progress` every second while a render runs. Called from the timer
+/// ticks fired inside the render, while the render's own run cycle is still busy.
+/// REAPER doesn't tell how far a render has got, so this only counts the time taken.
+pub fn report_progress() {
+    RENDERING.with(|r| {
+        let mut r = r.borrow_mut();
+        let Some(rendering) = r.as_mut() else {
+            return;
+        };
+        if rendering.last_report.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        rendering.last_report = Instant::now();
+        let msg = RenderProgressRoute::build_message(rendering.progress(), &rendering.reaper);
+        // The run cycle that would send it only ends once the render is done
+        let _ = rendering.osc_sender.send(OscPacket::Message(msg).into());
+        let _ = rendering.osc_sender.send(Feedback::Flush);
+    });
+}
+
+pub struct RenderPollSource {
+    reaper: Reaper,
+}
+
+impl RenderPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self { reaper }
+    }
+
+    fn report(
+        &self,
+        osc_sender: &Sender<Feedback>,
+        kind: RenderKind,
+        state: RenderState,
+    ) -> Result<(), PollError> {
+        let status = RenderStatusArgs { kind, state };
+        *LAST_STATUS.lock().unwrap() = Some(status);
+        osc_sender
+            .send(OscPacket::Message(RenderStatusRoute::build_message(status, &self.reaper)).into())
+            .map_err(PollError::Send)
+    }
+}
+
+impl PollSource for RenderPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let Some(kind) = PENDING.lock().unwrap().take() else {
+            return Ok(());
+        };
+        self.report(osc_sender, kind, RenderState::Started)?;
+        // Feedback is sent at the end of a run cycle, which only comes after the render
+        osc_sender.send(Feedback::Flush).map_err(PollError::Send)?;
+        RENDERING.with(|r| {
+            *r.borrow_mut() = Some(Rendering {
+                kind,
+                started: Instant::now(),
+                last_report: Instant::now(),
+                reaper: self.reaper.clone(),
+                osc_sender: osc_sender.clone(),
+            })
+        });
+        // REAPER renders modally, so the action only returns once rendering has finished
+        self.reaper
+            .main_on_command_ex(kind.command_id(), 0, CurrentProject);
+        RENDERING.with(|r| *r.borrow_mut() = None);
+        self.report(osc_sender, kind, RenderState::Complete)
+    }
+}
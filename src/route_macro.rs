@@ -0,0 +1,212 @@
+use rosc::OscType;
+
+/// A value carried as the single argument of a route defined with `define_osc_route!`
+pub trait OscValue: Sized {
+    /// Type name used in error messages, e.g. "a bool"
+    const DESCRIPTION: &'static str;
+
+    fn to_osc(self) -> OscType;
+    fn from_osc(arg: &OscType) -> Option<Self>;
+}
+
+impl OscValue for bool {
+    const DESCRIPTION: &'static str = "a bool";
+
+    fn to_osc(self) -> OscType {
+        OscType::Bool(self)
+    }
+
+    fn from_osc(arg: &OscType) -> Option<Self> {
+        match arg {
+            OscType::Bool(b) => Some(*b),
+            OscType::Int(i) => Some(*i != 0),
+            OscType::Float(f) => Some(*f != 0.0),
+            _ => None,
+        }
+    }
+}
+
+impl OscValue for i32 {
+    const DESCRIPTION: &'static str = "an int";
+
+    fn to_osc(self) -> OscType {
+        OscType::Int(self)
+    }
+
+    fn from_osc(arg: &OscType) -> Option<Self> {
+        arg.clone().int()
+    }
+}
+
+impl OscValue for i64 {
+    const DESCRIPTION: &'static str = "a long";
+
+    fn to_osc(self) -> OscType {
+        OscType::Long(self)
+    }
+
+    fn from_osc(arg: &OscType) -> Option<Self> {
+        match arg {
+            OscType::Long(l) => Some(*l),
+            OscType::Int(i) => Some(*i as i64),
+            _ => None,
+        }
+    }
+}
+
+impl OscValue for f64 {
+    const DESCRIPTION: &'static str = "a float";
+
+    fn to_osc(self) -> OscType {
+        OscType::Float(self as f32)
+    }
+
+    fn from_osc(arg: &OscType) -> Option<Self> {
+        match arg {
+            OscType::Float(f) => Some(*f as f64),
+            OscType::Double(d) => Some(*d),
+            _ => None,
+        }
+    }
+}
+
+impl OscValue for String {
+    const DESCRIPTION: &'static str = "a string";
+
+    fn to_osc(self) -> OscType {
+        OscType::String(self)
+    }
+
+    fn from_osc(arg: &OscType) -> Option<Self> {
+        arg.clone().string()
+    }
+}
+
+/// Defines a route carrying a single value, generating the route and params structs
+/// and the `OscRoute` impl.
+///
+/// The address is a slice pattern where string literals match fixed segments and
+/// identifiers capture variable ones into the params field of the same name, parsed
+/// with `FromStr`. `get` reads the value for queries; `set` is optional and routes
+/// without it are read-only, ignoring incoming values. An optional `undoable: false;`
+/// after the value keeps the route's changes out of `/arpad/undo-last`. Doc comments
+/// are passed through, so `@osc-doc` blocks go on top of the invocation as they would
+/// on a hand-written route.
+///
+/// ```ignore
+/// define_osc_route! {
+///     /// @osc-doc
+///     /// OSC Address: /monitorfx/{fx_index}/bypass
+///     /// ...
+///     pub struct MonitorFxBypassRoute;
+///     address: ["monitorfx", fx_index, "bypass"];
+///     params: MonitorFxBypassParams { fx_index: u32 };
+///     value: bool;
+///     get(reaper, params) { ... }
+///     set(reaper, params, bypass) { ... }
+/// }
+/// ```
+macro_rules! define_osc_route {
+    (@segment $params:ident, $literal:literal) => {
+        $literal.to_string()
+    };
+    (@segment $params:ident, $field:ident) => {
+        $params.$field.to_string()
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $route:ident;
+        address: [$($segment:tt),+];
+        params: $params:ident { $($field:ident: $field_ty:ty),* $(,)? };
+        value: $value:ty;
+        $(undoable: $undoable:literal;)?
+        get($get_reaper:ident, $get_params:ident) $get:block
+        $(set($set_reaper:ident, $set_params:ident, $set_value:ident) $set:block)?
+    ) => {
+        $(#[$attr])*
+        $vis struct $route;
+
+        #[derive(Debug, Clone)]
+        $vis struct $params {
+            $(pub $field: $field_ty),*
+        }
+
+        impl $route {
+            #[allow(unused_variables)]
+            fn get(
+                $get_reaper: &reaper_medium::Reaper,
+                $get_params: &$params,
+            ) -> Result<$value, $crate::RouteError> $get
+
+            $(
+                #[allow(unused_variables)]
+                fn set(
+                    $set_reaper: &reaper_medium::Reaper,
+                    $set_params: &$params,
+                    $set_value: $value,
+                ) -> Result<(), $crate::ReceiverError> $set
+            )?
+        }
+
+        impl $crate::OscRoute for $route {
+            type SendParams = ($params, $value);
+            type ReceiveParams = $params;
+
+            $(const UNDOABLE: bool = $undoable;)?
+            // Routes without a setter only report their value
+            const READONLY: bool = <[&str]>::is_empty(&[$(stringify!($set_value))?]);
+
+            fn matcher(segments: &[&str]) -> Option<Self::ReceiveParams> {
+                match segments {
+                    [$($segment),+] => Some($params {
+                        $($field: $field.parse().ok()?),*
+                    }),
+                    _ => None,
+                }
+            }
+
+            #[allow(unused_variables, unreachable_code)]
+            fn receive(
+                params: Self::ReceiveParams,
+                msg: &rosc::OscMessage,
+                reaper: &reaper_medium::Reaper,
+            ) -> Result<(), $crate::ReceiverError> {
+                $(
+                    let _ = stringify!($set_value);
+                    let value = msg
+                        .args
+                        .first()
+                        .and_then(<$value as $crate::route_macro::OscValue>::from_osc)
+                        .ok_or_else(|| {
+                            $crate::ReceiverError::BadValue(format!(
+                                "Invalid value, expected {}",
+                                <$value as $crate::route_macro::OscValue>::DESCRIPTION
+                            ))
+                        })?;
+                    return Self::set(reaper, &params, value);
+                )?
+                Ok(())
+            }
+
+            #[allow(unused_variables)]
+            fn build_message(
+                (params, value): Self::SendParams,
+                _: &reaper_medium::Reaper,
+            ) -> rosc::OscMessage {
+                let segments: Vec<String> =
+                    vec![$(define_osc_route!(@segment params, $segment)),+];
+                rosc::OscMessage {
+                    addr: format!("/{}", segments.join("/")),
+                    args: vec![$crate::route_macro::OscValue::to_osc(value)],
+                }
+            }
+
+            fn collect_send_params(
+                params: &Self::ReceiveParams,
+                reaper: &reaper_medium::Reaper,
+            ) -> Result<Self::SendParams, $crate::RouteError> {
+                Ok((params.clone(), Self::get(reaper, params)?))
+            }
+        }
+    };
+}
@@ -0,0 +1,64 @@
+use std::marker::PhantomData;
+
+use crossbeam_channel::Sender;
+use reaper_medium::Reaper;
+use rosc::OscMessage;
+
+use crate::feedback::Feedback;
+use crate::{dispatch_route, OscRoute};
+
+// A route with its types erased so routes of all kinds can share one list
+trait ErasedRoute {
+    fn dispatch(
+        &self,
+        segments: &[&str],
+        msg: &OscMessage,
+        reaper: &Reaper,
+        osc_sender: &Sender<Feedback>,
+    );
+}
+
+struct RouteEntry<T>(PhantomData<fn() -> T>);
+
+impl<T: OscRoute> ErasedRoute for RouteEntry<T> {
+    fn dispatch(
+        &self,
+        segments: &[&str],
+        msg: &OscMessage,
+        reaper: &Reaper,
+        osc_sender: &Sender<Feedback>,
+    ) {
+        dispatch_route::<T>(segments, msg, reaper, osc_sender);
+    }
+}
+
+/// Every route incoming messages are dispatched to, registered once at startup
+#[derive(Default)]
+pub struct RouteRegistry {
+    routes: Vec<Box<dyn ErasedRoute>>,
+}
+
+impl RouteRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<T: OscRoute + 'static>(&mut self) -> &mut Self {
+        self.routes.push(Box::new(RouteEntry::<T>(PhantomData)));
+        self
+    }
+
+    /// Offers the message to every registered route in registration order. Each route
+    /// whose matcher accepts the address either answers the query or applies the value.
+    pub fn dispatch(
+        &self,
+        segments: &[&str],
+        msg: &OscMessage,
+        reaper: &Reaper,
+        osc_sender: &Sender<Feedback>,
+    ) {
+        for route in &self.routes {
+            route.dispatch(segments, msg, reaper, osc_sender);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{Reaper, TrackSendCategory};
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::osc_routes::{SendSwitch, TrackSendSwitchArgs, TrackSendSwitchRoute};
+use crate::polling::{PollError, PollSource};
+use crate::surfaces;
+use crate::utils::get_track_guid;
+use crate::OscRoute;
+
+const SEND_SWITCH_INTERVAL: Duration = Duration::from_millis(200);
+
+const DEFAULT_SEND_PAGE_SIZE: u32 = 8;
+
+/// Number of sends in a page for the surface currently being served
+pub fn page_size() -> u32 {
+    surfaces::profile_for_namespace(&surfaces::current_namespace())
+        .and_then(|p| p.send_page_size)
+        .filter(|&size| size > 0)
+        .unwrap_or(DEFAULT_SEND_PAGE_SIZE)
+}
+
+/// Sends `/track/{guid}/send/{index}/{mute|phase|mono}` when a send switch changes.
+/// REAPER notifies control surfaces of send volume and pan but not of these, so they
+/// are polled.
+pub struct SendSwitchPollSource {
+    reaper: Reaper,
+    last_poll: Instant,
+    // Switch states last sent, by track GUID and send index, in `SendSwitch::ALL` order
+    last_sent: HashMap<(String, u32), [bool; 3]>,
+}
+
+impl SendSwitchPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_poll: Instant::now(),
+            last_sent: HashMap::new(),
+        }
+    }
+}
+
+impl PollSource for SendSwitchPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_poll.elapsed() < SEND_SWITCH_INTERVAL {
+            return Ok(());
+        }
+        self.last_poll = Instant::now();
+        let reaper = &self.reaper;
+        let mut seen = HashMap::new();
+        for track in (0..reaper.count_tracks(CurrentProject))
+            .filter_map(|i| reaper.get_track(CurrentProject, i))
+        {
+            let guid = get_track_guid(reaper, track);
+            for send_index in
+                0..unsafe { reaper.get_track_num_sends(track, TrackSendCategory::Send) }
+            {
+                let states = SendSwitch::ALL.map(|switch| switch.get(reaper, track, send_index));
+                let key = (guid.clone(), send_index);
+                let previous = self.last_sent.get(&key).copied();
+                for (i, switch) in SendSwitch::ALL.into_iter().enumerate() {
+                    // Sends start out with every switch off, so only report those turned on
+                    if previous.map_or(!states[i], |p| p[i] == states[i]) {
+                        continue;
+                    }
+                    osc_sender
+                        .send(
+                            OscPacket::Message(TrackSendSwitchRoute::build_message(
+                                TrackSendSwitchArgs {
+                                    track,
+                                    send_index,
+                                    switch,
+                                    on: states[i],
+                                },
+                                reaper,
+                            ))
+                            .into(),
+                        )
+                        .map_err(PollError::Send)?;
+                }
+                seen.insert(key, states);
+            }
+        }
+        // Sends that disappeared are forgotten, so a send taking their place is reported
+        self.last_sent = seen;
+        Ok(())
+    }
+}
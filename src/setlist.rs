@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::Reaper;
+use rosc::OscPacket;
+
+use crate::feedback::Feedback;
+use crate::markers::{playhead_position, project_markers};
+use crate::osc_routes::{CueCurrentArgs, CueCurrentRoute};
+use crate::polling::{PollError, PollSource};
+use crate::OscRoute;
+
+const CURRENT_CUE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// One entry of the cue list: a region, or the span from a marker to the next one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub name: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// The cue list: the project's regions if it has any, otherwise its markers, in
+/// timeline order
+pub fn cues(reaper: &Reaper) -> Vec<Cue> {
+    let markers = project_markers(reaper);
+    let mut cues: Vec<Cue> = if markers.iter().any(|m| m.is_region) {
+        markers
+            .into_iter()
+            .filter(|m| m.is_region)
+            .map(|m| Cue {
+                name: m.name,
+                start: m.position,
+                end: m.region_end,
+            })
+            .collect()
+    } else {
+        let project_end = unsafe { reaper.low().GetProjectLength(std::ptr::null_mut()) };
+        let starts: Vec<f64> = markers.iter().map(|m| m.position).collect();
+        markers
+            .into_iter()
+            .enumerate()
+            .map(|(i, m)| Cue {
+                name: m.name,
+                start: m.position,
+                end: starts.get(i + 1).copied().unwrap_or(project_end),
+            })
+            .collect()
+    };
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+
+/// Seconds from `position` to the nearest region end after it
+pub fn time_to_region_end(reaper: &Reaper, position: f64) -> Option<f64> {
+    project_markers(reaper)
+        .into_iter()
+        .filter(|m| m.is_region && m.region_end > position)
+        .map(|m| m.region_end - position)
+        .min_by(f64::total_cmp)
+}
+
+/// Index of the cue containing `position`: the last one starting at or before it
+pub fn cue_index_at(cues: &[Cue], position: f64) -> Option<usize> {
+    cues.iter().rposition(|cue| cue.start <= position)
+}
+
+pub fn current_cue(reaper: &Reaper) -> Option<(usize, Cue)> {
+    let cues = cues(reaper);
+    let index = cue_index_at(&cues, playhead_position(reaper))?;
+    Some((index, cues[index].clone()))
+}
+
+/// Moves the edit cursor (and the play position, while playing) to the start of a cue
+pub fn goto(reaper: &Reaper, index: usize) -> Option<()> {
+    let cue = cues(reaper).into_iter().nth(index)?;
+    unsafe {
+        reaper.low().SetEditCurPos(cue.start, true, true);
+    }
+    Some(())
+}
+
+/// Moves by `delta` cues from the current one. Before the first cue, "next" goes to
+/// the first cue.
+pub fn step(reaper: &Reaper, delta: isize) -> Option<()> {
+    let cues = cues(reaper);
+    let target = match cue_index_at(&cues, playhead_position(reaper)) {
+        Some(index) => index.checked_add_signed(delta)?,
+        None if delta > 0 => 0,
+        None => return None,
+    };
+    goto(reaper, target)
+}
+
+/// Sends the current cue whenever it changes, and its remaining time while playing
+pub struct CurrentCuePollSource {
+    reaper: Reaper,
+    last_sent: Instant,
+    prev: Option<CueCurrentArgs>,
+}
+
+impl CurrentCuePollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self {
+            reaper,
+            last_sent: Instant::now(),
+            prev: None,
+        }
+    }
+}
+
+impl PollSource for CurrentCuePollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        if self.last_sent.elapsed() < CURRENT_CUE_INTERVAL {
+            return Ok(());
+        }
+        self.last_sent = Instant::now();
+        let args = CueCurrentArgs::collect(&self.reaper);
+        if self.prev.as_ref() == Some(&args) {
+            return Ok(());
+        }
+        osc_sender
+            .send(
+                OscPacket::Message(CueCurrentRoute::build_message(args.clone(), &self.reaper))
+                    .into(),
+            )
+            .map_err(PollError::Send)?;
+        self.prev = Some(args);
+        Ok(())
+    }
+}
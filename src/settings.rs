@@ -0,0 +1,266 @@
+use std::ffi::{CStr, CString};
+use std::net::SocketAddrV4;
+use std::os::raw::c_char;
+use std::sync::OnceLock;
+
+use c_str_macro::c_str;
+use reaper_medium::Reaper;
+
+use crate::clients;
+use crate::clock::ClockConfig;
+use crate::config::Config;
+use crate::destinations::Destination;
+use crate::lockout;
+use crate::position::PositionConfig;
+use crate::stats;
+use crate::surfaces;
+use crate::transfer;
+use crate::vu::VuConfig;
+
+const SETTINGS_TITLE: &CStr = c_str!("arpad settings");
+const STATUS_TITLE: &CStr = c_str!("arpad status");
+// Captions of the settings dialog, in the order of `SettingsForm::fields`
+const SETTINGS_CAPTIONS: &CStr = c_str!(
+    "Listen address,Feedback destination,VU meters (on/off),Big clock (on/off),\
+     Play position (on/off),Fragment large packets (on/off),Lockout (on/off),extrawidth=120"
+);
+const SETTINGS_FIELD_COUNT: i32 = 7;
+const SETTINGS_BUF_LEN: usize = 1024;
+
+// Address the plugin actually listens on
+static LISTEN: OnceLock<SocketAddrV4> = OnceLock::new();
+
+pub fn init(listen: SocketAddrV4) {
+    let _ = LISTEN.set(listen);
+}
+
+/// Shows the listen address, surfaces, clients and message rates in a message box
+pub fn show_status(reaper: &Reaper) {
+    message_box(reaper, STATUS_TITLE, &status_text());
+}
+
+fn message_box(reaper: &Reaper, title: &CStr, text: &str) {
+    let text = CString::new(text).unwrap_or_default();
+    unsafe {
+        reaper
+            .low()
+            .ShowMessageBox(text.as_ptr(), title.as_ptr(), 0);
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn parse_on_off(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "on" | "yes" | "true" | "1" => Some(true),
+        "off" | "no" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn status_text() -> String {
+    let mut lines = Vec::new();
+    if let Some(listen) = LISTEN.get() {
+        lines.push(format!("Listening on {}", listen));
+    }
+    lines.push(String::new());
+    lines.push("Surfaces:".to_string());
+    for profile in surfaces::profiles() {
+        let destination = profile
+            .destination
+            .addr()
+            .map_or("unresolved".to_string(), |addr| addr.to_string());
+        lines.push(format!(
+            "  {}: {} ({})",
+            profile.name,
+            profile.destination.spec(),
+            destination
+        ));
+    }
+    let active = clients::active();
+    lines.push(String::new());
+    if active.is_empty() {
+        lines.push("No clients heard from recently".to_string());
+    } else {
+        lines.push("Clients:".to_string());
+        lines.extend(active.iter().map(|client| format!("  {}", client)));
+    }
+    let (incoming, outgoing) = stats::rates();
+    let (total_in, total_out) = stats::totals();
+    lines.push(String::new());
+    lines.push(format!(
+        "Messages: {:.1}/s received, {:.1}/s sent ({} received, {} sent in total)",
+        incoming, outgoing, total_in, total_out
+    ));
+    lines.push(format!("Lockout: {}", on_off(lockout::is_locked())));
+    lines.join("\n")
+}
+
+/// The values edited in the settings dialog
+struct SettingsForm {
+    listen: SocketAddrV4,
+    destination: String,
+    meters: bool,
+    clock: bool,
+    position: bool,
+    fragment_large_packets: bool,
+    lockout: bool,
+}
+
+impl SettingsForm {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            listen: config.osc.listen,
+            destination: config.osc.destination.spec().to_string(),
+            meters: config.vu.rate > 0.0,
+            clock: config.clock.rate > 0.0,
+            position: config.position.rate > 0.0,
+            fragment_large_packets: config.osc.fragment_large_packets,
+            lockout: lockout::is_locked(),
+        }
+    }
+
+    fn fields(&self) -> String {
+        [
+            self.listen.to_string(),
+            self.destination.clone(),
+            on_off(self.meters).to_string(),
+            on_off(self.clock).to_string(),
+            on_off(self.position).to_string(),
+            on_off(self.fragment_large_packets).to_string(),
+            on_off(self.lockout).to_string(),
+        ]
+        .join(",")
+    }
+
+    fn parse(fields: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = fields.split(',').map(str::trim).collect();
+        let [listen, destination, meters, clock, position, fragment, lockout] = fields[..] else {
+            return Err("Expected a value in every field".to_string());
+        };
+        let switch = |name: &str, value: &str| {
+            parse_on_off(value).ok_or_else(|| format!("{} must be on or off", name))
+        };
+        Ok(Self {
+            listen: listen
+                .parse()
+                .map_err(|_| format!("Invalid listen address {}, expected ip:port", listen))?,
+            destination: destination.parse::<Destination>()?.spec().to_string(),
+            meters: switch("VU meters", meters)?,
+            clock: switch("Big clock", clock)?,
+            position: switch("Play position", position)?,
+            fragment_large_packets: switch("Fragment large packets", fragment)?,
+            lockout: switch("Lockout", lockout)?,
+        })
+    }
+}
+
+// Rate to write for a stream switched on or off, keeping a configured rate
+fn rate(on: bool, current: f64, default: f64) -> f64 {
+    match (on, current > 0.0) {
+        (false, _) => 0.0,
+        (true, true) => current,
+        (true, false) => default,
+    }
+}
+
+/// Lets the user edit the main settings in a dialog. Lockout and fragmenting change
+/// right away; the rest is written to the config file.
+pub fn edit(reaper: &Reaper) {
+    let config = Config::load(reaper);
+    let current = SettingsForm::from_config(&config);
+    let mut buf = current.fields().into_bytes();
+    buf.resize(SETTINGS_BUF_LEN, 0);
+    let confirmed = unsafe {
+        reaper.low().GetUserInputs(
+            SETTINGS_TITLE.as_ptr(),
+            SETTINGS_FIELD_COUNT,
+            SETTINGS_CAPTIONS.as_ptr(),
+            buf.as_mut_ptr() as *mut c_char,
+            buf.len() as i32,
+        )
+    };
+    if !confirmed {
+        return;
+    }
+    let fields = CStr::from_bytes_until_nul(&buf)
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let form = match SettingsForm::parse(&fields) {
+        Ok(form) => form,
+        Err(e) => {
+            message_box(reaper, SETTINGS_TITLE, &e);
+            return;
+        }
+    };
+
+    // Lockout and fragmenting apply right away
+    if form.lockout != lockout::is_locked() {
+        lockout::set_locked(form.lockout);
+    }
+    transfer::init(form.fragment_large_packets);
+
+    let edits = [
+        ("osc", "listen", toml::Value::from(form.listen.to_string())),
+        (
+            "osc",
+            "destination",
+            toml::Value::from(form.destination.clone()),
+        ),
+        (
+            "osc",
+            "fragment_large_packets",
+            toml::Value::from(form.fragment_large_packets),
+        ),
+        (
+            "vu",
+            "rate",
+            toml::Value::from(rate(form.meters, config.vu.rate, VuConfig::default().rate)),
+        ),
+        (
+            "clock",
+            "rate",
+            toml::Value::from(rate(
+                form.clock,
+                config.clock.rate,
+                ClockConfig::default().rate,
+            )),
+        ),
+        (
+            "position",
+            "rate",
+            toml::Value::from(rate(
+                form.position,
+                config.position.rate,
+                PositionConfig::default().rate,
+            )),
+        ),
+    ];
+    let changed = form.listen != current.listen
+        || form.destination != current.destination
+        || form.meters != current.meters
+        || form.clock != current.clock
+        || form.position != current.position
+        || form.fragment_large_packets != current.fragment_large_packets;
+    if !changed {
+        return;
+    }
+    match Config::update_file(reaper, &edits) {
+        Ok(()) => message_box(
+            reaper,
+            SETTINGS_TITLE,
+            &format!(
+                "Saved to {}.\n\nChanges to the addresses, VU meters, big clock and play \
+                 position take effect after REAPER restarts.",
+                Config::path(reaper).display()
+            ),
+        ),
+        Err(e) => message_box(reaper, SETTINGS_TITLE, &format!("Failed to save: {}", e)),
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::{MediaTrack, Reaper, ReaperVolumeValue, TrackAttributeKey};
+use serde::Deserialize;
+
+use crate::feedback::Feedback;
+use crate::polling::{PollError, PollSource};
+use crate::utils::{get_track_by_guid, get_track_guid, normalized_to_volume, volume_to_normalized};
+
+/// The `[smoothing]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SmoothingConfig {
+    /// Milliseconds over which received track volume and pan changes are ramped; 0
+    /// applies them at once
+    pub time: u64,
+}
+
+static CONFIG: Mutex<Option<SmoothingConfig>> = Mutex::new(None);
+
+pub fn init(config: SmoothingConfig) {
+    *CONFIG.lock().unwrap() = Some(config);
+}
+
+fn ramp_time() -> Option<Duration> {
+    let time = CONFIG.lock().unwrap().as_ref().map_or(0, |c| c.time);
+    (time > 0).then(|| Duration::from_millis(time))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Param {
+    Volume,
+    Pan,
+}
+
+// Volumes ramp along the fader taper rather than in linear gain, so the change sounds
+// even
+struct Ramp {
+    from: f64,
+    to: f64,
+    started: Instant,
+}
+
+// Ramps in progress, keyed by track GUID so deleted tracks drop out
+static RAMPS: LazyLock<Mutex<HashMap<(String, Param), Ramp>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Sets a track's volume, ramping to it when smoothing is configured
+pub fn set_volume(reaper: &Reaper, track: MediaTrack, volume: ReaperVolumeValue) {
+    start(
+        reaper,
+        track,
+        Param::Volume,
+        volume_to_normalized(reaper, volume),
+    );
+}
+
+/// Sets a track's pan, ramping to it when smoothing is configured
+pub fn set_pan(reaper: &Reaper, track: MediaTrack, pan: f64) {
+    start(reaper, track, Param::Pan, pan);
+}
+
+fn start(reaper: &Reaper, track: MediaTrack, param: Param, to: f64) {
+    if ramp_time().is_none() {
+        apply(reaper, track, param, to);
+        return;
+    }
+    // A ramp interrupted by a newer value continues from where it got to
+    let from = current(reaper, track, param);
+    RAMPS.lock().unwrap().insert(
+        (get_track_guid(reaper, track), param),
+        Ramp {
+            from,
+            to,
+            started: Instant::now(),
+        },
+    );
+}
+
+fn current(reaper: &Reaper, track: MediaTrack, param: Param) -> f64 {
+    unsafe {
+        match param {
+            Param::Volume => volume_to_normalized(
+                reaper,
+                ReaperVolumeValue::new_panic(
+                    reaper.get_media_track_info_value(track, TrackAttributeKey::Vol),
+                ),
+            ),
+            Param::Pan => reaper.get_media_track_info_value(track, TrackAttributeKey::Pan),
+        }
+    }
+}
+
+fn apply(reaper: &Reaper, track: MediaTrack, param: Param, value: f64) {
+    unsafe {
+        match param {
+            Param::Volume => {
+                reaper.csurf_on_volume_change_ex(
+                    track,
+                    reaper_medium::ValueChange::Absolute(normalized_to_volume(reaper, value)),
+                    reaper_medium::GangBehavior::DenyGang,
+                );
+            }
+            Param::Pan => {
+                let _ = reaper.set_media_track_info_value(track, TrackAttributeKey::Pan, value);
+            }
+        }
+    }
+}
+
+/// Advances the volume and pan ramps every run cycle
+pub struct SmoothingPollSource {
+    reaper: Reaper,
+}
+
+impl SmoothingPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        Self { reaper }
+    }
+}
+
+impl PollSource for SmoothingPollSource {
+    fn poll_and_send(&mut self, _: &Sender<Feedback>) -> Result<(), PollError> {
+        let Some(time) = ramp_time() else {
+            return Ok(());
+        };
+        let mut steps = Vec::new();
+        RAMPS.lock().unwrap().retain(|(guid, param), ramp| {
+            let progress = (ramp.started.elapsed().as_secs_f64() / time.as_secs_f64()).min(1.0);
+            steps.push((
+                guid.clone(),
+                *param,
+                ramp.from + (ramp.to - ramp.from) * progress,
+            ));
+            progress < 1.0
+        });
+        // Applied outside the lock, since volume changes call back into the surface
+        for (guid, param, value) in steps {
+            if let Ok(track) = get_track_by_guid(&self.reaper, &guid) {
+                apply(&self.reaper, track, param, value);
+            }
+        }
+        Ok(())
+    }
+}
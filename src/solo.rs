@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use reaper_medium::{GangBehavior, Reaper};
+
+use crate::utils::{get_track_by_guid, get_track_guid};
+
+static EXCLUSIVE: AtomicBool = AtomicBool::new(false);
+
+// GUIDs of the tracks soloed over OSC
+static OSC_SOLOS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+pub fn is_exclusive() -> bool {
+    EXCLUSIVE.load(Ordering::Relaxed)
+}
+
+pub fn set_exclusive(exclusive: bool) {
+    EXCLUSIVE.store(exclusive, Ordering::Relaxed);
+}
+
+/// Solos or unsolos a track on behalf of a surface. In exclusive mode soloing a track
+/// first clears the other solos made over OSC; solos made in REAPER are left alone.
+pub fn set_solo(reaper: &Reaper, track: reaper_medium::MediaTrack, solo: bool) {
+    let guid = get_track_guid(reaper, track);
+    // REAPER calls back into the surface while soloing, so the lock isn't held then
+    let to_clear: Vec<String> = {
+        let mut osc_solos = OSC_SOLOS.lock().unwrap();
+        let to_clear = if solo && is_exclusive() {
+            osc_solos.drain().filter(|g| *g != guid).collect()
+        } else {
+            Vec::new()
+        };
+        if solo {
+            osc_solos.insert(guid);
+        } else {
+            osc_solos.remove(&guid);
+        }
+        to_clear
+    };
+    for other in to_clear {
+        if let Ok(other) = get_track_by_guid(reaper, &other) {
+            unsafe {
+                reaper.csurf_on_solo_change_ex(other, false, GangBehavior::DenyGang);
+            }
+        }
+    }
+    unsafe {
+        reaper.csurf_on_solo_change_ex(track, solo, GangBehavior::DenyGang);
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rosc::OscPacket;
+
+const RATE_INTERVAL: Duration = Duration::from_secs(1);
+
+// Messages received and sent since the plugin loaded
+static INCOMING: AtomicU64 = AtomicU64::new(0);
+static OUTGOING: AtomicU64 = AtomicU64::new(0);
+
+static RATES: Mutex<Rates> = Mutex::new(Rates {
+    measured_at: None,
+    incoming: 0,
+    outgoing: 0,
+    per_second: (0.0, 0.0),
+});
+
+struct Rates {
+    measured_at: Option<Instant>,
+    // Totals at the last measurement
+    incoming: u64,
+    outgoing: u64,
+    per_second: (f64, f64),
+}
+
+fn message_count(packet: &OscPacket) -> u64 {
+    match packet {
+        OscPacket::Message(_) => 1,
+        OscPacket::Bundle(bundle) => bundle.content.iter().map(message_count).sum(),
+    }
+}
+
+pub fn count_incoming(packet: &OscPacket) {
+    INCOMING.fetch_add(message_count(packet), Ordering::Relaxed);
+}
+
+pub fn count_outgoing(packet: &OscPacket) {
+    OUTGOING.fetch_add(message_count(packet), Ordering::Relaxed);
+}
+
+/// Measures the message rates once a second; called on every tick
+pub fn update() {
+    let mut rates = RATES.lock().unwrap();
+    let incoming = INCOMING.load(Ordering::Relaxed);
+    let outgoing = OUTGOING.load(Ordering::Relaxed);
+    let Some(measured_at) = rates.measured_at else {
+        *rates = Rates {
+            measured_at: Some(Instant::now()),
+            incoming,
+            outgoing,
+            per_second: (0.0, 0.0),
+        };
+        return;
+    };
+    let elapsed = measured_at.elapsed();
+    if elapsed < RATE_INTERVAL {
+        return;
+    }
+    let secs = elapsed.as_secs_f64();
+    rates.per_second = (
+        (incoming - rates.incoming) as f64 / secs,
+        (outgoing - rates.outgoing) as f64 / secs,
+    );
+    rates.measured_at = Some(Instant::now());
+    rates.incoming = incoming;
+    rates.outgoing = outgoing;
+}
+
+/// Messages per second received and sent over the last second
+pub fn rates() -> (f64, f64) {
+    RATES.lock().unwrap().per_second
+}
+
+/// Messages received and sent since the plugin loaded
+pub fn totals() -> (u64, u64) {
+    (
+        INCOMING.load(Ordering::Relaxed),
+        OUTGOING.load(Ordering::Relaxed),
+    )
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+use c_str_macro::c_str;
+use reaper_medium::{MediaTrack, Reaper, TrackFxLocation};
+use serde::Deserialize;
+
+// Project ext-state section holding per-project strip mappings, under keys like
+// "strip.gain" with values like "ReaEQ:Gain-Band 1"
+const PROJECT_EXT_SECTION: &CStr = c_str!("arpad");
+
+/// Where a channel strip control lives: a parameter of an FX in the track's chain
+#[derive(Debug, Clone, Deserialize)]
+pub struct StripParam {
+    /// Case-insensitive part of the FX name, e.g. "ReaComp"
+    pub fx: String,
+    /// Case-insensitive part of the parameter name, e.g. "Thresh"
+    pub param: String,
+}
+
+impl StripParam {
+    fn parse(value: &str) -> Option<Self> {
+        let (fx, param) = value.split_once(':')?;
+        Some(StripParam {
+            fx: fx.trim().to_string(),
+            param: param.trim().to_string(),
+        })
+    }
+}
+
+/// The `[strip]` section of the config file, mapping control names such as "gain",
+/// "hpf" or "comp-threshold" to FX parameters
+pub type StripConfig = HashMap<String, StripParam>;
+
+// Strip control the input gain can be mapped to, and the plugin used when it isn't
+const INPUT_GAIN_CONTROL: &str = "input-gain";
+const DEFAULT_INPUT_GAIN_FX: &str = "volume adjustment";
+const DEFAULT_INPUT_GAIN_PARAM: &str = "adjustment";
+
+static CONFIG: Mutex<Option<StripConfig>> = Mutex::new(None);
+
+pub fn init(config: StripConfig) {
+    *CONFIG.lock().unwrap() = Some(config);
+}
+
+fn project_mapping(reaper: &Reaper, control: &str) -> Option<StripParam> {
+    let key = CString::new(format!("strip.{}", control)).ok()?;
+    let mut buf = vec![0 as std::os::raw::c_char; 512];
+    let len = unsafe {
+        reaper.low().GetProjExtState(
+            std::ptr::null_mut(),
+            PROJECT_EXT_SECTION.as_ptr(),
+            key.as_ptr(),
+            buf.as_mut_ptr(),
+            buf.len() as i32,
+        )
+    };
+    if len <= 0 {
+        return None;
+    }
+    let value = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_string_lossy();
+    StripParam::parse(&value)
+}
+
+/// The FX parameter behind a strip control. Mappings saved in the project take
+/// precedence over the config file, so each session can point the same control at
+/// its own plugins.
+pub fn mapping(reaper: &Reaper, control: &str) -> Option<StripParam> {
+    project_mapping(reaper, control)
+        .or_else(|| CONFIG.lock().unwrap().as_ref()?.get(control).cloned())
+}
+
+/// Finds the parameter on the first FX of the track matching the mapping
+pub fn resolve(
+    reaper: &Reaper,
+    track: MediaTrack,
+    mapping: &StripParam,
+) -> Option<(TrackFxLocation, u32)> {
+    let chain = unsafe { reaper.track_fx_get_count(track) };
+    resolve_in(
+        reaper,
+        track,
+        mapping,
+        (0..chain).map(TrackFxLocation::NormalFxChain),
+    )
+}
+
+/// The parameter behind a track's input gain: the "input-gain" control when mapped,
+/// otherwise REAPER's JS volume adjustment plugin. Input FX are searched before the
+/// FX chain, so a trim on the recorded signal wins.
+pub fn resolve_input_gain(reaper: &Reaper, track: MediaTrack) -> Option<(TrackFxLocation, u32)> {
+    let mapping = mapping(reaper, INPUT_GAIN_CONTROL).unwrap_or_else(|| StripParam {
+        fx: DEFAULT_INPUT_GAIN_FX.to_string(),
+        param: DEFAULT_INPUT_GAIN_PARAM.to_string(),
+    });
+    let (input_chain, chain) = unsafe {
+        (
+            reaper.low().TrackFX_GetRecCount(track.as_ptr()) as u32,
+            reaper.track_fx_get_count(track),
+        )
+    };
+    resolve_in(
+        reaper,
+        track,
+        &mapping,
+        (0..input_chain)
+            .map(TrackFxLocation::InputFxChain)
+            .chain((0..chain).map(TrackFxLocation::NormalFxChain)),
+    )
+}
+
+fn resolve_in(
+    reaper: &Reaper,
+    track: MediaTrack,
+    mapping: &StripParam,
+    chain: impl IntoIterator<Item = TrackFxLocation>,
+) -> Option<(TrackFxLocation, u32)> {
+    let fx_name = mapping.fx.to_lowercase();
+    let param_name = mapping.param.to_lowercase();
+    unsafe {
+        let fx = chain.into_iter().find(|&fx| {
+            reaper
+                .track_fx_get_fx_name(track, fx, 256)
+                .is_ok_and(|name| name.to_str().to_lowercase().contains(&fx_name))
+        })?;
+        let param_index = (0..reaper.track_fx_get_num_params(track, fx)).find(|&i| {
+            reaper
+                .track_fx_get_param_name(track, fx, i, 256)
+                .is_ok_and(|name| name.to_str().to_lowercase().contains(&param_name))
+        })?;
+        Some((fx, param_index))
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::net::SocketAddrV4;
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+use crate::clients;
+
+// Address patterns each client subscribed to and when it last sent a packet, keyed by
+// the address it sends from
+static CLIENTS: LazyLock<Mutex<HashMap<SocketAddrV4, (Instant, Vec<String>)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn subscribe(client: SocketAddrV4, patterns: Vec<String>) {
+    let mut clients = CLIENTS.lock().unwrap();
+    let (last_seen, subscribed) = clients
+        .entry(client)
+        .or_insert_with(|| (Instant::now(), Vec::new()));
+    *last_seen = Instant::now();
+    for pattern in patterns {
+        if !subscribed.contains(&pattern) {
+            subscribed.push(pattern);
+        }
+    }
+}
+
+/// Removes the given patterns, or every subscription of the client when none are given
+pub fn unsubscribe(client: SocketAddrV4, patterns: &[String]) {
+    let mut clients = CLIENTS.lock().unwrap();
+    if let Some((_, subscribed)) = clients.get_mut(&client) {
+        subscribed.retain(|p| !patterns.is_empty() && !patterns.contains(p));
+        if subscribed.is_empty() {
+            clients.remove(&client);
+        }
+    }
+}
+
+/// Records a packet from `client`, keeping its subscriptions alive
+pub fn seen(client: SocketAddrV4) {
+    if let Some((last_seen, _)) = CLIENTS.lock().unwrap().get_mut(&client) {
+        *last_seen = Instant::now();
+    }
+}
+
+/// Every subscribed client with its patterns. Subscribers silent for longer than the
+/// client timeout are dropped, like other clients.
+pub fn clients() -> Vec<(SocketAddrV4, Vec<String>)> {
+    let mut clients = CLIENTS.lock().unwrap();
+    if let Some(timeout) = clients::timeout() {
+        clients.retain(|_, (last_seen, _)| last_seen.elapsed() < timeout);
+    }
+    clients
+        .iter()
+        .map(|(client, (_, patterns))| (*client, patterns.clone()))
+        .collect()
+}
+
+/// Whether an address matches one of the patterns. Patterns compare segment by
+/// segment, `*` matching any part of a segment, and cover every address below them,
+/// so `/track/*/volume` matches any track's volume and `/track` all track feedback.
+pub fn matches(patterns: &[String], addr: &str) -> bool {
+    let addr: Vec<&str> = addr.split('/').filter(|s| !s.is_empty()).collect();
+    patterns.iter().any(|pattern| {
+        let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+        pattern.len() <= addr.len()
+            && pattern
+                .iter()
+                .zip(&addr)
+                .all(|(p, segment)| segment_matches(p, segment))
+    })
+}
+
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = segment.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
@@ -0,0 +1,410 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::net::SocketAddrV4;
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use rosc::{OscBundle, OscMessage, OscPacket};
+use serde::Deserialize;
+
+use crate::clients;
+use crate::destinations::Destination;
+use crate::subscriptions;
+use crate::utils::VolumeMode;
+
+/// A logical surface, e.g. "FOH tablet" or "drummer phone". Each surface has its own
+/// destination and address namespace, so several surfaces can share one plugin instance.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SurfaceProfile {
+    pub name: String,
+    /// Where feedback for this surface is sent, as "host:port"; host names are
+    /// looked up again when the surface's address changes
+    pub destination: Destination,
+    /// Address prefix (e.g. "/foh") the surface uses for everything it sends and receives
+    #[serde(default)]
+    pub namespace: String,
+    /// Address prefixes of the feedback this surface wants; empty means everything
+    #[serde(default)]
+    pub subscriptions: Vec<String>,
+    /// Number of tracks in this surface's bank
+    #[serde(default)]
+    pub bank_size: Option<u32>,
+    /// Number of sends in each page of `/track/{guid}/send/page/{n}`
+    #[serde(default)]
+    pub send_page_size: Option<u32>,
+    /// How this surface expresses track volumes
+    #[serde(default)]
+    pub volume_mode: VolumeMode,
+    /// Address patterns, as taken by `/subscribe`, of the values this surface may
+    /// change; empty means everything. They apply to everything sent from the
+    /// destination's host, with or without the namespace.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Whether the surface is sent the full state when it's first heard from, and again
+    /// after `resync_after` seconds of silence
+    #[serde(default)]
+    pub resync: bool,
+    /// Actions this surface may run through `/action`, as numeric ids or command names;
+    /// "*" allows every action. Empty means none.
+    #[serde(default)]
+    pub actions: Vec<String>,
+    /// Whether received volumes and pans are ignored until the surface's fader reaches
+    /// REAPER's value, for faders that aren't motorized
+    #[serde(default)]
+    pub pickup: bool,
+    /// Whether the surface may lift a lockout set by another sender
+    #[serde(default)]
+    pub admin: bool,
+}
+
+impl SurfaceProfile {
+    fn wants(&self, addr: &str) -> bool {
+        self.subscriptions.is_empty() || self.subscriptions.iter().any(|s| addr.starts_with(s))
+    }
+
+    fn owns(&self, addr: &str) -> bool {
+        !self.namespace.is_empty()
+            && addr.starts_with(&self.namespace)
+            && addr[self.namespace.len()..].starts_with('/')
+    }
+}
+
+static PROFILES: OnceLock<Vec<SurfaceProfile>> = OnceLock::new();
+
+// Listen-only destinations mirroring the feedback
+static ECHO: OnceLock<Vec<SocketAddrV4>> = OnceLock::new();
+
+// Volume modes changed at runtime, keyed by surface namespace
+static VOLUME_MODES: LazyLock<Mutex<HashMap<String, VolumeMode>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Address prefix marking a packet for a single surface, followed by the surface's
+// position in the profile list
+const TARGET_PREFIX: &str = "/@surface";
+
+thread_local! {
+    // Namespace of the surface whose message is currently being dispatched
+    static CURRENT_NAMESPACE: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Registers the configured surfaces and echo destinations. When no surfaces are
+/// configured a single surface without a namespace receives all feedback at
+/// `default_destination` and may run `default_actions`.
+pub fn init(
+    mut profiles: Vec<SurfaceProfile>,
+    default_destination: Destination,
+    default_actions: Vec<String>,
+    echo: Vec<SocketAddrV4>,
+) {
+    if profiles.is_empty() {
+        profiles.push(SurfaceProfile {
+            name: "default".to_string(),
+            destination: default_destination,
+            namespace: String::new(),
+            subscriptions: Vec::new(),
+            bank_size: None,
+            send_page_size: None,
+            volume_mode: VolumeMode::default(),
+            permissions: Vec::new(),
+            resync: false,
+            actions: default_actions,
+            pickup: false,
+            admin: false,
+        });
+    }
+    let _ = PROFILES.set(profiles);
+    let _ = ECHO.set(echo);
+}
+
+/// Looks up the surfaces' host names again where due; called from the sender thread,
+/// so slow lookups don't hold up REAPER
+pub fn refresh_destinations() {
+    for profile in profiles() {
+        profile.destination.refresh();
+    }
+}
+
+/// Destinations receiving a copy of the shared feedback and of every query reply
+pub fn echo_destinations() -> &'static [SocketAddrV4] {
+    ECHO.get().map(|e| e.as_slice()).unwrap_or(&[])
+}
+
+pub fn profiles() -> &'static [SurfaceProfile] {
+    PROFILES.get().map(|p| p.as_slice()).unwrap_or(&[])
+}
+
+pub fn profile_for_namespace(namespace: &str) -> Option<&'static SurfaceProfile> {
+    profiles().iter().find(|p| p.namespace == namespace)
+}
+
+/// Splits an incoming address into the namespace of the surface that sent it and
+/// the address with that namespace removed
+pub fn resolve_incoming(addr: &str) -> (&'static str, &str) {
+    match profiles().iter().find(|p| p.owns(addr)) {
+        Some(profile) => (&profile.namespace, &addr[profile.namespace.len()..]),
+        None => ("", addr),
+    }
+}
+
+// Surfaces whose permissions apply to the message being dispatched: the one whose
+// namespace it uses, and every surface whose destination is on the host it was sent
+// from. A restricted surface can't get around its permissions by leaving out its
+// namespace or using another surface's.
+fn governing_profiles() -> impl Iterator<Item = &'static SurfaceProfile> {
+    let namespace = current_namespace();
+    let host = clients::current_client().map(|client| *client.ip());
+    profiles().iter().filter(move |p| {
+        p.namespace == namespace
+            || host.is_some_and(|host| p.destination.addr().is_some_and(|d| *d.ip() == host))
+    })
+}
+
+/// Whether the sender of the message being dispatched may change the value at an
+/// address, given as its segments without the namespace. Every surface the sender
+/// counts as, by namespace or by host, must permit it; senders that count as none may
+/// change everything.
+pub fn permits(segments: &[&str]) -> bool {
+    let addr = segments.join("/");
+    governing_profiles()
+        .all(|p| p.permissions.is_empty() || subscriptions::matches(&p.permissions, &addr))
+}
+
+/// Whether the sender of the message being dispatched may run a REAPER action, given
+/// by its numeric id or command name. Actions can do anything REAPER can, like closing
+/// the project, so unlike `permits` this refuses by default: every surface the sender
+/// counts as must list the action in `actions`, and senders that count as none may
+/// run no actions at all.
+pub fn permits_action(action: &str) -> bool {
+    let action = action.trim_start_matches('_');
+    let mut governing = governing_profiles().peekable();
+    governing.peek().is_some()
+        && governing.all(|p| {
+            p.actions
+                .iter()
+                .any(|allowed| allowed == "*" || allowed.trim_start_matches('_') == action)
+        })
+}
+
+/// Whether the sender of the message being dispatched counts as a surface that wants
+/// the full state when it reconnects
+pub fn wants_resync() -> bool {
+    governing_profiles().any(|p| p.resync)
+}
+
+/// Whether the sender of the message being dispatched counts as an admin surface
+pub fn is_admin() -> bool {
+    governing_profiles().any(|p| p.admin)
+}
+
+pub fn current_namespace() -> String {
+    CURRENT_NAMESPACE.with(|ns| ns.borrow().clone())
+}
+
+/// Marks `namespace` as the surface currently being served until the guard is dropped
+pub fn enter(namespace: &str) -> NamespaceGuard {
+    NamespaceGuard {
+        prev: CURRENT_NAMESPACE.with(|ns| ns.replace(namespace.to_string())),
+    }
+}
+
+pub struct NamespaceGuard {
+    prev: String,
+}
+
+impl Drop for NamespaceGuard {
+    fn drop(&mut self) {
+        let prev = std::mem::take(&mut self.prev);
+        CURRENT_NAMESPACE.with(|ns| *ns.borrow_mut() = prev);
+    }
+}
+
+/// Volume mode of the surface currently being served
+pub fn volume_mode() -> VolumeMode {
+    let namespace = current_namespace();
+    if let Some(mode) = VOLUME_MODES.lock().unwrap().get(&namespace) {
+        return *mode;
+    }
+    profile_for_namespace(&namespace)
+        .map(|p| p.volume_mode)
+        .unwrap_or_default()
+}
+
+pub fn set_volume_mode(mode: VolumeMode) {
+    VOLUME_MODES
+        .lock()
+        .unwrap()
+        .insert(current_namespace(), mode);
+}
+
+/// Namespace of a surface whose volume mode every surface shares, so volume feedback
+/// can be built once inside it for all of them; None when the surfaces disagree and
+/// it has to be built separately for each
+pub fn shared_volume_namespace() -> Option<&'static str> {
+    let mut modes = profiles().iter().map(|p| {
+        let _surface = enter(&p.namespace);
+        volume_mode()
+    });
+    let first = modes.next();
+    if modes.any(|mode| Some(mode) != first) {
+        return None;
+    }
+    Some(profiles().first().map_or("", |p| p.namespace.as_str()))
+}
+
+/// Builds feedback separately for each surface, with that surface entered while
+/// `build` runs. Each packet is delivered only to its surface, subject to its
+/// subscriptions.
+pub fn per_surface(mut build: impl FnMut() -> OscPacket) -> Vec<OscPacket> {
+    profiles()
+        .iter()
+        .enumerate()
+        .map(|(i, profile)| {
+            let _surface = enter(&profile.namespace);
+            namespaced(&format!("{}{}", TARGET_PREFIX, i), build())
+        })
+        .collect()
+}
+
+/// Prefixes every address in the packet with a namespace. Namespaced feedback is
+/// delivered only to the surface owning that namespace.
+pub fn namespaced(namespace: &str, packet: OscPacket) -> OscPacket {
+    if namespace.is_empty() {
+        return packet;
+    }
+    match packet {
+        OscPacket::Message(msg) => OscPacket::Message(OscMessage {
+            addr: format!("{}{}", namespace, msg.addr),
+            args: msg.args,
+        }),
+        OscPacket::Bundle(bundle) => OscPacket::Bundle(OscBundle {
+            timetag: bundle.timetag,
+            content: bundle
+                .content
+                .into_iter()
+                .map(|p| namespaced(namespace, p))
+                .collect(),
+        }),
+    }
+}
+
+/// Works out which destinations an outgoing packet goes to. Packets built by
+/// `per_surface` or already in a surface's namespace go to that surface only;
+/// everything else goes to every surface subscribed to it, with the surface's
+/// namespace added, to every client that subscribed to it with `/subscribe`, to
+/// every other client heard from recently, and to the echo destinations.
+pub fn route_outgoing(packet: &OscPacket) -> Vec<(SocketAddrV4, OscPacket)> {
+    let subscribed = subscriptions::clients();
+    let mut destinations: Vec<(SocketAddrV4, OscPacket)> = profiles()
+        .iter()
+        .enumerate()
+        // A subscribed client listening at a surface's destination gets only what it
+        // subscribed to
+        .filter_map(|(index, profile)| {
+            let destination = profile.destination.addr()?;
+            if subscribed.iter().any(|(c, _)| *c == destination) {
+                return None;
+            }
+            let routed = map_messages(packet.clone(), &mut |mut msg| {
+                if let Some(rest) = msg.addr.strip_prefix(TARGET_PREFIX) {
+                    let (target, addr) = rest.split_at(rest.find('/')?);
+                    if target.parse::<usize>().ok()? != index {
+                        return None;
+                    }
+                    let addr = addr.to_string();
+                    msg.addr = addr;
+                } else if let Some(owner) = profiles().iter().find(|p| p.owns(&msg.addr)) {
+                    return (owner.name == profile.name).then_some(msg);
+                }
+                profile.wants(&msg.addr).then(|| OscMessage {
+                    addr: format!("{}{}", profile.namespace, msg.addr),
+                    args: msg.args,
+                })
+            })?;
+            Some((destination, routed))
+        })
+        .collect();
+    let broadcast: Vec<SocketAddrV4> = clients::active()
+        .into_iter()
+        .filter(|client| {
+            !profiles()
+                .iter()
+                .any(|p| p.destination.addr() == Some(*client))
+                && !subscribed.iter().any(|(c, _)| c == client)
+                && !echo_destinations().contains(client)
+        })
+        .collect();
+    // Clients get shared feedback only, not feedback addressed to a surface
+    destinations.extend(subscribed.into_iter().filter_map(|(client, patterns)| {
+        let packet = shared_feedback(packet, |addr| subscriptions::matches(&patterns, addr))?;
+        Some((client, packet))
+    }));
+    destinations.extend(
+        broadcast
+            .into_iter()
+            .chain(echo_destinations().iter().copied())
+            .filter_map(|client| Some((client, shared_feedback(packet, |_| true)?))),
+    );
+    destinations
+}
+
+// The messages not addressed to a single surface that `wanted` accepts
+fn shared_feedback(packet: &OscPacket, wanted: impl Fn(&str) -> bool) -> Option<OscPacket> {
+    map_messages(packet.clone(), &mut |msg| {
+        let shared =
+            !msg.addr.starts_with(TARGET_PREFIX) && !profiles().iter().any(|p| p.owns(&msg.addr));
+        (shared && wanted(&msg.addr)).then_some(msg)
+    })
+}
+
+// Applies `f` to every message in the packet, dropping messages for which it returns
+// None and bundles left empty
+fn map_messages(
+    packet: OscPacket,
+    f: &mut impl FnMut(OscMessage) -> Option<OscMessage>,
+) -> Option<OscPacket> {
+    match packet {
+        OscPacket::Message(msg) => f(msg).map(OscPacket::Message),
+        OscPacket::Bundle(bundle) => {
+            let content: Vec<OscPacket> = bundle
+                .content
+                .into_iter()
+                .filter_map(|p| map_messages(p, f))
+                .collect();
+            (!content.is_empty()).then_some(OscPacket::Bundle(OscBundle {
+                timetag: bundle.timetag,
+                content,
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn single_surface_volume_mode_is_shared() {
+        init(
+            vec![SurfaceProfile {
+                name: "FOH".to_string(),
+                destination: SocketAddrV4::from_str("127.0.0.1:9000").unwrap().into(),
+                namespace: "/foh".to_string(),
+                subscriptions: Vec::new(),
+                bank_size: None,
+                send_page_size: None,
+                volume_mode: VolumeMode::Db,
+                permissions: Vec::new(),
+                resync: false,
+                actions: Vec::new(),
+                pickup: false,
+                admin: false,
+            }],
+            SocketAddrV4::from_str("0.0.0.0:9091").unwrap().into(),
+            Vec::new(),
+            Vec::new(),
+        );
+        let namespace = shared_volume_namespace().unwrap();
+        let _surface = enter(namespace);
+        assert_eq!(volume_mode(), VolumeMode::Db);
+    }
+}
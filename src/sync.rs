@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+use std::net::SocketAddrV4;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{Reaper, TrackSendCategory};
+
+use crate::clients;
+use crate::lasttouched;
+use crate::surfaces;
+use crate::utils::get_track_guid;
+
+// Queries answered per run cycle while sending full states, so a large project doesn't
+// hold up REAPER's main thread for a whole dump
+const QUERIES_PER_CYCLE: usize = 200;
+
+// Set by `/arpad/sync` while its message is dispatched
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Asks for the full state to be sent to the sender of the current message
+pub fn request() {
+    REQUESTED.store(true, Ordering::Relaxed);
+}
+
+pub fn take_request() -> bool {
+    REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+// Set by the "Resync all clients" action
+static REQUESTED_ALL: AtomicBool = AtomicBool::new(false);
+
+/// Asks for the full state to be sent to every surface and client
+pub fn request_all() {
+    REQUESTED_ALL.store(true, Ordering::Relaxed);
+}
+
+pub fn take_request_all() -> bool {
+    REQUESTED_ALL.swap(false, Ordering::Relaxed)
+}
+
+// Per-track values a surface draws
+const TRACK_FIELDS: [&str; 19] = [
+    "index",
+    "name",
+    "volume",
+    "pan",
+    "mute",
+    "solo",
+    "rec-arm",
+    "selected",
+    "color",
+    "automation-mode",
+    "monitor",
+    "rec-input",
+    "phase",
+    "nchan",
+    "pan-mode",
+    "width",
+    "pan-l",
+    "pan-r",
+    "fx/count",
+];
+const SEND_FIELDS: [&str; 3] = ["guid", "volume", "pan"];
+const FX_FIELDS: [&str; 2] = ["name", "enabled"];
+// Only part of the state once a parameter was touched
+const LAST_TOUCHED_ADDRESSES: [&str; 2] = [
+    "/fx/last-touched/param/name",
+    "/fx/last-touched/param/value",
+];
+// The session comes first, so clients can drop state from another project before the
+// rest arrives
+const GLOBAL_ADDRESSES: [&str; 20] = [
+    "/arpad/session",
+    "/project/name",
+    "/project/path",
+    "/project/length",
+    "/project/track-count",
+    "/project/dirty",
+    "/transport/play",
+    "/transport/stop",
+    "/transport/pause",
+    "/transport/record",
+    "/transport/repeat",
+    "/transport/playrate",
+    "/transport/preserve-pitch",
+    "/transport/time",
+    "/project/tempo",
+    "/project/timesig",
+    "/master/volume",
+    "/master/pan",
+    "/master/mute",
+    "/master/solo",
+];
+
+/// Addresses whose current values make up the state a surface needs to redraw from
+/// scratch; each is answered as a query
+pub fn state_addresses(reaper: &Reaper) -> Vec<String> {
+    let mut addresses: Vec<String> = GLOBAL_ADDRESSES.iter().map(|a| a.to_string()).collect();
+    for track in
+        (0..reaper.count_tracks(CurrentProject)).filter_map(|i| reaper.get_track(CurrentProject, i))
+    {
+        let guid = get_track_guid(reaper, track);
+        addresses.extend(
+            TRACK_FIELDS
+                .iter()
+                .map(|field| format!("/track/{}/{}", guid, field)),
+        );
+        let sends = unsafe { reaper.get_track_num_sends(track, TrackSendCategory::Send) };
+        for send in 0..sends {
+            addresses.extend(
+                SEND_FIELDS
+                    .iter()
+                    .map(|field| format!("/track/{}/send/{}/{}", guid, send, field)),
+            );
+        }
+        for fx in 0..unsafe { reaper.track_fx_get_count(track) } {
+            addresses.extend(
+                FX_FIELDS
+                    .iter()
+                    .map(|field| format!("/track/{}/fx/{}/{}", guid, fx, field)),
+            );
+        }
+    }
+    if lasttouched::get(reaper).is_some() {
+        addresses.extend(LAST_TOUCHED_ADDRESSES.iter().map(|a| a.to_string()));
+    }
+    addresses
+}
+
+/// The full state on its way to one surface or client
+struct StateDump {
+    namespace: String,
+    client: Option<SocketAddrV4>,
+    addresses: VecDeque<String>,
+}
+
+static DUMPS: Mutex<VecDeque<StateDump>> = Mutex::new(VecDeque::new());
+
+/// Queues the full state for the surface and client currently being served, to be
+/// answered over the next run cycles. A dump already on its way to them starts over.
+pub fn queue_state(reaper: &Reaper) {
+    let namespace = surfaces::current_namespace();
+    let client = clients::current_client();
+    let mut dumps = DUMPS.lock().unwrap();
+    dumps.retain(|dump| dump.namespace != namespace || dump.client != client);
+    dumps.push_back(StateDump {
+        namespace,
+        client,
+        addresses: state_addresses(reaper).into(),
+    });
+}
+
+/// Takes the addresses to answer in this run cycle from the queued dumps, oldest
+/// first, each with the surface namespace and client it is for
+pub fn next_queries() -> Vec<(String, Option<SocketAddrV4>, Vec<String>)> {
+    let mut dumps = DUMPS.lock().unwrap();
+    let mut budget = QUERIES_PER_CYCLE;
+    let mut queries = Vec::new();
+    while budget > 0 {
+        let Some(dump) = dumps.front_mut() else {
+            break;
+        };
+        let count = budget.min(dump.addresses.len());
+        budget -= count;
+        queries.push((
+            dump.namespace.clone(),
+            dump.client,
+            dump.addresses.drain(..count).collect(),
+        ));
+        if dump.addresses.is_empty() {
+            dumps.pop_front();
+        }
+    }
+    queries
+}
@@ -0,0 +1,64 @@
+use std::ffi::{CStr, CString};
+
+use c_str_macro::c_str;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper};
+
+// Track extension key holding the track's tags, comma-separated; stored in the project
+const TAGS_EXT_KEY: &CStr = c_str!("P_EXT:arpad_tags");
+
+const TAGS_BUF_SIZE: usize = 4096;
+
+pub fn tags(reaper: &Reaper, track: MediaTrack) -> Vec<String> {
+    let mut buf = [0 as std::os::raw::c_char; TAGS_BUF_SIZE];
+    let found = unsafe {
+        reaper.low().GetSetMediaTrackInfo_String(
+            track.as_ptr(),
+            TAGS_EXT_KEY.as_ptr(),
+            buf.as_mut_ptr(),
+            false,
+        )
+    };
+    if !found {
+        return Vec::new();
+    }
+    unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Replaces the track's tags. Tags are trimmed; empty tags and duplicates are dropped,
+/// and commas can't be part of a tag.
+pub fn set_tags(reaper: &Reaper, track: MediaTrack, tags: &[String]) {
+    let mut unique: Vec<&str> = Vec::new();
+    for tag in tags.iter().flat_map(|t| t.split(',')).map(str::trim) {
+        if !tag.is_empty() && !unique.contains(&tag) {
+            unique.push(tag);
+        }
+    }
+    let value = CString::new(unique.join(",")).unwrap_or_default();
+    unsafe {
+        reaper.low().GetSetMediaTrackInfo_String(
+            track.as_ptr(),
+            TAGS_EXT_KEY.as_ptr(),
+            value.as_ptr() as *mut _,
+            true,
+        );
+    }
+}
+
+/// Tracks carrying a tag, in track order. Tags are compared case-insensitively.
+pub fn tracks_with_tag(reaper: &Reaper, tag: &str) -> Vec<MediaTrack> {
+    (0..reaper.count_tracks(CurrentProject))
+        .filter_map(|i| reaper.get_track(CurrentProject, i))
+        .filter(|&track| {
+            tags(reaper, track)
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(tag))
+        })
+        .collect()
+}
@@ -0,0 +1,133 @@
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+use c_str_macro::c_str;
+use reaper_medium::{MediaTrack, Reaper, TrackAttributeKey};
+use serde::Deserialize;
+
+use crate::cuemix;
+use crate::utils::{find_track_by_guid_or_name, get_track_by_guid, get_track_guid};
+use crate::RouteError;
+
+// Ext-state used when the config file doesn't name a talkback track
+const EXT_STATE_SECTION: &CStr = c_str!("arpad");
+const EXT_STATE_TRACK_KEY: &CStr = c_str!("talkback_track");
+
+/// The `[talkback]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TalkbackConfig {
+    /// GUID or name of the talkback track
+    pub track: Option<String>,
+    /// Attenuation in dB applied to every cue bus while talkback is held, from -inf to
+    /// 0; positive values are taken as 0. Defaults to -15.
+    pub dim_db: Option<f64>,
+}
+
+const DEFAULT_DIM_DB: f64 = -15.0;
+
+static CONFIG: Mutex<Option<TalkbackConfig>> = Mutex::new(None);
+
+/// State from before talkback was pressed, restored on release
+struct Held {
+    talkback_muted: bool,
+    cue_volumes: Vec<(String, f64)>,
+}
+
+// None while released
+static HELD: Mutex<Option<Held>> = Mutex::new(None);
+
+pub fn init(config: TalkbackConfig) {
+    *CONFIG.lock().unwrap() = Some(config);
+}
+
+fn configured_track(reaper: &Reaper) -> Option<String> {
+    if let Some(track) = CONFIG
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|c| c.track.clone())
+    {
+        return Some(track);
+    }
+    let value = unsafe {
+        let ptr = reaper
+            .low()
+            .GetExtState(EXT_STATE_SECTION.as_ptr(), EXT_STATE_TRACK_KEY.as_ptr());
+        if ptr.is_null() {
+            return None;
+        }
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+pub fn talkback_track(reaper: &Reaper) -> Result<MediaTrack, RouteError> {
+    let id = configured_track(reaper)
+        .ok_or_else(|| RouteError::ValueNotFound("Talkback track".to_string()))?;
+    find_track_by_guid_or_name(reaper, &id)
+}
+
+pub fn is_held() -> bool {
+    HELD.lock().unwrap().is_some()
+}
+
+/// Unmutes the talkback track and dims the cue buses while held; releasing restores
+/// the talkback track's mute and the cue bus volumes.
+pub fn set_held(reaper: &Reaper, held: bool) -> Result<(), RouteError> {
+    let track = talkback_track(reaper)?;
+    let mut state = HELD.lock().unwrap();
+    if held == state.is_some() {
+        return Ok(());
+    }
+    if held {
+        let talkback_muted =
+            unsafe { reaper.get_media_track_info_value(track, TrackAttributeKey::Mute) } != 0.0;
+        set_mute(reaper, track, false);
+        let dim_db = CONFIG
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|c| c.dim_db)
+            .filter(|db| !db.is_nan())
+            .unwrap_or(DEFAULT_DIM_DB)
+            .min(0.0);
+        let factor = 10f64.powf(dim_db / 20.0);
+        let mut cue_volumes = Vec::new();
+        for cue in cuemix::cue_buses(reaper) {
+            let volume = unsafe { reaper.get_media_track_info_value(cue, TrackAttributeKey::Vol) };
+            set_volume(reaper, cue, volume * factor);
+            cue_volumes.push((get_track_guid(reaper, cue), volume));
+        }
+        *state = Some(Held {
+            talkback_muted,
+            cue_volumes,
+        });
+    } else if let Some(prev) = state.take() {
+        set_mute(reaper, track, prev.talkback_muted);
+        for (guid, volume) in prev.cue_volumes {
+            if let Ok(cue) = get_track_by_guid(reaper, &guid) {
+                set_volume(reaper, cue, volume);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set_mute(reaper: &Reaper, track: MediaTrack, mute: bool) {
+    unsafe {
+        reaper.low().SetMediaTrackInfo_Value(
+            track.as_ptr(),
+            c_str!("B_MUTE").as_ptr(),
+            if mute { 1.0 } else { 0.0 },
+        );
+    }
+}
+
+fn set_volume(reaper: &Reaper, track: MediaTrack, volume: f64) {
+    unsafe {
+        reaper
+            .low()
+            .SetMediaTrackInfo_Value(track.as_ptr(), c_str!("D_VOL").as_ptr(), volume);
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::Reaper;
+
+// Number of tap intervals averaged into the tempo
+const TAP_WINDOW: usize = 4;
+// A pause this long starts a new series of taps
+const TAP_RESET: Duration = Duration::from_secs(2);
+// Tempo range REAPER accepts
+const MIN_BPM: f64 = 1.0;
+const MAX_BPM: f64 = 960.0;
+
+static TAPS: Mutex<Vec<Instant>> = Mutex::new(Vec::new());
+
+/// A tempo/time signature marker as returned by GetTempoTimeSigMarker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoMarker {
+    pub position: f64,
+    pub bpm: f64,
+    pub timesig_num: i32,
+    pub timesig_denom: i32,
+    pub linear: bool,
+}
+
+pub fn count_tempo_markers(reaper: &Reaper) -> u32 {
+    unsafe { reaper.low().CountTempoTimeSigMarkers(std::ptr::null_mut()) as u32 }
+}
+
+pub fn get_tempo_marker(reaper: &Reaper, index: u32) -> Option<TempoMarker> {
+    let mut marker = TempoMarker {
+        position: 0.0,
+        bpm: 0.0,
+        timesig_num: 0,
+        timesig_denom: 0,
+        linear: false,
+    };
+    let (mut measure_pos, mut beat_pos) = (0, 0.0);
+    let found = unsafe {
+        reaper.low().GetTempoTimeSigMarker(
+            std::ptr::null_mut(),
+            index as i32,
+            &mut marker.position,
+            &mut measure_pos,
+            &mut beat_pos,
+            &mut marker.bpm,
+            &mut marker.timesig_num,
+            &mut marker.timesig_denom,
+            &mut marker.linear,
+        )
+    };
+    found.then_some(marker)
+}
+
+/// Replaces the marker at `index`, keeping it anchored to its time position
+pub fn set_tempo_marker(reaper: &Reaper, index: u32, marker: TempoMarker) -> bool {
+    let ok = unsafe {
+        reaper.low().SetTempoTimeSigMarker(
+            std::ptr::null_mut(),
+            index as i32,
+            marker.position,
+            -1,
+            -1.0,
+            marker.bpm,
+            marker.timesig_num,
+            marker.timesig_denom,
+            marker.linear,
+        )
+    };
+    if ok {
+        reaper.update_timeline();
+    }
+    ok
+}
+
+/// Tempo at the play position while playing, otherwise at the edit cursor
+pub fn current_bpm(reaper: &Reaper) -> f64 {
+    unsafe { reaper.low().Master_GetTempo() }
+}
+
+/// Sets the project tempo as a control surface would, adding an undo point. Returns
+/// false for tempos REAPER doesn't accept.
+pub fn set_bpm(reaper: &Reaper, bpm: f64) -> bool {
+    if !(MIN_BPM..=MAX_BPM).contains(&bpm) {
+        return false;
+    }
+    unsafe {
+        reaper.low().CSurf_OnTempoChange(bpm);
+    }
+    true
+}
+
+/// Time signature numerator and denominator at the play position while playing,
+/// otherwise at the edit cursor
+pub fn current_time_signature(reaper: &Reaper) -> (i32, i32) {
+    let position = if reaper.get_play_state_ex(CurrentProject).is_playing {
+        reaper.get_play_position_ex(CurrentProject).get()
+    } else {
+        reaper.get_cursor_position_ex(CurrentProject).get()
+    };
+    let (mut num, mut denom, mut bpm) = (0, 0, 0.0);
+    unsafe {
+        reaper.low().TimeMap_GetTimeSigAtTime(
+            std::ptr::null_mut(),
+            position,
+            &mut num,
+            &mut denom,
+            &mut bpm,
+        );
+    }
+    (num, denom)
+}
+
+/// Registers a tap and, from the second tap of a series on, sets the project tempo to
+/// the average of the last few tap intervals. Returns the new tempo.
+pub fn tap(reaper: &Reaper) -> Option<f64> {
+    let now = Instant::now();
+    let mut taps = TAPS.lock().unwrap();
+    if taps.last().is_some_and(|last| now - *last > TAP_RESET) {
+        taps.clear();
+    }
+    taps.push(now);
+    if taps.len() > TAP_WINDOW + 1 {
+        taps.remove(0);
+    }
+    let (first, last) = (taps.first()?, taps.last()?);
+    let intervals = taps.len() - 1;
+    if intervals == 0 {
+        return None;
+    }
+    let interval = (*last - *first).as_secs_f64() / intervals as f64;
+    let bpm = (60.0 / interval).clamp(MIN_BPM, MAX_BPM);
+    unsafe {
+        reaper.low().SetCurrentBPM(std::ptr::null_mut(), bpm, true);
+    }
+    Some(bpm)
+}
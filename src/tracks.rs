@@ -0,0 +1,307 @@
+use std::ffi::CString;
+use std::path::Path;
+
+use c_str_macro::c_str;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{
+    CommandId, MasterTrackBehavior, MediaTrack, NativeColor, NativeColorValue, Reaper,
+};
+
+use crate::solo;
+use crate::RouteError;
+
+// "Track: Duplicate tracks"
+const DUPLICATE_TRACKS_COMMAND: u32 = 40062;
+
+const TRACK_TEMPLATE_DIR: &str = "TrackTemplates";
+const TRACK_TEMPLATE_EXTENSION: &str = "RTrackTemplate";
+
+// I_RECINPUT flags and fields
+const RECINPUT_STEREO_FLAG: i32 = 1024;
+const RECINPUT_MULTICHANNEL_FLAG: i32 = 2048;
+const RECINPUT_MIDI_FLAG: i32 = 4096;
+const RECINPUT_AUDIO_CHANNEL_MASK: i32 = 1023;
+const RECINPUT_MIDI_CHANNEL_MASK: i32 = 31;
+const RECINPUT_MIDI_DEVICE_SHIFT: i32 = 5;
+const RECINPUT_MIDI_DEVICE_MASK: i32 = 63;
+
+/// What a track records from, as encoded in I_RECINPUT
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordInput {
+    None,
+    /// Audio starting at the 0-based input channel
+    Mono(i32),
+    Stereo(i32),
+    Multichannel(i32),
+    /// MIDI device index, 63 meaning all devices, and channel, 0 meaning all channels
+    Midi {
+        device: i32,
+        channel: i32,
+    },
+}
+
+impl RecordInput {
+    pub fn from_raw(raw: i32) -> Self {
+        if raw < 0 {
+            RecordInput::None
+        } else if raw & RECINPUT_MIDI_FLAG != 0 {
+            RecordInput::Midi {
+                device: (raw >> RECINPUT_MIDI_DEVICE_SHIFT) & RECINPUT_MIDI_DEVICE_MASK,
+                channel: raw & RECINPUT_MIDI_CHANNEL_MASK,
+            }
+        } else if raw & RECINPUT_MULTICHANNEL_FLAG != 0 {
+            RecordInput::Multichannel(raw & RECINPUT_AUDIO_CHANNEL_MASK)
+        } else if raw & RECINPUT_STEREO_FLAG != 0 {
+            RecordInput::Stereo(raw & RECINPUT_AUDIO_CHANNEL_MASK)
+        } else {
+            RecordInput::Mono(raw & RECINPUT_AUDIO_CHANNEL_MASK)
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        match self {
+            RecordInput::None => -1,
+            RecordInput::Mono(channel) => channel & RECINPUT_AUDIO_CHANNEL_MASK,
+            RecordInput::Stereo(channel) => {
+                RECINPUT_STEREO_FLAG | (channel & RECINPUT_AUDIO_CHANNEL_MASK)
+            }
+            RecordInput::Multichannel(channel) => {
+                RECINPUT_MULTICHANNEL_FLAG | (channel & RECINPUT_AUDIO_CHANNEL_MASK)
+            }
+            RecordInput::Midi { device, channel } => {
+                RECINPUT_MIDI_FLAG
+                    | ((device & RECINPUT_MIDI_DEVICE_MASK) << RECINPUT_MIDI_DEVICE_SHIFT)
+                    | (channel & RECINPUT_MIDI_CHANNEL_MASK)
+            }
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RecordInput::None => "none",
+            RecordInput::Mono(_) => "mono",
+            RecordInput::Stereo(_) => "stereo",
+            RecordInput::Multichannel(_) => "multichannel",
+            RecordInput::Midi { .. } => "midi",
+        }
+    }
+
+    /// Builds an input from its kind, the audio channel or MIDI device, and the MIDI
+    /// channel
+    pub fn from_parts(kind: &str, input: i32, midi_channel: i32) -> Option<Self> {
+        let input_ok = match kind {
+            "midi" => (0..=RECINPUT_MIDI_DEVICE_MASK).contains(&input),
+            _ => (0..=RECINPUT_AUDIO_CHANNEL_MASK).contains(&input),
+        };
+        if !input_ok || !(0..=16).contains(&midi_channel) {
+            return None;
+        }
+        match kind {
+            "none" => Some(RecordInput::None),
+            "mono" => Some(RecordInput::Mono(input)),
+            "stereo" => Some(RecordInput::Stereo(input)),
+            "multichannel" => Some(RecordInput::Multichannel(input)),
+            "midi" => Some(RecordInput::Midi {
+                device: input,
+                channel: midi_channel,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Settings applied to a newly created or duplicated track
+#[derive(Debug, Default)]
+pub struct NewTrackOptions {
+    pub name: Option<String>,
+    pub color: Option<i32>,
+    /// Track template to create the track from, either a path or a name inside the
+    /// REAPER TrackTemplates directory
+    pub template: Option<String>,
+}
+
+/// Appends a track, or inserts a track template, and applies the options to it in a
+/// single undo step
+pub fn create_track(reaper: &Reaper, options: &NewTrackOptions) -> Result<MediaTrack, RouteError> {
+    atomically(reaper, "Create track via arpad", || {
+        let track = match &options.template {
+            Some(template) => insert_template(reaper, template)?,
+            None => {
+                let index = reaper.count_tracks(CurrentProject);
+                unsafe { reaper.low().InsertTrackAtIndex(index as i32, true) };
+                reaper
+                    .get_track(CurrentProject, index)
+                    .ok_or_else(|| RouteError::ValueNotFound("New track".to_string()))?
+            }
+        };
+        apply_options(reaper, track, options);
+        Ok(track)
+    })
+}
+
+/// Duplicates a track and applies the options to the copy in a single undo step. The
+/// template option is ignored.
+pub fn duplicate_track(
+    reaper: &Reaper,
+    source: MediaTrack,
+    options: &NewTrackOptions,
+) -> Result<MediaTrack, RouteError> {
+    atomically(reaper, "Duplicate track via arpad", || {
+        // The duplicate action works on the selection, which is put back afterwards
+        let selection = selected_tracks(reaper);
+        unsafe { reaper.low().SetOnlyTrackSelected(source.as_ptr()) };
+        reaper.main_on_command_ex(CommandId::new(DUPLICATE_TRACKS_COMMAND), 0, CurrentProject);
+        // The duplicate is inserted below the source and becomes the selected track
+        let track = reaper
+            .get_selected_track(CurrentProject, 0)
+            .filter(|&track| track != source);
+        unsafe {
+            let low = reaper.low();
+            if let Some(track) = track {
+                low.SetTrackSelected(track.as_ptr(), false);
+            }
+            low.SetTrackSelected(source.as_ptr(), false);
+            for selected in &selection {
+                low.SetTrackSelected(selected.as_ptr(), true);
+            }
+        }
+        let track =
+            track.ok_or_else(|| RouteError::ValueNotFound("Duplicated track".to_string()))?;
+        apply_options(reaper, track, options);
+        Ok(track)
+    })
+}
+
+/// One attribute change in a batch applied by `set_many`
+#[derive(Debug, Clone)]
+pub enum TrackSetting {
+    /// Linear volume, 1.0 being 0 dB
+    Volume(f64),
+    Pan(f64),
+    Mute(bool),
+    Solo(bool),
+    RecArm(bool),
+    Name(String),
+    Color(i32),
+}
+
+/// Applies a batch of track changes in a single undo step, without refreshing the UI
+/// between changes
+pub fn set_many(reaper: &Reaper, changes: &[(MediaTrack, TrackSetting)]) {
+    let _ = atomically(reaper, "Set tracks via arpad", || {
+        for (track, setting) in changes {
+            apply_setting(reaper, *track, setting);
+        }
+        Ok(())
+    });
+}
+
+fn apply_setting(reaper: &Reaper, track: MediaTrack, setting: &TrackSetting) {
+    let (key, value) = match setting {
+        TrackSetting::Volume(volume) => (c_str!("D_VOL"), *volume),
+        TrackSetting::Pan(pan) => (c_str!("D_PAN"), *pan),
+        TrackSetting::Mute(mute) => (c_str!("B_MUTE"), *mute as i32 as f64),
+        // Solos go through the OSC solo tracking, so exclusive mode can clear them
+        TrackSetting::Solo(solo) => return solo::set_solo(reaper, track, *solo),
+        TrackSetting::RecArm(armed) => (c_str!("I_RECARM"), *armed as i32 as f64),
+        TrackSetting::Name(name) => {
+            return apply_options(
+                reaper,
+                track,
+                &NewTrackOptions {
+                    name: Some(name.clone()),
+                    ..Default::default()
+                },
+            )
+        }
+        TrackSetting::Color(color) => {
+            return apply_options(
+                reaper,
+                track,
+                &NewTrackOptions {
+                    color: Some(*color),
+                    ..Default::default()
+                },
+            )
+        }
+    };
+    unsafe {
+        reaper
+            .low()
+            .SetMediaTrackInfo_Value(track.as_ptr(), key.as_ptr(), value);
+    }
+}
+
+fn atomically<R>(
+    reaper: &Reaper,
+    description: &str,
+    f: impl FnOnce() -> Result<R, RouteError>,
+) -> Result<R, RouteError> {
+    let description = CString::new(description).unwrap_or_default();
+    let low = reaper.low();
+    unsafe {
+        low.PreventUIRefresh(1);
+        low.Undo_BeginBlock2(std::ptr::null_mut());
+    }
+    let result = f();
+    unsafe {
+        low.Undo_EndBlock2(std::ptr::null_mut(), description.as_ptr(), -1);
+        low.PreventUIRefresh(-1);
+    }
+    result
+}
+
+fn insert_template(reaper: &Reaper, template: &str) -> Result<MediaTrack, RouteError> {
+    let mut path = Path::new(template).to_path_buf();
+    if path.is_relative() {
+        path = reaper.get_resource_path(|dir| dir.join(TRACK_TEMPLATE_DIR).join(&path));
+    }
+    if path.extension().is_none() {
+        path.set_extension(TRACK_TEMPLATE_EXTENSION);
+    }
+    if !path.is_file() {
+        return Err(RouteError::ValueNotFound(format!(
+            "Track template {}",
+            path.display()
+        )));
+    }
+    let path = CString::new(path.to_string_lossy().into_owned())
+        .map_err(|_| RouteError::ValueNotFound(format!("Track template {}", template)))?;
+    // Opening a track template inserts its tracks and selects them
+    unsafe { reaper.low().Main_openProject(path.as_ptr()) };
+    reaper
+        .get_selected_track(CurrentProject, 0)
+        .ok_or_else(|| RouteError::ValueNotFound(format!("Tracks from template {}", template)))
+}
+
+/// The selected tracks, in track order
+pub fn selected_tracks(reaper: &Reaper) -> Vec<MediaTrack> {
+    (0..reaper.count_selected_tracks_2(CurrentProject, MasterTrackBehavior::ExcludeMasterTrack))
+        .filter_map(|i| {
+            reaper.get_selected_track_2(CurrentProject, i, MasterTrackBehavior::ExcludeMasterTrack)
+        })
+        .collect()
+}
+
+fn apply_options(reaper: &Reaper, track: MediaTrack, options: &NewTrackOptions) {
+    unsafe {
+        if let Some(name) = &options.name {
+            let name = CString::new(name.as_str()).unwrap_or_default();
+            reaper.low().GetSetMediaTrackInfo_String(
+                track.as_ptr(),
+                c_str!("P_NAME").as_ptr(),
+                name.as_ptr() as *mut _,
+                true,
+            );
+        }
+        if let Some(color) = options.color {
+            reaper.get_set_media_track_info_set_custom_color(
+                track,
+                NativeColorValue {
+                    color: NativeColor::new(color),
+                    is_used: true,
+                },
+            );
+        }
+    }
+}
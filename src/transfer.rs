@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::net::SocketAddrV4;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use rosc::{encoder, OscMessage, OscPacket, OscType};
+
+// Payload bytes per fragment. With the address and the id, index and count arguments
+// a fragment stays within one 1472 byte datagram.
+const FRAGMENT_LEN: usize = 1400;
+// Unacknowledged fragments are sent again after this
+const RETRY_AFTER: Duration = Duration::from_millis(200);
+// Sends of a fragment before its transfer is given up
+const MAX_ATTEMPTS: u32 = 5;
+// Incoming transfers are limited to this many fragments, about 1.4MB
+const MAX_INCOMING_FRAGMENTS: i32 = 1024;
+// Incoming transfers are forgotten after this long without a fragment
+const INCOMING_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const FRAGMENT_ADDRESS: &str = "/arpad/blob";
+const ACK_ADDRESS: &str = "/arpad/blob/ack";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static NEXT_ID: AtomicI32 = AtomicI32::new(0);
+
+// Fragments sent and not yet acknowledged, keyed by transfer id. Ids are unique
+// across destinations, so acknowledgments are matched even when a surface sends from
+// another port than it receives on.
+static OUTGOING: LazyLock<Mutex<HashMap<i32, Outgoing>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Fragments received so far, keyed by sender and transfer id
+static INCOMING: LazyLock<Mutex<HashMap<(SocketAddrV4, i32), Incoming>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// `enabled` of false sends oversized packets as single datagrams, for clients that
+/// don't reassemble fragments
+pub fn init(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+struct Outgoing {
+    dest: SocketAddrV4,
+    // Encoded fragment datagrams; None once acknowledged
+    fragments: Vec<Option<Vec<u8>>>,
+    sent_at: Instant,
+    attempts: u32,
+}
+
+struct Incoming {
+    fragments: Vec<Option<Vec<u8>>>,
+    received_at: Instant,
+    // Kept after delivery until the timeout, to recognize repeated fragments
+    complete: bool,
+}
+
+fn fragment_message(id: i32, index: usize, count: usize, data: &[u8]) -> Option<Vec<u8>> {
+    encoder::encode(&OscPacket::Message(OscMessage {
+        addr: FRAGMENT_ADDRESS.to_string(),
+        args: vec![
+            OscType::Int(id),
+            OscType::Int(index as i32),
+            OscType::Int(count as i32),
+            OscType::Blob(data.to_vec()),
+        ],
+    }))
+    .ok()
+}
+
+/// Splits an encoded packet too large for one datagram into `/arpad/blob` fragments,
+/// returning the datagrams to send. The fragments are sent again until the destination
+/// acknowledges each of them. Returns the packet itself when fragmenting is off.
+pub fn start(dest: SocketAddrV4, datagram: Vec<u8>) -> Vec<Vec<u8>> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return vec![datagram];
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let chunks: Vec<&[u8]> = datagram.chunks(FRAGMENT_LEN).collect();
+    let fragments: Vec<Vec<u8>> = chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, data)| fragment_message(id, index, chunks.len(), data))
+        .collect();
+    OUTGOING.lock().unwrap().insert(
+        id,
+        Outgoing {
+            dest,
+            fragments: fragments.iter().cloned().map(Some).collect(),
+            sent_at: Instant::now(),
+            attempts: 1,
+        },
+    );
+    fragments
+}
+
+/// Marks a fragment as received, forgetting the transfer once every fragment is
+pub fn acknowledge(id: i32, index: i32) {
+    let mut outgoing = OUTGOING.lock().unwrap();
+    let Some(transfer) = outgoing.get_mut(&id) else {
+        return;
+    };
+    if let Some(fragment) = usize::try_from(index)
+        .ok()
+        .and_then(|i| transfer.fragments.get_mut(i))
+    {
+        *fragment = None;
+    }
+    if transfer.fragments.iter().all(Option::is_none) {
+        outgoing.remove(&id);
+    }
+}
+
+/// Fragments due to be sent again, with their destination. Transfers that ran out of
+/// attempts are given up.
+pub fn retransmit() -> Vec<(SocketAddrV4, Vec<u8>)> {
+    let mut outgoing = OUTGOING.lock().unwrap();
+    outgoing.retain(|_, transfer| {
+        transfer.sent_at.elapsed() < RETRY_AFTER || transfer.attempts < MAX_ATTEMPTS
+    });
+    let mut due = Vec::new();
+    for transfer in outgoing.values_mut() {
+        if transfer.sent_at.elapsed() < RETRY_AFTER {
+            continue;
+        }
+        transfer.sent_at = Instant::now();
+        transfer.attempts += 1;
+        due.extend(
+            transfer
+                .fragments
+                .iter()
+                .flatten()
+                .map(|f| (transfer.dest, f.clone())),
+        );
+    }
+    due
+}
+
+/// The acknowledgment of a fragment
+fn ack_message(id: i32, index: i32) -> OscMessage {
+    OscMessage {
+        addr: ACK_ADDRESS.to_string(),
+        args: vec![OscType::Int(id), OscType::Int(index)],
+    }
+}
+
+/// Stores a fragment from `client`. Returns its acknowledgment, or None for a
+/// malformed fragment, and the reassembled packet once the last fragment arrived.
+pub fn receive(client: SocketAddrV4, msg: &OscMessage) -> (Option<OscMessage>, Option<Vec<u8>>) {
+    let [OscType::Int(id), OscType::Int(index), OscType::Int(count), OscType::Blob(data)] =
+        msg.args.as_slice()
+    else {
+        return (None, None);
+    };
+    if !(1..=MAX_INCOMING_FRAGMENTS).contains(count) || !(0..*count).contains(index) {
+        return (None, None);
+    }
+    let mut incoming = INCOMING.lock().unwrap();
+    incoming.retain(|_, transfer| transfer.received_at.elapsed() < INCOMING_TIMEOUT);
+    let transfer = incoming.entry((client, *id)).or_insert_with(|| Incoming {
+        fragments: vec![None; *count as usize],
+        received_at: Instant::now(),
+        complete: false,
+    });
+    if transfer.fragments.len() != *count as usize {
+        return (None, None);
+    }
+    transfer.received_at = Instant::now();
+    let ack = Some(ack_message(*id, *index));
+    // Fragments repeated because an acknowledgment was lost are acknowledged again
+    // without delivering the packet twice
+    if transfer.complete {
+        return (ack, None);
+    }
+    transfer.fragments[*index as usize] = Some(data.clone());
+    if transfer.fragments.iter().any(Option::is_none) {
+        return (ack, None);
+    }
+    transfer.complete = true;
+    let packet = transfer
+        .fragments
+        .iter_mut()
+        .flat_map(|fragment| fragment.take().unwrap_or_default())
+        .collect();
+    (ack, Some(packet))
+}
@@ -0,0 +1,194 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::net::SocketAddrV4;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rosc::{OscMessage, OscPacket, OscType};
+
+use crate::clients;
+use crate::surfaces;
+
+// Changes kept for /arpad/undo-last
+const MAX_CHANGES: usize = 100;
+// Changes to one address from one sender closer together than this count as one
+// change, so an undo takes back a whole fader move
+const COALESCE_WINDOW: Duration = Duration::from_secs(1);
+
+/// A change made over OSC, with the message setting the value back
+struct Change {
+    namespace: String,
+    client: Option<SocketAddrV4>,
+    restore: OscMessage,
+    at: Instant,
+}
+
+static CHANGES: Mutex<VecDeque<Change>> = Mutex::new(VecDeque::new());
+
+// Set by `/arpad/undo-last` while its message is dispatched
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Whether a recorded change is being undone, which is not itself recorded
+    static RESTORING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The message setting a value back, given the answer to a query made before the
+/// change, the message making it, and whether an address belongs to the route that
+/// handled it. The answer addresses the changed value by its canonical GUID, which
+/// the change may have given in short or `{...}` form, so it is checked against the
+/// route rather than the address of the change. Only answers made of one message with
+/// a value can be sent back. Answers of routes that trigger something rather than hold
+/// a value, like the blank name `/marker/drop` answers with, don't reflect any state,
+/// and sending them would trigger the route again. An answer equal to the change means
+/// nothing changed.
+pub fn restore_message(
+    answer: OscPacket,
+    change: &OscMessage,
+    same_route: impl Fn(&str) -> bool,
+) -> Option<OscMessage> {
+    let OscPacket::Message(msg) = answer else {
+        return None;
+    };
+    let is_placeholder = msg
+        .args
+        .iter()
+        .all(|arg| matches!(arg, OscType::String(s) if s.is_empty()));
+    (same_route(&msg.addr) && !msg.args.is_empty() && !is_placeholder && msg.args != change.args)
+        .then_some(msg)
+}
+
+/// Records a change made by the sender of the current message
+pub fn record(restore: OscMessage) {
+    if RESTORING.with(Cell::get) {
+        return;
+    }
+    let namespace = surfaces::current_namespace();
+    let client = clients::current_client();
+    let mut changes = CHANGES.lock().unwrap();
+    if let Some(last) = changes.back_mut() {
+        // The first change of a run keeps the value from before the run
+        if last.namespace == namespace
+            && last.client == client
+            && last.restore.addr == restore.addr
+            && last.at.elapsed() < COALESCE_WINDOW
+        {
+            last.at = Instant::now();
+            return;
+        }
+    }
+    changes.push_back(Change {
+        namespace,
+        client,
+        restore,
+        at: Instant::now(),
+    });
+    if changes.len() > MAX_CHANGES {
+        changes.pop_front();
+    }
+}
+
+/// Takes the most recent change made through the surface currently being served,
+/// returning the message that sets its value back. Changes made in REAPER itself or
+/// through other surfaces are left alone.
+pub fn pop() -> Option<OscMessage> {
+    let namespace = surfaces::current_namespace();
+    let mut changes = CHANGES.lock().unwrap();
+    let index = changes.iter().rposition(|c| c.namespace == namespace)?;
+    changes.remove(index).map(|c| c.restore)
+}
+
+/// Asks for the most recent change of the current surface to be undone
+pub fn request() {
+    REQUESTED.store(true, Ordering::Relaxed);
+}
+
+pub fn take_request() -> bool {
+    REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+/// Marks a change as being undone until the guard is dropped
+pub fn restoring() -> RestoringGuard {
+    RestoringGuard {
+        prev: RESTORING.with(|r| r.replace(true)),
+    }
+}
+
+pub struct RestoringGuard {
+    prev: bool,
+}
+
+impl Drop for RestoringGuard {
+    fn drop(&mut self) {
+        RESTORING.with(|r| r.set(self.prev));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osc_routes::{MarkerDropRoute, TrackMuteRoute};
+    use crate::OscRoute;
+
+    fn message(addr: &str, args: Vec<OscType>) -> OscMessage {
+        OscMessage {
+            addr: addr.to_string(),
+            args,
+        }
+    }
+
+    fn routed_by<T: OscRoute>(addr: &str) -> bool {
+        let segments: Vec<&str> = addr.split('/').filter(|s| !s.is_empty()).collect();
+        T::matcher(&segments).is_some()
+    }
+
+    #[test]
+    fn marker_drop_is_not_undone() {
+        assert!(!MarkerDropRoute::UNDOABLE);
+        // What a `/marker/drop?` query answers, which would drop another marker if sent
+        let answer = OscPacket::Message(message(
+            "/marker/drop",
+            vec![OscType::String(String::new())],
+        ));
+        let change = message("/marker/drop", vec![OscType::String("Chorus".to_string())]);
+        assert_eq!(
+            restore_message(answer, &change, routed_by::<MarkerDropRoute>),
+            None
+        );
+        assert!(pop().is_none());
+    }
+
+    #[test]
+    fn values_are_restored() {
+        let answer = OscPacket::Message(message("/track/abc/mute", vec![OscType::Bool(false)]));
+        let change = message("/track/abc/mute", vec![OscType::Bool(true)]);
+        let restore = restore_message(answer, &change, routed_by::<TrackMuteRoute>).unwrap();
+        assert_eq!(restore.args, vec![OscType::Bool(false)]);
+    }
+
+    #[test]
+    fn changes_by_short_guid_are_restored() {
+        let guid = "0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0";
+        let answer = OscPacket::Message(message(
+            &format!("/track/{}/mute", guid),
+            vec![OscType::Bool(false)],
+        ));
+        for addr in [
+            "/track/0f1e/mute",
+            "/track/{0F1E2D3C-4B5A-6978-8796-A5B4C3D2E1F0}/mute",
+        ] {
+            let change = message(addr, vec![OscType::Bool(true)]);
+            let restore =
+                restore_message(answer.clone(), &change, routed_by::<TrackMuteRoute>).unwrap();
+            assert_eq!(restore.addr, format!("/track/{}/mute", guid));
+        }
+    }
+
+    #[test]
+    fn unchanged_values_are_not_recorded() {
+        let answer = OscPacket::Message(message("/track/abc/mute", vec![OscType::Bool(true)]));
+        let change = message("/track/abc/mute", vec![OscType::Bool(true)]);
+        assert!(restore_message(answer, &change, routed_by::<TrackMuteRoute>).is_none());
+    }
+}
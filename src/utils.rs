@@ -1,5 +1,14 @@
-use reaper_medium::{MediaTrack, ProjectContext::CurrentProject, Reaper, TrackAttributeKey};
+use std::ffi::{CStr, CString};
 
+use reaper_medium::{
+    CommandId, Db, MediaTrack, ProjectContext::CurrentProject, Reaper, ReaperVolumeValue,
+    SectionId, TrackAttributeKey, TrackFxLocation, TrackSendCategory, VolumeSliderValue,
+};
+
+use rosc::{OscBundle, OscPacket, OscTime};
+use serde::Deserialize;
+
+use crate::registry;
 use crate::RouteError;
 
 pub(crate) fn guid_to_string(guid: reaper_low::raw::GUID) -> String {
@@ -30,16 +39,278 @@ pub(crate) fn get_track_guid(reaper: &Reaper, track: MediaTrack) -> String {
     }
 }
 
+// Shortest prefix accepted as a short-form GUID
+const MIN_GUID_PREFIX_LEN: usize = 4;
+
+/// Brings any accepted GUID form to the canonical lowercase dashed form: REAPER's
+/// native `{...}` form loses its braces and is lowercased. Short prefixes stay short.
+pub(crate) fn normalize_guid(guid: &str) -> String {
+    guid.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .to_lowercase()
+}
+
+/// Finds a track by GUID, given as the dashed hex string, REAPER's `{...}` form, or a
+/// prefix of the dashed form that matches exactly one track
 pub(crate) fn get_track_by_guid(reaper: &Reaper, guid: &str) -> Result<MediaTrack, RouteError> {
-    let master_track = reaper.get_master_track(CurrentProject);
-    if get_track_guid(reaper, master_track) == guid {
-        return Ok(master_track);
-    }
-    for i in 0..reaper.count_tracks(CurrentProject) {
-        let track = reaper.get_track(CurrentProject, i).unwrap();
-        if get_track_guid(reaper, track) == guid {
-            return Ok(track);
+    let wanted = normalize_guid(guid);
+    registry::find(reaper, |tracks| {
+        if let Some(track) = tracks.get(&wanted) {
+            return Ok(*track);
+        }
+        if wanted.len() < MIN_GUID_PREFIX_LEN {
+            return Err(RouteError::GuidNotFound(guid.to_string()));
+        }
+        let mut matches = tracks
+            .iter()
+            .filter(|(track_guid, _)| track_guid.starts_with(&wanted));
+        match (matches.next(), matches.next()) {
+            (Some((_, track)), None) => Ok(*track),
+            (None, _) => Err(RouteError::GuidNotFound(guid.to_string())),
+            _ => Err(RouteError::AmbiguousGuid(guid.to_string())),
+        }
+    })
+}
+
+/// Finds a track by GUID, falling back to the first track with that name
+pub(crate) fn find_track_by_guid_or_name(
+    reaper: &Reaper,
+    id: &str,
+) -> Result<MediaTrack, RouteError> {
+    if let Ok(track) = get_track_by_guid(reaper, id) {
+        return Ok(track);
+    }
+    (0..reaper.count_tracks(CurrentProject))
+        .filter_map(|i| reaper.get_track(CurrentProject, i))
+        .find(|&track| unsafe {
+            reaper
+                .get_set_media_track_info_get_name(track, |name| name.to_str() == id)
+                .unwrap_or(false)
+        })
+        .ok_or(RouteError::ValueNotFound(format!("Track {}", id)))
+}
+
+/// Finds the first parameter on the track's FX chain whose name contains `name`
+/// (case-insensitive), returning the FX location and parameter index.
+pub(crate) fn find_fx_param_by_name(
+    reaper: &Reaper,
+    track: MediaTrack,
+    name: &str,
+) -> Option<(TrackFxLocation, u32)> {
+    let name = name.to_lowercase();
+    unsafe {
+        for fx_index in 0..reaper.track_fx_get_count(track) {
+            let fx = TrackFxLocation::NormalFxChain(fx_index);
+            for param_index in 0..reaper.track_fx_get_num_params(track, fx) {
+                let Ok(param_name) = reaper.track_fx_get_param_name(track, fx, param_index, 256)
+                else {
+                    continue;
+                };
+                if param_name.to_str().to_lowercase().contains(&name) {
+                    return Some((fx, param_index));
+                }
+            }
         }
     }
-    Err(RouteError::GuidNotFound(guid.to_string()))
+    None
+}
+
+/// Wraps several packets in a bundle to be executed immediately by the receiver
+pub(crate) fn immediate_bundle(content: Vec<OscPacket>) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: OscTime {
+            seconds: 0,
+            fractional: 1,
+        },
+        content,
+    })
+}
+
+/// Returns a pointer to one of REAPER's preference variables (see `get_config_var` in
+/// the REAPER API), checking that the variable has the expected size.
+pub(crate) fn get_config_var<T>(reaper: &Reaper, name: &CStr) -> Option<*mut T> {
+    let mut size = 0;
+    let ptr = unsafe { reaper.low().get_config_var(name.as_ptr(), &mut size) };
+    if ptr.is_null() || size as usize != std::mem::size_of::<T>() {
+        return None;
+    }
+    Some(ptr as *mut T)
+}
+
+/// Returns the state of a toggle action in the main section
+pub(crate) fn get_toggle_action_state(reaper: &Reaper, command: u32) -> bool {
+    reaper
+        .get_toggle_command_state_ex(SectionId::new(0), CommandId::new(command))
+        .unwrap_or(false)
+}
+
+/// Runs a toggle action in the main section if it isn't already in the requested state
+pub(crate) fn set_toggle_action_state(reaper: &Reaper, command: u32, on: bool) {
+    if get_toggle_action_state(reaper, command) != on {
+        reaper.main_on_command_ex(CommandId::new(command), 0, CurrentProject);
+    }
+}
+
+/// Runs an action of the main section, as picking it in REAPER's action list would
+pub(crate) fn run_action(reaper: &Reaper, command: u32) {
+    reaper.main_on_command_ex(CommandId::new(command), 0, CurrentProject);
+}
+
+/// Looks up the command id of an extension or script action by the name shown in the
+/// action list's "Command ID" column, e.g. "_SWS_ABOUT". The leading underscore may be
+/// left out, as it is awkward to type on some surfaces.
+pub(crate) fn named_command_id(reaper: &Reaper, name: &str) -> Option<u32> {
+    let name = CString::new(format!("_{}", name.trim_start_matches('_'))).ok()?;
+    let command = unsafe { reaper.low().NamedCommandLookup(name.as_ptr()) };
+    (command > 0).then_some(command as u32)
+}
+
+/// Converts a fader position normalized to 0 to 1.0 (following REAPER's volume slider
+/// taper, with 1.0 at +12dB) to a volume
+pub(crate) fn normalized_to_volume(reaper: &Reaper, normalized: f64) -> ReaperVolumeValue {
+    let slider_value = VolumeSliderValue::new(normalized * VolumeSliderValue::TWELVE_DB.get());
+    reaper.slider2db(slider_value).to_linear_volume_value()
+}
+
+/// Converts a volume to a fader position normalized to 0 to 1.0
+pub(crate) fn volume_to_normalized(reaper: &Reaper, volume: ReaperVolumeValue) -> f64 {
+    let vol_db = volume.to_db_ex(Db::MINUS_150_DB);
+    reaper.db2slider(vol_db).get() / VolumeSliderValue::TWELVE_DB.get()
+}
+
+/// How volumes are expressed on the wire
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeMode {
+    /// REAPER's fader taper, normalized to 0 to 1.0 with 1.0 at +12dB
+    #[default]
+    Slider,
+    /// Linear gain, 1.0 at 0dB
+    Linear,
+    /// Decibels
+    Db,
+}
+
+impl VolumeMode {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "slider" => Some(VolumeMode::Slider),
+            "linear" => Some(VolumeMode::Linear),
+            "db" => Some(VolumeMode::Db),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VolumeMode::Slider => "slider",
+            VolumeMode::Linear => "linear",
+            VolumeMode::Db => "db",
+        }
+    }
+}
+
+/// Converts a volume received in the given mode to a volume
+pub(crate) fn mode_value_to_volume(
+    reaper: &Reaper,
+    mode: VolumeMode,
+    value: f64,
+) -> Option<ReaperVolumeValue> {
+    match mode {
+        VolumeMode::Slider => Some(normalized_to_volume(reaper, value)),
+        VolumeMode::Linear => ReaperVolumeValue::new(value).ok(),
+        VolumeMode::Db => Db::new(value).ok().map(|db| db.to_linear_volume_value()),
+    }
+}
+
+/// Converts a volume to its value in the given mode
+pub(crate) fn volume_to_mode_value(
+    reaper: &Reaper,
+    mode: VolumeMode,
+    volume: ReaperVolumeValue,
+) -> f64 {
+    match mode {
+        VolumeMode::Slider => volume_to_normalized(reaper, volume),
+        VolumeMode::Linear => volume.get(),
+        VolumeMode::Db => volume.to_db_ex(Db::MINUS_150_DB).get(),
+    }
+}
+
+/// Sets a send, receive or hardware output attribute (see SetTrackSendInfo_Value)
+pub(crate) fn set_track_send_info_value(
+    reaper: &Reaper,
+    track: MediaTrack,
+    category: TrackSendCategory,
+    index: u32,
+    key: &CStr,
+    value: f64,
+) -> Result<(), RouteError> {
+    let ok = unsafe {
+        reaper.low().SetTrackSendInfo_Value(
+            track.as_ptr(),
+            category.to_raw(),
+            index as i32,
+            key.as_ptr(),
+            value,
+        )
+    };
+    if !ok {
+        return Err(RouteError::ValueNotFound(format!(
+            "Send {} attribute {}",
+            index,
+            key.to_string_lossy()
+        )));
+    }
+    Ok(())
+}
+
+/// Reads a named FX config parameter (see TrackFX_GetNamedConfigParm)
+pub(crate) fn get_fx_named_config(
+    reaper: &Reaper,
+    track: MediaTrack,
+    fx_index: u32,
+    name: &str,
+) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    let mut buf = [0 as std::os::raw::c_char; 256];
+    let ok = unsafe {
+        reaper.low().TrackFX_GetNamedConfigParm(
+            track.as_ptr(),
+            fx_index as i32,
+            name.as_ptr(),
+            buf.as_mut_ptr(),
+            buf.len() as i32,
+        )
+    };
+    ok.then(|| {
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+/// Writes a named FX config parameter (see TrackFX_SetNamedConfigParm)
+pub(crate) fn set_fx_named_config(
+    reaper: &Reaper,
+    track: MediaTrack,
+    fx_index: u32,
+    name: &str,
+    value: &str,
+) -> Result<(), RouteError> {
+    let not_found = || RouteError::ValueNotFound(format!("FX {} config {}", fx_index, name));
+    let c_name = CString::new(name).map_err(|_| not_found())?;
+    let c_value = CString::new(value).map_err(|_| not_found())?;
+    let ok = unsafe {
+        reaper.low().TrackFX_SetNamedConfigParm(
+            track.as_ptr(),
+            fx_index as i32,
+            c_name.as_ptr(),
+            c_value.as_ptr(),
+        )
+    };
+    if !ok {
+        return Err(not_found());
+    }
+    Ok(())
 }
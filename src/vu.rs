@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use reaper_medium::ProjectContext::CurrentProject;
+use reaper_medium::{MediaTrack, Reaper, TrackAttributeKey};
+use rosc::OscPacket;
+use serde::Deserialize;
+
+use crate::feedback::{Feedback, Lane};
+use crate::osc_routes::{TrackVuArgs, TrackVuRoute, VuChannel};
+use crate::polling::{PollError, PollSource};
+use crate::utils::{get_track_guid, immediate_bundle};
+use crate::OscRoute;
+
+/// The `[vu]` section of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct VuConfig {
+    /// How many times per second meters are sent; 0 disables them
+    pub rate: f64,
+    /// How fast a meter falls after a peak, in dB per second; 0 follows the signal
+    /// without decay
+    pub decay: f64,
+    /// How long the peak-hold value stays before following the meter, in seconds
+    pub peak_hold: f64,
+    /// Where in the track the meters are taken
+    pub mode: MeterMode,
+}
+
+/// Where streamed meters are taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MeterMode {
+    /// After the fader, as REAPER's meters show
+    #[default]
+    PostFader,
+    /// Before the fader, approximated by taking the track volume back out of the
+    /// post-fader peaks; muted tracks read silent
+    PreFader,
+}
+
+impl MeterMode {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "post-fader" => Some(MeterMode::PostFader),
+            "pre-fader" => Some(MeterMode::PreFader),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeterMode::PostFader => "post-fader",
+            MeterMode::PreFader => "pre-fader",
+        }
+    }
+}
+
+impl Default for VuConfig {
+    fn default() -> Self {
+        Self {
+            rate: 20.0,
+            decay: 24.0,
+            peak_hold: 1.5,
+            mode: MeterMode::default(),
+        }
+    }
+}
+
+static CONFIG: OnceLock<VuConfig> = OnceLock::new();
+
+pub fn init(config: VuConfig) {
+    let _ = CONFIG.set(config);
+}
+
+fn config() -> VuConfig {
+    CONFIG.get().cloned().unwrap_or_default()
+}
+
+// Meter mode chosen over OSC, overriding the config file
+static MODE: Mutex<Option<MeterMode>> = Mutex::new(None);
+
+pub fn meter_mode() -> MeterMode {
+    MODE.lock().unwrap().unwrap_or_else(|| config().mode)
+}
+
+pub fn set_meter_mode(mode: MeterMode) {
+    *MODE.lock().unwrap() = Some(mode);
+}
+
+// Volumes below this are treated as the fader being down, leaving nothing to undo
+const MIN_FADER_GAIN: f64 = 1e-6;
+
+/// Meter level and held peak of one channel, both linear where 1.0 is 0dB
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VuLevel {
+    pub level: f64,
+    pub hold: f64,
+}
+
+/// Latest meter of a track
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VuReading {
+    pub left: VuLevel,
+    pub right: VuLevel,
+}
+
+impl VuReading {
+    pub fn channel(&self, channel: VuChannel) -> VuLevel {
+        match channel {
+            VuChannel::Left => self.left,
+            VuChannel::Right => self.right,
+            VuChannel::Both => VuLevel {
+                level: self.left.level.max(self.right.level),
+                hold: self.left.hold.max(self.right.hold),
+            },
+        }
+    }
+}
+
+// Latest readings by track GUID, for answering queries
+static LATEST: LazyLock<Mutex<HashMap<String, VuReading>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the latest meter reading of a track, or silence before its first poll
+pub fn latest(track_guid: &str) -> VuReading {
+    LATEST
+        .lock()
+        .unwrap()
+        .get(track_guid)
+        .copied()
+        .unwrap_or_default()
+}
+
+// Set by /meters/reset until the poll source has dropped its held peaks
+static RESET_HOLDS: AtomicBool = AtomicBool::new(false);
+
+/// Drops every held peak, so the holds start again from the current levels
+pub fn reset_peak_holds() {
+    for reading in LATEST.lock().unwrap().values_mut() {
+        reading.left.hold = reading.left.level;
+        reading.right.hold = reading.right.level;
+    }
+    RESET_HOLDS.store(true, Ordering::Relaxed);
+}
+
+/// Applies decay and peak hold to the raw peaks of one channel
+#[derive(Default)]
+struct Ballistics {
+    level: f64,
+    hold: f64,
+    held_at: Option<Instant>,
+}
+
+impl Ballistics {
+    fn update(&mut self, peak: f64, elapsed: Duration, config: &VuConfig) -> VuLevel {
+        let decayed = self.level * 10f64.powf(-config.decay * elapsed.as_secs_f64() / 20.0);
+        self.level = if config.decay > 0.0 {
+            peak.max(decayed)
+        } else {
+            peak
+        };
+        let now = Instant::now();
+        let expired = self
+            .held_at
+            .is_none_or(|at| now.duration_since(at).as_secs_f64() >= config.peak_hold);
+        if self.level >= self.hold || expired {
+            self.hold = self.level;
+            self.held_at = Some(now);
+        }
+        VuLevel {
+            level: self.level,
+            hold: self.hold,
+        }
+    }
+
+    fn reset_hold(&mut self) {
+        self.hold = 0.0;
+        self.held_at = None;
+    }
+}
+
+/// Streams per-track meters for surfaces drawing VU meters. Only tracks whose
+/// reading changed since the last send are sent, so silent projects stay quiet.
+pub struct VuPollSource {
+    reaper: Reaper,
+    config: VuConfig,
+    interval: Option<Duration>,
+    last_sent: Instant,
+    ballistics: HashMap<String, [Ballistics; 2]>,
+}
+
+impl VuPollSource {
+    pub fn new(reaper: Reaper) -> Self {
+        let config = config();
+        Self {
+            reaper,
+            interval: (config.rate > 0.0).then(|| Duration::from_secs_f64(1.0 / config.rate)),
+            config,
+            last_sent: Instant::now(),
+            ballistics: HashMap::new(),
+        }
+    }
+
+    // Pre-fader readings only divide out the volume. The pan gain depends on the track's
+    // pan mode and on the track and project pan laws, and is left in.
+    fn read(&mut self, track: MediaTrack, guid: &str, elapsed: Duration) -> VuReading {
+        let fader_gain = match meter_mode() {
+            MeterMode::PostFader => 1.0,
+            MeterMode::PreFader => unsafe {
+                self.reaper
+                    .get_media_track_info_value(track, TrackAttributeKey::Vol)
+            },
+        };
+        let [left, right] = [0, 1].map(|ch| {
+            let peak = unsafe { self.reaper.low().Track_GetPeakInfo(track.as_ptr(), ch) };
+            if fader_gain < MIN_FADER_GAIN {
+                0.0
+            } else {
+                peak / fader_gain
+            }
+        });
+        let ballistics = self.ballistics.entry(guid.to_string()).or_default();
+        VuReading {
+            left: ballistics[0].update(left, elapsed, &self.config),
+            right: ballistics[1].update(right, elapsed, &self.config),
+        }
+    }
+}
+
+impl PollSource for VuPollSource {
+    fn poll_and_send(&mut self, osc_sender: &Sender<Feedback>) -> Result<(), PollError> {
+        let Some(interval) = self.interval else {
+            return Ok(());
+        };
+        let elapsed = self.last_sent.elapsed();
+        if elapsed < interval {
+            return Ok(());
+        }
+        self.last_sent = Instant::now();
+        if RESET_HOLDS.swap(false, Ordering::Relaxed) {
+            self.ballistics
+                .values_mut()
+                .flatten()
+                .for_each(Ballistics::reset_hold);
+        }
+
+        let master = self.reaper.get_master_track(CurrentProject);
+        let tracks: Vec<MediaTrack> = std::iter::once(master)
+            .chain(
+                (0..self.reaper.count_tracks(CurrentProject))
+                    .filter_map(|i| self.reaper.get_track(CurrentProject, i)),
+            )
+            .collect();
+        for track in tracks {
+            let guid = get_track_guid(&self.reaper, track);
+            let reading = self.read(track, &guid, elapsed);
+            if LATEST.lock().unwrap().insert(guid, reading) == Some(reading) {
+                continue;
+            }
+            let content = [VuChannel::Both, VuChannel::Left, VuChannel::Right]
+                .into_iter()
+                .map(|channel| {
+                    OscPacket::Message(TrackVuRoute::build_message(
+                        TrackVuArgs {
+                            track,
+                            channel,
+                            level: reading.channel(channel),
+                        },
+                        &self.reaper,
+                    ))
+                })
+                .collect();
+            osc_sender
+                .send(immediate_bundle(content).into())
+                .map_err(PollError::Send)?;
+        }
+        Ok(())
+    }
+
+    fn lane(&self) -> Lane {
+        Lane::Meters
+    }
+}
@@ -0,0 +1,277 @@
+use rosc::{decoder, encoder, OscMessage, OscPacket, OscType};
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::process::exit;
+use std::time::Duration;
+
+// Mirror the addresses arpad binds to and sends feedback to by default
+const DEFAULT_HOST: &str = "127.0.0.1:9090";
+const DEFAULT_LISTEN: &str = "0.0.0.0:9091";
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+const USAGE: &str = "\
+Usage: arpad-cli [options] <command>
+
+Commands:
+  send <address> [args...]   send a message; args are inferred as bool, int, float or string
+                             (force a type with an i:, f:, s: or b: prefix)
+  query <address>            send <address>/? and print the replies
+  subscribe [prefix]         print all feedback, optionally only addresses starting with prefix
+
+Options:
+  --host <ip:port>           address arpad listens on (default 127.0.0.1:9090)
+  --listen <ip:port>         address arpad sends feedback to (default 0.0.0.0:9091)
+  --timeout <ms>             how long query waits for replies (default 1000)";
+
+struct Options {
+    host: SocketAddr,
+    listen: SocketAddr,
+    timeout: Duration,
+    command: Vec<String>,
+}
+
+fn parse_options(args: Vec<String>) -> Result<Options, String> {
+    let mut host = DEFAULT_HOST.to_string();
+    let mut listen = DEFAULT_LISTEN.to_string();
+    let mut timeout = DEFAULT_TIMEOUT_MS.to_string();
+    let mut command = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--host" => host = args.next().ok_or("--host requires a value")?,
+            "--listen" => listen = args.next().ok_or("--listen requires a value")?,
+            "--timeout" => timeout = args.next().ok_or("--timeout requires a value")?,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => {
+                command.push(arg);
+                command.extend(args.by_ref());
+            }
+        }
+    }
+
+    Ok(Options {
+        host: host
+            .parse()
+            .map_err(|_| format!("Invalid host address: {}", host))?,
+        listen: listen
+            .parse()
+            .map_err(|_| format!("Invalid listen address: {}", listen))?,
+        timeout: Duration::from_millis(
+            timeout
+                .parse()
+                .map_err(|_| format!("Invalid timeout: {}", timeout))?,
+        ),
+        command,
+    })
+}
+
+fn parse_arg(arg: &str) -> Result<OscType, String> {
+    if let Some((prefix, value)) = arg.split_once(':') {
+        let explicit = match prefix {
+            "i" => Some(value.parse().map(OscType::Int).map_err(|_| ())),
+            "f" => Some(value.parse().map(OscType::Float).map_err(|_| ())),
+            "b" => Some(value.parse().map(OscType::Bool).map_err(|_| ())),
+            "s" => Some(Ok(OscType::String(value.to_string()))),
+            _ => None,
+        };
+        if let Some(parsed) = explicit {
+            return parsed.map_err(|_| format!("Invalid argument: {}", arg));
+        }
+    }
+    if let Ok(b) = arg.parse::<bool>() {
+        return Ok(OscType::Bool(b));
+    }
+    if let Ok(i) = arg.parse::<i32>() {
+        return Ok(OscType::Int(i));
+    }
+    if let Ok(f) = arg.parse::<f32>() {
+        return Ok(OscType::Float(f));
+    }
+    Ok(OscType::String(arg.to_string()))
+}
+
+fn format_arg(arg: &OscType) -> String {
+    match arg {
+        OscType::Int(i) => format!("{} (int)", i),
+        OscType::Float(f) => format!("{:.4} (float)", f),
+        OscType::Double(d) => format!("{:.4} (double)", d),
+        OscType::Bool(b) => format!("{} (bool)", b),
+        OscType::String(s) => format!("{:?} (string)", s),
+        OscType::Blob(b) => format!("<{} bytes> (blob)", b.len()),
+        other => format!("{:?}", other),
+    }
+}
+
+fn print_packet(packet: &OscPacket, prefix: Option<&str>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match packet {
+        OscPacket::Message(msg) => {
+            if prefix.is_some_and(|p| !msg.addr.starts_with(p)) {
+                return;
+            }
+            let args: Vec<String> = msg.args.iter().map(format_arg).collect();
+            println!("{}{}  {}", indent, msg.addr, args.join(", "));
+        }
+        OscPacket::Bundle(bundle) => {
+            println!("{}bundle ({} elements)", indent, bundle.content.len());
+            for p in &bundle.content {
+                print_packet(p, prefix, depth + 1);
+            }
+        }
+    }
+}
+
+fn send(sock: &UdpSocket, host: SocketAddr, msg: OscMessage) -> Result<(), String> {
+    let buf = encoder::encode(&OscPacket::Message(msg)).map_err(|e| format!("{:?}", e))?;
+    sock.send_to(&buf, host).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Fragments of packets arpad split because they don't fit one datagram, by
+/// transfer id
+#[derive(Default)]
+struct Fragments(HashMap<i32, Vec<Option<Vec<u8>>>>);
+
+impl Fragments {
+    /// Acknowledges a `/arpad/blob` fragment and returns the packet once complete
+    fn receive(
+        &mut self,
+        sock: &UdpSocket,
+        from: SocketAddr,
+        msg: &OscMessage,
+    ) -> Result<Option<OscPacket>, String> {
+        let [OscType::Int(id), OscType::Int(index), OscType::Int(count), OscType::Blob(data)] =
+            msg.args.as_slice()
+        else {
+            return Ok(None);
+        };
+        send(
+            sock,
+            from,
+            OscMessage {
+                addr: "/arpad/blob/ack".to_string(),
+                args: vec![OscType::Int(*id), OscType::Int(*index)],
+            },
+        )?;
+        let fragments = self
+            .0
+            .entry(*id)
+            .or_insert_with(|| vec![None; (*count).max(0) as usize]);
+        if let Some(fragment) = fragments.get_mut(*index as usize) {
+            *fragment = Some(data.clone());
+        }
+        if fragments.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+        let buf: Vec<u8> = self
+            .0
+            .remove(id)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flatten()
+            .collect();
+        Ok(decoder::decode_udp(&buf).ok().map(|(_, packet)| packet))
+    }
+}
+
+fn receive(
+    sock: &UdpSocket,
+    fragments: &mut Fragments,
+    prefix: Option<&str>,
+) -> Result<bool, String> {
+    let mut buf = [0u8; decoder::MTU];
+    match sock.recv_from(&mut buf) {
+        Ok((size, from)) => {
+            let packet = match decoder::decode_udp(&buf[..size]) {
+                Ok((_, OscPacket::Message(msg))) if msg.addr == "/arpad/blob" => {
+                    fragments.receive(sock, from, &msg)?
+                }
+                Ok((_, packet)) => Some(packet),
+                Err(_) => None,
+            };
+            if let Some(packet) = packet {
+                print_packet(&packet, prefix, 0);
+            }
+            Ok(true)
+        }
+        Err(e)
+            if e.kind() == std::io::ErrorKind::WouldBlock
+                || e.kind() == std::io::ErrorKind::TimedOut =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn run(opts: Options) -> Result<(), String> {
+    let (command, rest) = opts.command.split_first().ok_or(USAGE)?;
+    let sock = UdpSocket::bind(opts.listen)
+        .map_err(|e| format!("Failed to bind {}: {}", opts.listen, e))?;
+    let mut fragments = Fragments::default();
+
+    match command.as_str() {
+        "send" => {
+            let (addr, args) = rest.split_first().ok_or("send requires an address")?;
+            let args = args
+                .iter()
+                .map(|a| parse_arg(a))
+                .collect::<Result<Vec<_>, _>>()?;
+            send(
+                &sock,
+                opts.host,
+                OscMessage {
+                    addr: addr.to_string(),
+                    args,
+                },
+            )
+        }
+        "query" => {
+            let addr = rest.first().ok_or("query requires an address")?;
+            send(
+                &sock,
+                opts.host,
+                OscMessage {
+                    addr: format!("{}/?", addr.trim_end_matches('/')),
+                    args: vec![],
+                },
+            )?;
+            sock.set_read_timeout(Some(opts.timeout))
+                .map_err(|e| e.to_string())?;
+            let mut replied = false;
+            while receive(&sock, &mut fragments, None)? {
+                replied = true;
+            }
+            if !replied {
+                return Err(format!(
+                    "No reply from {} within {:?}",
+                    opts.host, opts.timeout
+                ));
+            }
+            Ok(())
+        }
+        "subscribe" => {
+            let prefix = rest.first().map(|p| p.as_str());
+            loop {
+                receive(&sock, &mut fragments, prefix)?;
+            }
+        }
+        _ => Err(format!("Unknown command: {}\n\n{}", command, USAGE)),
+    }
+}
+
+fn main() {
+    let opts = match parse_options(std::env::args().skip(1).collect()) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(2);
+        }
+    };
+    if let Err(e) = run(opts) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
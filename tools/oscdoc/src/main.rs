@@ -22,7 +22,9 @@ struct OscArg {
 
 fn main() {
     let src = fs::read_to_string("src/osc_routes.rs").expect("No src/lib.rs found");
-    let re = Regex::new(r"(?s)/// ?@osc-doc\n(.*?)(?:fn (\w+)[^\n]*\{)").unwrap();
+    // A doc block is the run of `///` lines after `@osc-doc`, indented or not, so docs
+    // inside define_osc_route! invocations are found as well
+    let re = Regex::new(r"(?m)^[ \t]*/// ?@osc-doc\n((?:[ \t]*///.*\n)*)").unwrap();
 
     let mut docs = Vec::new();
 